@@ -5,7 +5,7 @@ use crate::auth::OAuth;
 use serde::Serialize;
 
 /// Update specified anime in animelist
-#[derive(Clone, Debug, Serialize)]
+#[derive(Clone, Debug, Default, Serialize)]
 pub struct UpdateUserAnimeListStatusQuery {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub status: Option<UserWatchStatus>,
@@ -22,12 +22,16 @@ pub struct UpdateUserAnimeListStatusQuery {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub rewatch_value: Option<u8>,
     #[serde(skip_serializing_if = "Option::is_none")]
+    pub start_date: Option<DateWrapper>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub finish_date: Option<DateWrapper>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub tags: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub comments: Option<String>,
 }
 
-pub fn update_anime_list_status(
+pub async fn update_anime_list_status(
     anime_id: u64,
     update: &UpdateUserAnimeListStatusQuery,
     auth: &OAuth,
@@ -36,15 +40,17 @@ pub fn update_anime_list_status(
         &format!("{}/anime/{}/my_list_status", API_URL, anime_id,),
         auth,
         update,
-    )?;
+    )
+    .await?;
     handle_response(&response)
 }
 
-pub fn delete_anime_from_list(anime_id: u64, auth: &OAuth) -> Result<(), Error> {
+pub async fn delete_anime_from_list(anime_id: u64, auth: &OAuth) -> Result<(), Error> {
     let response = delete(
         &format!("{}/anime/{}/my_list_status", API_URL, anime_id),
         auth,
-    )?;
+    )
+    .await?;
     if response.status.is_success() {
         Ok(())
     } else {
@@ -65,7 +71,7 @@ pub struct GetUserAnimeListQuery {
     pub nsfw: bool,
 }
 
-pub fn get_user_anime_list<U: ToString>(
+pub async fn get_user_anime_list<U: ToString>(
     user: U,
     query: &GetUserAnimeListQuery,
     auth: &OAuth,
@@ -78,7 +84,8 @@ pub fn get_user_anime_list<U: ToString>(
             serde_urlencoded::to_string(query)?
         ),
         auth,
-    )?;
+    )
+    .await?;
     handle_response(&response)
 }
 
@@ -87,15 +94,28 @@ mod tests {
     use super::*;
     use crate::api::anime::tests::*;
 
-    #[test]
-    fn test_delete_anime_from_list() {
+    #[tokio::test]
+    async fn test_delete_anime_from_list() {
         let auth = crate::auth::tests::get_auth();
-        let anime = get_anime("God of High School", &auth).unwrap();
-        delete_anime_from_list(anime.id, &auth).unwrap();
+        let anime = get_anime("God of High School", &auth).await.unwrap();
+        delete_anime_from_list(anime.id, &auth).await.unwrap();
     }
 
     #[test]
-    fn test_update_anime_list() {
+    fn test_update_query_dates() {
+        let query = UpdateUserAnimeListStatusQuery {
+            start_date: Some(DateWrapper::year_month(2021, 4).unwrap()),
+            finish_date: Some(DateWrapper::ymd(2021, 6, 30).unwrap()),
+            ..Default::default()
+        };
+        assert_eq!(
+            serde_urlencoded::to_string(&query).unwrap(),
+            "start_date=2021-04&finish_date=2021-06-30"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_update_anime_list() {
         let auth = crate::auth::tests::get_auth();
         let query = UpdateUserAnimeListStatusQuery {
             status: Some(UserWatchStatus::Watching),
@@ -105,19 +125,23 @@ mod tests {
             priority: None,
             num_times_rewatched: None,
             rewatch_value: None,
+            start_date: Some(DateWrapper::year_month(2020, 7).unwrap()),
+            finish_date: None,
             tags: None,
             comments: None,
         };
 
-        let anime = get_anime("God of High School", &auth).unwrap();
+        let anime = get_anime("God of High School", &auth).await.unwrap();
 
-        let result = update_anime_list_status(anime.id, &query, &auth).unwrap();
+        let result = update_anime_list_status(anime.id, &query, &auth)
+            .await
+            .unwrap();
         println!("{:#?}", result);
         assert_eq!(result.num_episodes_watched, 5);
     }
 
-    #[test]
-    fn test_get_user_anime_list() {
+    #[tokio::test]
+    async fn test_get_user_anime_list() {
         let auth = crate::auth::tests::get_auth();
         let query = GetUserAnimeListQuery {
             fields: Some(ALL_ANIME_AND_MANGA_FIELDS.to_string()),
@@ -127,10 +151,10 @@ mod tests {
             offset: 0,
             nsfw: true,
         };
-        let result = get_user_anime_list("@me", &query, &auth).unwrap();
+        let result = get_user_anime_list("@me", &query, &auth).await.unwrap();
 
         print!("{:#?}", result);
 
-        assert!(result.data.len() > 0);
+        assert!(!result.data.is_empty());
     }
 }
@@ -4,7 +4,7 @@ use super::{delete, get, handle_response, patch, API_URL};
 use crate::auth::OAuth;
 use serde::Serialize;
 
-#[derive(Clone, Debug, Serialize)]
+#[derive(Clone, Debug, Default, Serialize)]
 pub struct UpdateUserMangaStatus {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub status: Option<UserReadStatus>,
@@ -23,12 +23,16 @@ pub struct UpdateUserMangaStatus {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub reread_value: Option<u8>,
     #[serde(skip_serializing_if = "Option::is_none")]
+    pub start_date: Option<DateWrapper>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub finish_date: Option<DateWrapper>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub tags: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub comments: Option<String>,
 }
 
-pub fn update_manga_list_status(
+pub async fn update_manga_list_status(
     manga_id: u64,
     update: &UpdateUserMangaStatus,
     auth: &OAuth,
@@ -37,15 +41,17 @@ pub fn update_manga_list_status(
         &format!("{}/manga/{}/my_list_status", API_URL, manga_id),
         auth,
         update,
-    )?;
+    )
+    .await?;
     handle_response(&response)
 }
 
-pub fn delete_manga_from_list(manga_id: u64, auth: &OAuth) -> Result<(), Error> {
+pub async fn delete_manga_from_list(manga_id: u64, auth: &OAuth) -> Result<(), Error> {
     let response = delete(
         &format!("{}/manga/{}/my_list_status", API_URL, manga_id),
         auth,
-    )?;
+    )
+    .await?;
     if response.status.is_success() {
         Ok(())
     } else {
@@ -66,7 +72,7 @@ pub struct GetUserMangaListQuery {
     pub nsfw: bool,
 }
 
-pub fn get_user_manga_list<U: ToString>(
+pub async fn get_user_manga_list<U: ToString>(
     user: U,
     query: &GetUserMangaListQuery,
     auth: &OAuth,
@@ -79,7 +85,8 @@ pub fn get_user_manga_list<U: ToString>(
             serde_urlencoded::to_string(query)?
         ),
         auth,
-    )?;
+    )
+    .await?;
     handle_response(&response)
 }
 
@@ -88,15 +95,15 @@ mod test {
     use super::*;
     use crate::api::manga::tests::*;
 
-    #[test]
-    fn test_delete_manga_from_list() {
+    #[tokio::test]
+    async fn test_delete_manga_from_list() {
         let auth = crate::auth::tests::get_auth();
-        let manga = get_manga("Grand Blue", &auth).unwrap();
-        delete_manga_from_list(manga.id, &auth).unwrap();
+        let manga = get_manga("Grand Blue", &auth).await.unwrap();
+        delete_manga_from_list(manga.id, &auth).await.unwrap();
     }
 
-    #[test]
-    fn test_update_manga_list() {
+    #[tokio::test]
+    async fn test_update_manga_list() {
         let auth = crate::auth::tests::get_auth();
         let query = UpdateUserMangaStatus {
             status: Some(UserReadStatus::Reading),
@@ -107,17 +114,21 @@ mod test {
             priority: None,
             num_times_reread: None,
             reread_value: None,
+            start_date: None,
+            finish_date: None,
             tags: None,
             comments: None,
         };
-        let manga = get_manga("Grand Blue", &auth).unwrap();
-        let result = update_manga_list_status(manga.id, &query, &auth).unwrap();
+        let manga = get_manga("Grand Blue", &auth).await.unwrap();
+        let result = update_manga_list_status(manga.id, &query, &auth)
+            .await
+            .unwrap();
         println!("{:#?}", result);
         assert_eq!(result.num_chapters_read, 62);
     }
 
-    #[test]
-    fn test_get_user_manga_list() {
+    #[tokio::test]
+    async fn test_get_user_manga_list() {
         let auth = crate::auth::tests::get_auth();
         let query = GetUserMangaListQuery {
             fields: Some(ALL_ANIME_AND_MANGA_FIELDS.to_string()),
@@ -127,10 +138,10 @@ mod test {
             offset: 0,
             nsfw: true,
         };
-        let result = get_user_manga_list("@me", &query, &auth).unwrap();
+        let result = get_user_manga_list("@me", &query, &auth).await.unwrap();
 
         print!("{:#?}", result);
 
-        assert!(result.data.len() > 0);
+        assert!(!result.data.is_empty());
     }
 }
@@ -12,11 +12,15 @@ pub mod mangalist;
 pub use mangalist::*;
 /// API objects
 pub mod model;
+/// Helpers for walking paginated responses
+pub mod paging;
+pub use paging::*;
 /// User API endpoints
 pub mod user;
 pub use user::*;
 
 use crate::auth::OAuth;
+use reqwest::{ClientBuilder, RequestBuilder};
 use serde::{Deserialize, Serialize};
 
 pub const API_URL: &str = "https://api.myanimelist.net/v2";
@@ -32,6 +36,30 @@ pub enum Error {
     HttpError(reqwest::StatusCode),
 }
 
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match *self {
+            Error::ParseError(ref e) => Some(e),
+            Error::QuerySerializeError(ref e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl std::fmt::Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match *self {
+            Error::NoAuth => write!(f, "Not logged in"),
+            Error::TimedOut => write!(f, "Request timed out"),
+            Error::Unknown => write!(f, "Unknown error"),
+            Error::NoBody => write!(f, "Empty response"),
+            Error::ParseError(ref e) => write!(f, "Could not parse the response: {}", e),
+            Error::QuerySerializeError(ref e) => e.fmt(f),
+            Error::HttpError(status) => write!(f, "Http error: {}", status),
+        }
+    }
+}
+
 impl From<reqwest::Error> for Error {
     fn from(e: reqwest::Error) -> Self {
         if e.is_timeout() {
@@ -58,12 +86,12 @@ impl From<serde_urlencoded::ser::Error> for Error {
 pub(crate) struct ApiResponse {
     status: reqwest::StatusCode,
     body: Option<String>,
+    retry_after: Option<u64>,
 }
 
-pub(crate) fn apply_headers(
-    req: reqwest::blocking::RequestBuilder,
-    auth: &OAuth,
-) -> Result<reqwest::blocking::RequestBuilder, Error> {
+pub type ApiResult<T> = Result<T, Error>;
+
+pub(crate) fn apply_headers(req: RequestBuilder, auth: &OAuth) -> ApiResult<RequestBuilder> {
     let access_token = match auth.token() {
         Some(token) => &token.token.access_token,
         None => return Err(Error::NoAuth),
@@ -80,58 +108,57 @@ pub(crate) fn apply_headers(
         ))
 }
 
-pub(crate) fn send(
-    request: reqwest::blocking::RequestBuilder,
-    auth: &OAuth,
-) -> Result<ApiResponse, Error> {
+pub(crate) async fn send(request: RequestBuilder, auth: &OAuth) -> ApiResult<ApiResponse> {
     let request = apply_headers(request, auth)?;
-    let response = request.send()?;
+    let response = request.send().await?;
     let status = response.status();
+    let retry_after = response
+        .headers()
+        .get(reqwest::header::RETRY_AFTER)
+        .and_then(|header| header.to_str().ok())
+        .and_then(|secs| secs.parse().ok());
     Ok(ApiResponse {
         status,
-        body: if let Ok(body) = response.text() {
-            Some(body)
-        } else {
-            None
-        },
+        retry_after,
+        body: response.text().await.ok(),
     })
 }
 
-pub(crate) fn get<U: reqwest::IntoUrl>(url: U, auth: &OAuth) -> Result<ApiResponse, Error> {
-    let request = reqwest::blocking::ClientBuilder::new()
+pub(crate) async fn get<U: reqwest::IntoUrl>(url: U, auth: &OAuth) -> ApiResult<ApiResponse> {
+    let request = ClientBuilder::new()
         .user_agent(auth.user_agent())
         .build()?
         .get(url);
-    send(request, auth)
+    send(request, auth).await
 }
 
-pub(crate) fn patch<U: reqwest::IntoUrl, B: Serialize>(
+pub(crate) async fn patch<U: reqwest::IntoUrl, B: Serialize>(
     url: U,
     auth: &OAuth,
     body: &B,
-) -> Result<ApiResponse, Error> {
-    let request = reqwest::blocking::ClientBuilder::new()
+) -> ApiResult<ApiResponse> {
+    let request = ClientBuilder::new()
         .user_agent(auth.user_agent())
         .build()?
         .patch(url)
         .body(serde_urlencoded::to_string(body)?);
-    send(request, auth)
+    send(request, auth).await
 }
 
-pub(crate) fn delete<U: reqwest::IntoUrl>(url: U, auth: &OAuth) -> Result<ApiResponse, Error> {
-    let request = reqwest::blocking::ClientBuilder::new()
+pub(crate) async fn delete<U: reqwest::IntoUrl>(url: U, auth: &OAuth) -> ApiResult<ApiResponse> {
+    let request = ClientBuilder::new()
         .user_agent(auth.user_agent())
         .build()?
         .delete(url);
-    send(request, auth)
+    send(request, auth).await
 }
 
-pub(crate) fn handle_response<'a, D: Deserialize<'a>>(res: &'a ApiResponse) -> Result<D, Error> {
+pub(crate) fn handle_response<'a, D: Deserialize<'a>>(res: &'a ApiResponse) -> ApiResult<D> {
     if !res.status.is_success() {
         return Err(Error::HttpError(res.status));
     }
     if let Some(body) = &res.body {
-        Ok(serde_json::from_str::<D>(&body)?)
+        Ok(serde_json::from_str::<D>(body)?)
     } else {
         Err(Error::NoBody)
     }
@@ -9,11 +9,12 @@ pub struct AnimeSeason {
     pub season: Season,
 }
 
-#[derive(Clone, Debug, PartialEq, EnumString, IntoStaticStr)]
+/// Fields that can be requested for an anime
+#[derive(Clone, Copy, Debug, PartialEq, EnumString, IntoStaticStr)]
 #[strum(serialize_all = "snake_case")]
 pub enum AnimeField {
     Id,
-    Titel,
+    Title,
     MainPicture,
     AlternativeTitles,
     StartDate,
@@ -25,12 +26,14 @@ pub enum AnimeField {
     NumListUsers,
     NumScoringUsers,
     NSFW,
+    Genres,
     CreatedAt,
     UpdatedAt,
     MediaType,
     Status,
     MyListStatus,
     NumEpisodes,
+    StartSeason,
     Broadcast,
     Source,
     AverageEpisodeDuration,
@@ -42,20 +45,7 @@ pub enum AnimeField {
     Recommendations,
     Studios,
     Statistics,
-
-    NumVolumes,
-    NumChapters,
-    Authors,
-
-    Name,
-    Picture,
-    Gender,
-    Birthday,
-    Location,
-    JoinedAt,
-    AnimeStatistics,
-    TimeZone,
-    IsSupporter,
+    NumFavorites,
 }
 
 #[derive(Clone, Debug, PartialEq, EnumString, IntoStaticStr, AsStaticStr)]
@@ -86,7 +76,6 @@ pub enum AnimeStatus {
 #[derive(Clone, Debug, PartialEq, EnumString, IntoStaticStr)]
 #[strum(serialize_all = "snake_case")]
 pub enum Source {
-    Other,
     Original,
     Manga,
     #[strum(serialize = "4_koma_manga")]
@@ -102,6 +91,8 @@ pub enum Source {
     PictureBook,
     Radio,
     Music,
+    MixedMedia,
+    Other(String),
 }
 
 #[derive(Clone, Debug, Deserialize, Serialize)]
@@ -120,7 +111,8 @@ pub struct UserAnimeListStatus {
     pub updated_at: DateTimeWrapper,
 }
 
-#[derive(Clone, Debug, EnumString, IntoStaticStr)]
+#[derive(Clone, Debug, PartialEq, EnumString, IntoStaticStr)]
+#[strum(serialize_all = "snake_case")]
 pub enum Rating {
     G,
     #[strum(serialize = "pg")]
@@ -132,13 +124,50 @@ pub enum Rating {
     Rp,
     #[strum(serialize = "rx")]
     RX,
+    Other(String),
+}
+
+/// Number of users with the anime in each list
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct StatusCounts {
+    #[serde(
+        deserialize_with = "u64_from_str_or_number",
+        serialize_with = "u64_to_str"
+    )]
+    pub watching: u64,
+    #[serde(
+        deserialize_with = "u64_from_str_or_number",
+        serialize_with = "u64_to_str"
+    )]
+    pub completed: u64,
+    #[serde(
+        deserialize_with = "u64_from_str_or_number",
+        serialize_with = "u64_to_str"
+    )]
+    pub on_hold: u64,
+    #[serde(
+        deserialize_with = "u64_from_str_or_number",
+        serialize_with = "u64_to_str"
+    )]
+    pub dropped: u64,
+    #[serde(
+        deserialize_with = "u64_from_str_or_number",
+        serialize_with = "u64_to_str"
+    )]
+    pub plan_to_watch: u64,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct Statistics {
+    pub status: StatusCounts,
+    pub num_list_users: u64,
 }
 
 #[derive(Clone, Debug, Deserialize, Serialize)]
 pub struct Anime {
     pub id: u64,
     pub title: String,
-    pub main_picture: Picture,
+    pub main_picture: Option<Picture>,
     pub alternative_titles: Option<AlternativeTitles>,
     pub start_date: Option<DateWrapper>,
     pub end_date: Option<DateWrapper>,
@@ -156,14 +185,19 @@ pub struct Anime {
     pub status: Option<AnimeStatus>,
     pub my_list_status: Option<UserAnimeListStatus>,
     pub num_episodes: Option<u64>,
-    pub start_season: Option<Season>,
+    pub start_season: Option<AnimeSeason>,
     pub broadcast: Option<Broadcast>,
     pub source: Option<Source>,
     pub average_episode_duration: Option<u64>,
-    pub rating: Option<String>,
+    pub rating: Option<Rating>,
     pub studios: Option<Vec<Studio>>,
     pub pictures: Option<Vec<Picture>>,
     pub background: Option<String>,
+    pub related_anime: Option<Vec<RelatedAnime>>,
+    pub related_manga: Option<Vec<RelatedManga>>,
+    pub recommendations: Option<Vec<Recommendation<Anime>>>,
+    pub statistics: Option<Statistics>,
+    pub num_favorites: Option<u64>,
 }
 
 #[derive(Clone, Debug, PartialEq, EnumString, IntoStaticStr)]
@@ -211,3 +245,43 @@ pub enum SortStyle {
     AnimeId,
     Other(String),
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::model::tests::round_trip;
+
+    #[test]
+    fn test_anime_details_round_trip() {
+        let anime: Anime = round_trip(include_str!("../../../fixtures/anime_details.json"));
+        assert_eq!(anime.rating, Some(Rating::R));
+        assert_eq!(anime.source, Some(Source::Original));
+        assert_eq!(anime.num_favorites, Some(72000));
+        assert_eq!(anime.start_date.unwrap().to_string(), "1998-04-03");
+        assert_eq!(
+            anime.my_list_status.unwrap().start_date,
+            Some(DateWrapper::year_month(2019, 6).unwrap())
+        );
+        assert_eq!(
+            anime.alternative_titles.unwrap().jp.unwrap(),
+            "カウボーイビバップ"
+        );
+
+        let statistics = anime.statistics.unwrap();
+        assert_eq!(statistics.status.completed, 1041442);
+        assert_eq!(statistics.num_list_users, 1412568);
+
+        let related_anime = anime.related_anime.unwrap();
+        assert_eq!(related_anime.len(), 2);
+        assert_eq!(related_anime[0].relation_type, RelationType::SideStory);
+        assert!(related_anime[1].node.main_picture.is_none());
+
+        let related_manga = anime.related_manga.unwrap();
+        assert_eq!(related_manga[0].relation_type, RelationType::Adaptation);
+        assert_eq!(related_manga[0].node.id, 173);
+
+        let recommendations = anime.recommendations.unwrap();
+        assert_eq!(recommendations[0].node.title, "Samurai Champloo");
+        assert_eq!(recommendations[0].num_recommendations, 70);
+    }
+}
@@ -0,0 +1,168 @@
+use std::fmt;
+use strum_macros::{EnumString, IntoStaticStr};
+
+/// Fields of `my_list_status` (anime and manga)
+#[derive(Clone, Copy, Debug, PartialEq, EnumString, IntoStaticStr)]
+#[strum(serialize_all = "snake_case")]
+pub enum ListStatusField {
+    Status,
+    Score,
+    StartDate,
+    FinishDate,
+    Priority,
+    Tags,
+    Comments,
+    UpdatedAt,
+    NumEpisodesWatched,
+    IsRewatching,
+    NumTimesRewatched,
+    RewatchValue,
+    NumVolumesRead,
+    NumChaptersRead,
+    IsRereading,
+    NumTimesReread,
+    RereadValue,
+}
+
+/// Builder for the `fields` query parameter.
+///
+/// Supports nested selections:
+/// ```
+/// use rmal::api::model::*;
+///
+/// let fields = Fields::new()
+///     .fields(&[AnimeField::Id, AnimeField::Title])
+///     .nested(AnimeField::MyListStatus, Fields::new().field(ListStatusField::Score))
+///     .nodes(AnimeField::RelatedAnime, Fields::new().field(AnimeField::Title));
+///
+/// assert_eq!(
+///     fields.to_string(),
+///     "id,title,my_list_status{score},related_anime{node{title}}"
+/// );
+/// ```
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Fields {
+    selections: Vec<(&'static str, Option<Fields>)>,
+}
+
+impl Fields {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Select a field
+    pub fn field<F: Into<&'static str>>(self, field: F) -> Self {
+        self.select(field.into(), None)
+    }
+
+    /// Select several fields
+    pub fn fields<F: Into<&'static str> + Copy>(self, fields: &[F]) -> Self {
+        fields
+            .iter()
+            .fold(self, |selection, field| selection.field(*field))
+    }
+
+    /// Select a field along with the fields of the object it holds, e.g. `my_list_status{score}`
+    pub fn nested<F: Into<&'static str>>(self, field: F, nested: Fields) -> Self {
+        self.select(field.into(), Some(nested))
+    }
+
+    /// Select a list of nodes along with the fields of each node, e.g. `related_anime{node{title}}`
+    pub fn nodes<F: Into<&'static str>>(self, field: F, node: Fields) -> Self {
+        self.nested(field, Fields::new().nested("node", node))
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.selections.is_empty()
+    }
+
+    /// The value expected by the `fields` member of query objects
+    pub fn to_query(&self) -> Option<String> {
+        if self.is_empty() {
+            None
+        } else {
+            Some(self.to_string())
+        }
+    }
+
+    fn select(mut self, name: &'static str, nested: Option<Fields>) -> Self {
+        match self.selections.iter_mut().find(|(n, _)| *n == name) {
+            Some(selection) => selection.1 = nested,
+            None => self.selections.push((name, nested)),
+        }
+        self
+    }
+}
+
+impl fmt::Display for Fields {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for (i, (name, nested)) in self.selections.iter().enumerate() {
+            if i > 0 {
+                write!(f, ",")?;
+            }
+            write!(f, "{}", name)?;
+            if let Some(nested) = nested {
+                write!(f, "{{{}}}", nested)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Utility to convert a list of fields to a string (in the format expected by query objects)
+pub fn fields_to_string<F: Into<&'static str> + Copy>(fields: &[F]) -> String {
+    Fields::new().fields(fields).to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::model::{AnimeField, MangaField, UserField};
+
+    #[test]
+    fn test_fields_to_string() {
+        assert_eq!(
+            fields_to_string(&[AnimeField::Id, AnimeField::NSFW, AnimeField::StartSeason]),
+            "id,nsfw,start_season"
+        );
+        assert_eq!(
+            fields_to_string(&[MangaField::NumChapters, MangaField::Serialization]),
+            "num_chapters,serialization"
+        );
+        assert_eq!(
+            fields_to_string(&[UserField::Name, UserField::AnimeStatistics]),
+            "name,anime_statistics"
+        );
+    }
+
+    #[test]
+    fn test_nested_fields() {
+        let fields = Fields::new()
+            .field(MangaField::Title)
+            .nested(
+                MangaField::MyListStatus,
+                Fields::new().fields(&[ListStatusField::Status, ListStatusField::NumChaptersRead]),
+            )
+            .nodes(
+                MangaField::Recommendations,
+                Fields::new().fields(&[MangaField::Title, MangaField::Mean]),
+            );
+        assert_eq!(
+            fields.to_string(),
+            "title,my_list_status{status,num_chapters_read},recommendations{node{title,mean}}"
+        );
+    }
+
+    #[test]
+    fn test_reselecting_replaces() {
+        let fields = Fields::new()
+            .nested(
+                AnimeField::MyListStatus,
+                Fields::new().field(ListStatusField::Score),
+            )
+            .field(AnimeField::Id)
+            .field(AnimeField::MyListStatus);
+        assert_eq!(fields.to_string(), "my_list_status,id");
+        assert_eq!(Fields::new().to_query(), None);
+    }
+}
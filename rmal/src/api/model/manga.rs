@@ -3,6 +3,41 @@ use serde::{Deserialize, Serialize};
 use std::fmt::Debug;
 use strum_macros::{AsStaticStr, EnumString, IntoStaticStr};
 
+/// Fields that can be requested for a manga
+#[derive(Clone, Copy, Debug, PartialEq, EnumString, IntoStaticStr)]
+#[strum(serialize_all = "snake_case")]
+pub enum MangaField {
+    Id,
+    Title,
+    MainPicture,
+    AlternativeTitles,
+    StartDate,
+    EndDate,
+    Synopsis,
+    Mean,
+    Rank,
+    Popularity,
+    NumListUsers,
+    NumScoringUsers,
+    NSFW,
+    Genres,
+    CreatedAt,
+    UpdatedAt,
+    MediaType,
+    Status,
+    MyListStatus,
+    NumVolumes,
+    NumChapters,
+    Authors,
+    Pictures,
+    Background,
+    RelatedAnime,
+    RelatedManga,
+    Recommendations,
+    Serialization,
+    NumFavorites,
+}
+
 #[derive(Clone, Debug, PartialEq, EnumString, IntoStaticStr)]
 #[strum(serialize_all = "snake_case")]
 pub enum MangaRankingType {
@@ -101,4 +136,51 @@ pub struct Manga {
     pub num_volumes: Option<u64>,
     pub num_chapters: Option<u64>,
     pub authors: Option<Vec<PersonRole>>,
+    pub pictures: Option<Vec<Picture>>,
+    pub background: Option<String>,
+    pub related_anime: Option<Vec<RelatedAnime>>,
+    pub related_manga: Option<Vec<RelatedManga>>,
+    pub recommendations: Option<Vec<Recommendation<Manga>>>,
+    pub serialization: Option<Vec<Serialization>>,
+    pub num_favorites: Option<u64>,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct Magazine {
+    pub id: u64,
+    pub name: String,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct Serialization {
+    pub node: Magazine,
+    pub role: Option<String>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::model::tests::round_trip;
+
+    #[test]
+    fn test_manga_details_round_trip() {
+        let manga: Manga = round_trip(include_str!("../../../fixtures/manga_details.json"));
+        assert_eq!(manga.status, Some(MangaStatus::CurrentlyPublishing));
+        assert_eq!(manga.num_favorites, Some(118000));
+
+        let serialization = manga.serialization.unwrap();
+        assert_eq!(serialization[0].node.name, "Young Animal");
+        assert!(serialization[0].role.is_none());
+
+        let authors = manga.authors.unwrap();
+        assert_eq!(authors[0].node.last_name.as_deref(), Some("Miura"));
+        assert_eq!(authors[0].role, "Story & Art");
+
+        assert_eq!(manga.related_anime.unwrap()[0].node.id, 33);
+        assert_eq!(
+            manga.related_manga.unwrap()[0].relation_type,
+            RelationType::SideStory
+        );
+        assert_eq!(manga.recommendations.unwrap()[0].node.title, "Claymore");
+    }
 }
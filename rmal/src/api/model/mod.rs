@@ -7,6 +7,9 @@ pub use manga::*;
 /// User related structs
 pub mod user;
 pub use user::*;
+/// Builders for the `fields` query parameter
+pub mod fields;
+pub use fields::*;
 
 use serde::{Deserialize, Deserializer, Serialize, Serializer};
 use std::fmt::Debug;
@@ -44,6 +47,7 @@ pub struct Picture {
 pub struct AlternativeTitles {
     pub synonyms: Option<Vec<String>>,
     pub en: Option<String>,
+    #[serde(rename = "ja", alias = "jp")]
     pub jp: Option<String>,
 }
 
@@ -67,9 +71,16 @@ pub enum Season {
 pub struct TimeWrapper {
     pub time: Time,
 }
-#[derive(Clone, Debug)]
+/// A date as returned by MAL, which may only be known to the year (`2021`)
+/// or the month (`2021-04`).
+///
+/// Keeps the precision it was parsed with, so it serializes back to the same
+/// form. Less precise dates order before more precise ones in the same period.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct DateWrapper {
-    pub date: Date,
+    pub year: i32,
+    pub month: Option<u8>,
+    pub day: Option<u8>,
 }
 #[derive(Clone, Debug)]
 pub struct DateTimeWrapper {
@@ -88,18 +99,9 @@ pub struct Studio {
     pub name: String,
 }
 
-pub const ALL_ANIME_AND_MANGA_FIELDS: &str = "id,title,main_picture,alternative_titles,start_date,end_date,synopsis,mean,rank,popularity,num_list_users,num_scoring_users,nsfw,genres,create_at,updated_at,media_type,status,my_list_status,num_episodes,broadcast,source,average_episode_duration,rating,pictures,background,related_anime,related_manga,recommendations,studios,statistics,num_volumes,num_chapters,authors";
+pub const ALL_ANIME_AND_MANGA_FIELDS: &str = "id,title,main_picture,alternative_titles,start_date,end_date,synopsis,mean,rank,popularity,num_list_users,num_scoring_users,nsfw,genres,created_at,updated_at,media_type,status,my_list_status,num_episodes,start_season,broadcast,source,average_episode_duration,rating,pictures,background,related_anime,related_manga,recommendations,studios,statistics,num_favorites,num_volumes,num_chapters,authors{first_name,last_name},serialization";
 pub const ALL_USER_FIELDS: &str =
-    "id,name,picture,gender,birthday,location,joined_at,anime_statistics,time_zone,is_supporter";
-
-/// Utility to convert a list of fields to a string (in the format expected by query objects)
-pub fn fields_to_string(fields: &[AnimeField]) -> String {
-    fields
-        .iter()
-        .map(|field| field.into())
-        .collect::<Vec<&str>>()
-        .join(",")
-}
+    "id,name,picture,gender,birthday,location,joined_at,anime_statistics,manga_statistics,time_zone,is_supporter";
 
 #[derive(Clone, Debug, PartialEq, EnumString, IntoStaticStr)]
 #[strum(serialize_all = "snake_case")]
@@ -118,15 +120,78 @@ pub struct RankingInfo {
 
 #[derive(Clone, Debug, Deserialize, Serialize)]
 pub struct Person {
-    id: u64,
-    first_name: Option<String>,
-    last_name: Option<String>,
+    pub id: u64,
+    pub first_name: Option<String>,
+    pub last_name: Option<String>,
 }
 
 #[derive(Clone, Debug, Deserialize, Serialize)]
 pub struct PersonRole {
-    node: Person,
-    role: String,
+    pub node: Person,
+    pub role: String,
+}
+
+#[derive(Clone, Debug, PartialEq, EnumString, IntoStaticStr)]
+#[strum(serialize_all = "snake_case")]
+pub enum RelationType {
+    Sequel,
+    Prequel,
+    AlternativeSetting,
+    AlternativeVersion,
+    SideStory,
+    ParentStory,
+    Summary,
+    FullStory,
+    SpinOff,
+    Adaptation,
+    Character,
+    Other(String),
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct RelatedAnime {
+    pub node: Anime,
+    pub relation_type: RelationType,
+    pub relation_type_formatted: String,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct RelatedManga {
+    pub node: Manga,
+    pub relation_type: RelationType,
+    pub relation_type_formatted: String,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct Recommendation<N: Clone + Debug> {
+    pub node: N,
+    pub num_recommendations: u64,
+}
+
+/// MAL sends some counts (e.g. `statistics`) as strings, so accept both
+pub(crate) fn u64_from_str_or_number<'de, D>(deserializer: D) -> Result<u64, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Count {
+        Number(u64),
+        String(String),
+    }
+
+    match Count::deserialize(deserializer)? {
+        Count::Number(n) => Ok(n),
+        Count::String(s) => s.parse().map_err(serde::de::Error::custom),
+    }
+}
+
+/// Writes a count the way `u64_from_str_or_number` reads MAL's, as a string
+pub(crate) fn u64_to_str<S>(n: &u64, serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    serializer.collect_str(n)
 }
 
 macro_rules! impl_serialize_deserialize {
@@ -140,7 +205,10 @@ macro_rules! impl_serialize_deserialize {
             where
                 S: Serializer,
             {
-                serializer.serialize_str(self.into())
+                match self {
+                    Self::Other(s) => serializer.serialize_str(s),
+                    _ => serializer.serialize_str(self.into()),
+                }
             }
         }
 
@@ -151,8 +219,8 @@ macro_rules! impl_serialize_deserialize {
             {
                 let s = String::deserialize(deserializer)?;
                 match Self::from_str(s.as_str()) {
+                    Ok(Self::Other(_)) | Err(_) => Ok(Self::Other(s)),
                     Ok(n) => Ok(n),
-                    Err(_) => Ok(Self::Other(s)),
                 }
             }
 
@@ -165,8 +233,8 @@ macro_rules! impl_serialize_deserialize {
             {
                 let s = String::deserialize(deserializer)?;
                 *place = match Self::from_str(s.as_str()) {
+                    Ok(Self::Other(_)) | Err(_) => Self::Other(s),
                     Ok(n) => n,
-                    Err(_) => Self::Other(s),
                 };
                 Ok(())
             }
@@ -188,52 +256,19 @@ impl_serialize_deserialize!(
     SortStyle,
     UserReadStatus,
     MangaMediaType,
-    MangaStatus
+    MangaStatus,
+    Source,
+    Rating,
+    RelationType
 );
 
-impl Serialize for Source {
-    fn serialize<S>(&self, serializer: S) -> Result<<S as Serializer>::Ok, <S as Serializer>::Error>
-    where
-        S: Serializer,
-    {
-        serializer.serialize_str(self.into())
-    }
-}
-
-impl<'de> Deserialize<'de> for Source {
-    fn deserialize<D>(deserializer: D) -> Result<Self, <D as Deserializer<'de>>::Error>
-    where
-        D: Deserializer<'de>,
-    {
-        let s = String::deserialize(deserializer)?;
-        match Self::from_str(s.as_str()) {
-            Ok(n) => Ok(n),
-            Err(_) => Ok(Self::Other),
-        }
-    }
-
-    fn deserialize_in_place<D>(
-        deserializer: D,
-        place: &mut Self,
-    ) -> Result<(), <D as Deserializer<'de>>::Error>
-    where
-        D: Deserializer<'de>,
-    {
-        let s = String::deserialize(deserializer)?;
-        *place = match Self::from_str(s.as_str()) {
-            Ok(n) => n,
-            Err(_) => Self::Other,
-        };
-        Ok(())
-    }
-}
-
 impl Serialize for TimeWrapper {
     fn serialize<S>(&self, serializer: S) -> Result<<S as Serializer>::Ok, <S as Serializer>::Error>
     where
         S: Serializer,
     {
-        serializer.serialize_str(&self.time.format("%H:%M:%S"))
+        // MAL leaves out the seconds, and they aren't kept
+        serializer.serialize_str(&self.time.format("%H:%M"))
     }
 }
 
@@ -275,12 +310,9 @@ impl<'de> Deserialize<'de> for TimeWrapper {
     {
         use serde::de::Error;
         let s = String::deserialize(deserializer)?;
-        match Time::parse(&s, "%H:%M:%S") {
-            Ok(time) => {
-                place.time = time;
-                return Ok(());
-            }
-            Err(_) => (),
+        if let Ok(time) = Time::parse(&s, "%H:%M:%S") {
+            place.time = time;
+            return Ok(());
         };
         let re = regex::Regex::new(r"([0-9]+):([0-9]+)").unwrap();
         if let Some(caps) = re.captures(&s) {
@@ -305,12 +337,119 @@ impl<'de> Deserialize<'de> for TimeWrapper {
     }
 }
 
+impl DateWrapper {
+    pub fn year(year: i32) -> Self {
+        Self {
+            year,
+            month: None,
+            day: None,
+        }
+    }
+
+    pub fn year_month(year: i32, month: u8) -> Result<Self, time::ComponentRangeError> {
+        Date::try_from_ymd(year, month, 1)?;
+        Ok(Self {
+            year,
+            month: Some(month),
+            day: None,
+        })
+    }
+
+    pub fn ymd(year: i32, month: u8, day: u8) -> Result<Self, time::ComponentRangeError> {
+        Ok(Date::try_from_ymd(year, month, day)?.into())
+    }
+
+    /// Whether month and day are both known
+    pub fn is_complete(&self) -> bool {
+        self.day.is_some()
+    }
+
+    /// The first day of the period this date covers
+    pub fn first_day(&self) -> Date {
+        Date::try_from_ymd(self.year, self.month.unwrap_or(1), self.day.unwrap_or(1))
+            .expect("DateWrapper always holds a valid date")
+    }
+
+    /// Human readable form, e.g. `2021`, `Apr 2021` or `Apr 7, 2021`
+    pub fn pretty(&self) -> String {
+        match (self.month, self.day) {
+            (Some(_), Some(_)) => self.first_day().format("%b %-d, %Y"),
+            (Some(_), None) => self.first_day().format("%b %Y"),
+            _ => self.year.to_string(),
+        }
+    }
+}
+
+impl From<Date> for DateWrapper {
+    fn from(date: Date) -> Self {
+        Self {
+            year: date.year(),
+            month: Some(date.month()),
+            day: Some(date.day()),
+        }
+    }
+}
+
+impl FromStr for DateWrapper {
+    type Err = String;
+
+    /// Parses `YYYY`, `YYYY-MM` or `YYYY-MM-DD`
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut parts = s.trim().split('-');
+        let mut next = |name: &str| -> Result<Option<&str>, String> {
+            match parts.next() {
+                Some(part) if part.is_empty() || !part.chars().all(|c| c.is_ascii_digit()) => {
+                    Err(format!("Invalid {} in date {:?}", name, s))
+                }
+                part => Ok(part),
+            }
+        };
+        let year = next("year")?;
+        let month = next("month")?;
+        let day = next("day")?;
+        let invalid = |e: &dyn ToString| format!("Invalid date {:?}: {}", s, e.to_string());
+        let date = match (year, month, day) {
+            (Some(y), None, None) => Self::year(y.parse().map_err(|e| invalid(&e))?),
+            (Some(y), Some(m), None) => Self::year_month(
+                y.parse().map_err(|e| invalid(&e))?,
+                m.parse().map_err(|e| invalid(&e))?,
+            )
+            .map_err(|e| invalid(&e))?,
+            (Some(y), Some(m), Some(d)) => Self::ymd(
+                y.parse().map_err(|e| invalid(&e))?,
+                m.parse().map_err(|e| invalid(&e))?,
+                d.parse().map_err(|e| invalid(&e))?,
+            )
+            .map_err(|e| invalid(&e))?,
+            _ => return Err(format!("Invalid date {:?}", s)),
+        };
+        if parts.next().is_some() {
+            return Err(format!("Invalid date {:?}", s));
+        }
+        Ok(date)
+    }
+}
+
+/// Formats the date the way MAL sends it, keeping its precision
+impl std::fmt::Display for DateWrapper {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{:04}", self.year)?;
+        if let Some(month) = self.month {
+            write!(f, "-{:02}", month)?;
+            if let Some(day) = self.day {
+                write!(f, "-{:02}", day)?;
+            }
+        }
+        Ok(())
+    }
+}
+
 impl Serialize for DateWrapper {
     fn serialize<S>(&self, serializer: S) -> Result<<S as Serializer>::Ok, <S as Serializer>::Error>
     where
         S: Serializer,
     {
-        serializer.serialize_str(&self.date.format("%Y-%m-%d"))
+        serializer.collect_str(self)
     }
 }
 
@@ -321,74 +460,7 @@ impl<'de> Deserialize<'de> for DateWrapper {
     {
         use serde::de::Error;
         let s = String::deserialize(deserializer)?;
-        match Date::parse(&s, "%Y-%m-%d") {
-            Ok(date) => return Ok(DateWrapper { date }),
-            Err(_) => (),
-        };
-        let re = regex::Regex::new(r"([0-9]+)-?([0-9]+)?").unwrap();
-        if let Some(caps) = re.captures(&s) {
-            let year = caps.get(1).unwrap();
-            let year = match year.as_str().parse::<i32>() {
-                Ok(year) => year,
-                Err(e) => return Err(serde::de::Error::custom(e.to_string())),
-            };
-            let month = if let Some(month) = caps.get(2) {
-                match month.as_str().parse::<u8>() {
-                    Ok(month) => month,
-                    Err(e) => return Err(serde::de::Error::custom(e.to_string())),
-                }
-            } else {
-                1
-            };
-            let date = match Date::try_from_ymd(year, month, 1) {
-                Ok(date) => date,
-                Err(e) => return Err(serde::de::Error::custom(e.to_string())),
-            };
-            Ok(DateWrapper { date })
-        } else {
-            Err(D::Error::custom("Could not parse date"))
-        }
-    }
-
-    fn deserialize_in_place<D>(
-        deserializer: D,
-        place: &mut Self,
-    ) -> Result<(), <D as Deserializer<'de>>::Error>
-    where
-        D: Deserializer<'de>,
-    {
-        use serde::de::Error;
-        let s = String::deserialize(deserializer)?;
-        match Date::parse(&s, "%Y-%m-%d") {
-            Ok(date) => {
-                place.date = date;
-                return Ok(());
-            }
-            Err(_) => (),
-        };
-        let re = regex::Regex::new(r"([0-9]+)-?([0-9]+)?").unwrap();
-        if let Some(caps) = re.captures(&s) {
-            let year = caps.get(1).unwrap();
-            let year = match year.as_str().parse::<i32>() {
-                Ok(year) => year,
-                Err(e) => return Err(serde::de::Error::custom(e.to_string())),
-            };
-            let month = if let Some(month) = caps.get(2) {
-                match month.as_str().parse::<u8>() {
-                    Ok(month) => month,
-                    Err(e) => return Err(serde::de::Error::custom(e.to_string())),
-                }
-            } else {
-                1
-            };
-            place.date = match Date::try_from_ymd(year, month, 1) {
-                Ok(date) => date,
-                Err(e) => return Err(serde::de::Error::custom(e.to_string())),
-            };
-            Ok(())
-        } else {
-            Err(D::Error::custom("Could not parse date"))
-        }
+        s.parse().map_err(D::Error::custom)
     }
 }
 
@@ -397,7 +469,8 @@ impl Serialize for DateTimeWrapper {
     where
         S: Serializer,
     {
-        serializer.serialize_str(&self.datetime.format("%Y-%m-%dT%H:%M:%S"))
+        // MAL's times are all in UTC
+        serializer.serialize_str(&self.datetime.format("%Y-%m-%dT%H:%M:%S+00:00"))
     }
 }
 
@@ -432,3 +505,96 @@ impl<'de> Deserialize<'de> for DateTimeWrapper {
         }
     }
 }
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use serde::de::DeserializeOwned;
+
+    /// Deserializes `json` and checks serializing it gives the same json back, so no
+    /// field was dropped or renamed on the way. Nulls are left out of the comparison,
+    /// as a missing field and a null one deserialize the same.
+    pub fn round_trip<T: DeserializeOwned + Serialize>(json: &str) -> T {
+        let fixture: serde_json::Value = serde_json::from_str(json).unwrap();
+        let parsed: T = serde_json::from_value(fixture.clone()).unwrap();
+        let serialized = serde_json::to_value(&parsed).unwrap();
+        assert_eq!(without_nulls(serialized), without_nulls(fixture));
+        parsed
+    }
+
+    fn without_nulls(value: serde_json::Value) -> serde_json::Value {
+        use serde_json::Value;
+        match value {
+            Value::Object(map) => Value::Object(
+                map.into_iter()
+                    .filter(|(_, value)| !value.is_null())
+                    .map(|(key, value)| (key, without_nulls(value)))
+                    .collect(),
+            ),
+            Value::Array(values) => Value::Array(values.into_iter().map(without_nulls).collect()),
+            value => value,
+        }
+    }
+
+    #[test]
+    fn test_partial_dates() {
+        let year: DateWrapper = round_trip("\"2021\"");
+        assert_eq!(year, DateWrapper::year(2021));
+        let month: DateWrapper = round_trip("\"2021-04\"");
+        assert_eq!(month, DateWrapper::year_month(2021, 4).unwrap());
+        let day: DateWrapper = round_trip("\"2021-04-07\"");
+        assert_eq!(day, DateWrapper::ymd(2021, 4, 7).unwrap());
+        assert!(day.is_complete() && !month.is_complete());
+
+        assert_eq!(serde_json::to_string(&year).unwrap(), "\"2021\"");
+        assert_eq!(serde_json::to_string(&month).unwrap(), "\"2021-04\"");
+        assert_eq!(serde_json::to_string(&day).unwrap(), "\"2021-04-07\"");
+
+        assert_eq!(year.pretty(), "2021");
+        assert_eq!(month.pretty(), "Apr 2021");
+        assert_eq!(day.pretty(), "Apr 7, 2021");
+
+        for invalid in &[
+            "",
+            "2021-13",
+            "2021-02-30",
+            "2021-4-x",
+            "21-04-07-01",
+            "april",
+        ] {
+            assert!(invalid.parse::<DateWrapper>().is_err(), "{}", invalid);
+        }
+    }
+
+    #[test]
+    fn test_partial_date_order() {
+        let mut dates: Vec<DateWrapper> = ["2021-04-07", "2020", "2021-04", "2021", "2021-03-31"]
+            .iter()
+            .map(|s| s.parse().unwrap())
+            .collect();
+        dates.sort();
+        let dates: Vec<String> = dates.iter().map(ToString::to_string).collect();
+        assert_eq!(
+            dates,
+            vec!["2020", "2021", "2021-03-31", "2021-04", "2021-04-07"]
+        );
+    }
+
+    #[test]
+    fn test_unknown_enum_values_are_kept() {
+        let source: Source = round_trip("\"mixed_media\"");
+        assert_eq!(source, Source::MixedMedia);
+        let source: Source = round_trip("\"other\"");
+        assert_eq!(source, Source::Other("other".to_string()));
+        let source: Source = round_trip("\"radio_drama\"");
+        assert_eq!(source, Source::Other("radio_drama".to_string()));
+        assert_eq!(serde_json::to_string(&source).unwrap(), "\"radio_drama\"");
+
+        let rating: Rating = round_trip("\"r+\"");
+        assert_eq!(rating, Rating::Rp);
+        let rating: Rating = round_trip("\"pg_13\"");
+        assert_eq!(rating, Rating::PG13);
+        let rating: Rating = round_trip("\"nc_17\"");
+        assert_eq!(serde_json::to_string(&rating).unwrap(), "\"nc_17\"");
+    }
+}
@@ -14,6 +14,23 @@ pub enum UserWatchStatus {
     Other(String),
 }
 
+/// Fields that can be requested for a user
+#[derive(Clone, Copy, Debug, PartialEq, EnumString, IntoStaticStr)]
+#[strum(serialize_all = "snake_case")]
+pub enum UserField {
+    Id,
+    Name,
+    Picture,
+    Gender,
+    Birthday,
+    Location,
+    JoinedAt,
+    AnimeStatistics,
+    MangaStatistics,
+    TimeZone,
+    IsSupporter,
+}
+
 #[derive(Clone, Debug, Deserialize, Serialize)]
 pub struct AnimeStatistics {
     pub num_items_watching: u64,
@@ -33,6 +50,26 @@ pub struct AnimeStatistics {
     pub mean_score: f64,
 }
 
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct MangaStatistics {
+    pub num_items_reading: u64,
+    pub num_items_completed: u64,
+    pub num_items_on_hold: u64,
+    pub num_items_dropped: u64,
+    pub num_items_plan_to_read: u64,
+    pub num_items: u64,
+    pub num_days_read: f64,
+    pub num_days_reading: f64,
+    pub num_days_completed: f64,
+    pub num_days_on_hold: f64,
+    pub num_days_dropped: f64,
+    pub num_days: f64,
+    pub num_volumes: u64,
+    pub num_chapters: u64,
+    pub num_times_reread: u64,
+    pub mean_score: f64,
+}
+
 #[derive(Clone, Debug, Deserialize, Serialize)]
 pub struct UserInfo {
     pub id: u64,
@@ -43,6 +80,23 @@ pub struct UserInfo {
     pub location: Option<String>,
     pub joined_at: DateTimeWrapper,
     pub anime_statistics: Option<AnimeStatistics>,
+    pub manga_statistics: Option<MangaStatistics>,
     pub time_zone: Option<String>,
     pub is_supporter: Option<bool>,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::model::tests::round_trip;
+
+    #[test]
+    fn test_user_info_round_trip() {
+        let user: UserInfo = round_trip(include_str!("../../../fixtures/user_info.json"));
+        assert_eq!(user.anime_statistics.unwrap().num_items_completed, 312);
+
+        let manga_statistics = user.manga_statistics.unwrap();
+        assert_eq!(manga_statistics.num_items_plan_to_read, 12);
+        assert_eq!(manga_statistics.num_chapters, 3050);
+    }
+}
@@ -0,0 +1,150 @@
+use super::model::*;
+use super::Error;
+use super::{get, handle_response};
+use crate::auth::OAuth;
+use futures::stream::{self, Stream, TryStreamExt};
+use serde::de::DeserializeOwned;
+use std::collections::VecDeque;
+use std::fmt::Debug;
+use std::time::Duration;
+
+/// How many times a rate limited page request is retried before giving up
+const MAX_RATE_LIMIT_RETRIES: u32 = 5;
+/// Seconds to wait before the first retry when MAL doesn't send `Retry-After`
+const INITIAL_BACKOFF_SECS: u64 = 1;
+
+struct PagerState<'a, D> {
+    buffer: VecDeque<D>,
+    next: Option<String>,
+    remaining: Option<usize>,
+    auth: &'a OAuth,
+}
+
+/// Lazily walks a paginated response by following `Paging.next`.
+///
+/// Works for both `Page<T>` (yielding `Node<T>`) and `Ranking<T>`.
+/// The next page is only requested once the current one has been consumed,
+/// and the stream ends after `max_items` items if it is set.
+pub fn paginate<'a, D>(
+    first: PageableData<Vec<D>>,
+    max_items: Option<usize>,
+    auth: &'a OAuth,
+) -> impl Stream<Item = Result<D, Error>> + 'a
+where
+    D: DeserializeOwned + Clone + Debug + 'a,
+{
+    let state = PagerState {
+        buffer: first.data.into(),
+        next: first.paging.next,
+        remaining: max_items,
+        auth,
+    };
+
+    stream::unfold(state, |mut state| async move {
+        if state.remaining == Some(0) {
+            return None;
+        }
+
+        while state.buffer.is_empty() {
+            let url = state.next.take()?;
+            match fetch_page::<D>(&url, state.auth).await {
+                Ok(page) => {
+                    state.buffer = page.data.into();
+                    state.next = page.paging.next;
+                }
+                // `next` has been taken so the stream ends after the error
+                Err(e) => return Some((Err(e), state)),
+            }
+        }
+
+        let item = state.buffer.pop_front()?;
+        if let Some(remaining) = state.remaining.as_mut() {
+            *remaining -= 1;
+        }
+        Some((Ok(item), state))
+    })
+}
+
+/// Walks every page (up to `max_items` items) and collects the results
+pub async fn collect_pages<D>(
+    first: PageableData<Vec<D>>,
+    max_items: Option<usize>,
+    auth: &OAuth,
+) -> Result<Vec<D>, Error>
+where
+    D: DeserializeOwned + Clone + Debug,
+{
+    paginate(first, max_items, auth).try_collect().await
+}
+
+/// Fetches a `Paging.next` url, backing off while MAL is rate limiting us
+async fn fetch_page<D>(url: &str, auth: &OAuth) -> Result<PageableData<Vec<D>>, Error>
+where
+    D: DeserializeOwned + Clone + Debug,
+{
+    let mut backoff = INITIAL_BACKOFF_SECS;
+    for _ in 0..MAX_RATE_LIMIT_RETRIES {
+        let response = get(url, auth).await?;
+        if response.status != reqwest::StatusCode::TOO_MANY_REQUESTS {
+            return handle_response(&response);
+        }
+        let wait = response.retry_after.unwrap_or(backoff);
+        tokio::time::delay_for(Duration::from_secs(wait)).await;
+        backoff *= 2;
+    }
+    Err(Error::HttpError(reqwest::StatusCode::TOO_MANY_REQUESTS))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::anime::tests::get_anime;
+    use crate::api::{get_anime_list, GetAnimeListQuery};
+    use futures::StreamExt;
+
+    fn single_page(ids: &[u64]) -> PageableData<Vec<u64>> {
+        PageableData {
+            data: ids.to_vec(),
+            paging: Paging {
+                previous: None,
+                next: None,
+            },
+        }
+    }
+
+    #[tokio::test]
+    async fn test_paginate_single_page() {
+        let auth = OAuth::new("mal-cli", "client_id", None, "127.0.0.1:7878");
+        let items: Vec<u64> = paginate(single_page(&[1, 2, 3]), None, &auth)
+            .map(Result::unwrap)
+            .collect()
+            .await;
+        assert_eq!(items, vec![1, 2, 3]);
+    }
+
+    #[tokio::test]
+    async fn test_paginate_max_items() {
+        let auth = OAuth::new("mal-cli", "client_id", None, "127.0.0.1:7878");
+        let items = collect_pages(single_page(&[1, 2, 3]), Some(2), &auth)
+            .await
+            .unwrap();
+        assert_eq!(items, vec![1, 2]);
+    }
+
+    #[tokio::test]
+    async fn test_collect_pages() {
+        let auth = crate::auth::tests::get_auth();
+        let anime = get_anime("Gintama", &auth).await.unwrap();
+        let query = GetAnimeListQuery {
+            q: anime.title,
+            limit: 4,
+            offset: 0,
+            nsfw: false,
+            fields: None,
+        };
+        let first = get_anime_list(&query, &auth).await.unwrap();
+        let result = collect_pages(first, Some(10), &auth).await.unwrap();
+        println!("{:#?}", result);
+        assert_eq!(result.len(), 10);
+    }
+}
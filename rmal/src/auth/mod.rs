@@ -4,11 +4,6 @@ pub mod redirect;
 /// structs and methods for token management
 pub mod token;
 
-/// methods for cache
-pub mod cache;
-
-use crate::config::oauth_config::AuthConfig;
-use eyre::Result;
 use rand::{distributions::Alphanumeric, thread_rng, Rng};
 use serde::{Deserialize, Serialize};
 use serde_json;
@@ -28,6 +23,10 @@ pub enum AuthError {
     InvalidResponse(String),
     AuthNotPresent,
     TokenNotPresent,
+    /// The user declined the authorization request
+    AccessDenied(String),
+    /// The redirect doesn't belong to this login attempt
+    StateMismatch,
 }
 
 impl From<reqwest::Error> for AuthError {
@@ -48,6 +47,8 @@ impl std::error::Error for AuthError {
             AuthError::InvalidResponse(_) => None,
             AuthError::AuthNotPresent => None,
             AuthError::TokenNotPresent => None,
+            AuthError::AccessDenied(_) => None,
+            AuthError::StateMismatch => None,
         }
     }
 }
@@ -60,13 +61,19 @@ impl std::fmt::Display for AuthError {
             AuthError::InvalidResponse(ref err) => err.fmt(f),
             AuthError::AuthNotPresent => write!(f, "Auth is not present"),
             AuthError::TokenNotPresent => write!(f, "Token is not present"),
+            AuthError::AccessDenied(ref reason) => write!(f, "Access denied: {}", reason),
+            AuthError::StateMismatch => write!(
+                f,
+                "The authorization response does not belong to this login attempt"
+            ),
         }
     }
 }
 
 const CODE_CHALLENGE_LENGTH: usize = 128;
+const STATE_LENGTH: usize = 32;
 
-#[derive(Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct OAuth {
     pub client_id: String,
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -91,15 +98,11 @@ impl OAuth {
     ) -> Self {
         OAuth {
             client_id: client_id.to_string(),
-            client_secret: if let Some(cs) = client_secret {
-                Some(cs.to_string())
-            } else {
-                None
-            },
+            client_secret: client_secret.map(|cs| cs.to_string()),
             redirect_url: redirect_url.to_string(),
             user_agent: user_agent.to_string(),
             challenge: Self::new_challenge(CODE_CHALLENGE_LENGTH),
-            state: "AUTHSTART".to_string(),
+            state: random_string(STATE_LENGTH),
             auth_code: None,
             token: None,
         }
@@ -111,16 +114,10 @@ impl OAuth {
     fn new_challenge(len: usize) -> String {
         // Check whether the len in in between the valid length for a
         // PKCE code (43 chars - 128 chars)
-        if len < 48 || len > 128 {
+        if !(48..=128).contains(&len) {
             panic!("len is not in between 48 and 128");
         }
-        let mut rng = thread_rng();
-        // needs to be url safe so we use Alphanumeric
-        let challenge: String = iter::repeat(())
-            .map(|()| rng.sample(Alphanumeric))
-            .take(len)
-            .collect();
-        challenge
+        random_string(len)
     }
 
     /// Returns user agent
@@ -159,11 +156,17 @@ impl OAuth {
     }
 
     /// Parses redirection url
+    ///
+    /// Fails with `AuthError::StateMismatch` if it wasn't meant for this flow and
+    /// with `AuthError::AccessDenied` if the user declined
     pub fn parse_redirect_query_string(&mut self, query_string: &str) -> Result<(), AuthError> {
         #[derive(Deserialize, Debug)]
         struct AuthResponse {
-            code: String,
-            state: String,
+            code: Option<String>,
+            state: Option<String>,
+            error: Option<String>,
+            error_description: Option<String>,
+            message: Option<String>,
         }
 
         let auth_response = match serde_urlencoded::from_str::<AuthResponse>(query_string) {
@@ -173,12 +176,59 @@ impl OAuth {
             }
         };
 
-        if auth_response.state != self.state {
-            return Err(AuthError::InvalidResponse("State Mismatch".to_string()));
+        if let Some(error) = auth_response.error {
+            // the state is checked too so a forged response can't pass for ours
+            if auth_response.state.is_some_and(|state| state != self.state) {
+                return Err(AuthError::StateMismatch);
+            }
+            let reason = auth_response
+                .error_description
+                .or(auth_response.message)
+                .unwrap_or_else(|| error.clone());
+            return Err(if error == "access_denied" {
+                AuthError::AccessDenied(reason)
+            } else {
+                AuthError::InvalidResponse(format!("{}: {}", error, reason))
+            });
         }
 
-        self.auth_code = Some(auth_response.code);
-        Ok(())
+        if auth_response.state.as_ref() != Some(&self.state) {
+            return Err(AuthError::StateMismatch);
+        }
+
+        match auth_response.code {
+            Some(code) => {
+                self.auth_code = Some(code);
+                Ok(())
+            }
+            None => Err(AuthError::InvalidResponse(
+                "No code in the redirect".to_string(),
+            )),
+        }
+    }
+
+    /// Parses what the user pasted after authorizing in a browser on another machine:
+    /// the full redirect url, its query string or just the code
+    pub fn parse_pasted_redirect(&mut self, input: &str) -> Result<(), AuthError> {
+        let input = input.trim();
+        if input.is_empty() {
+            return Err(AuthError::InvalidResponse("Nothing was pasted".to_string()));
+        }
+
+        if let Ok(url) = Url::parse(input) {
+            if let Some(query) = url.query() {
+                return self.parse_redirect_query_string(query);
+            }
+        }
+        let query = input.trim_start_matches('?');
+        if query.contains("code=") {
+            return self.parse_redirect_query_string(query);
+        }
+
+        // a bare code carries no state, so use our own
+        let query = serde_urlencoded::to_string([("code", input), ("state", &self.state)])
+            .map_err(|e| AuthError::InvalidResponse(e.to_string()))?;
+        self.parse_redirect_query_string(&query)
     }
 
     /// Creates a new url to get the token
@@ -343,107 +393,94 @@ impl OAuth {
             Ok(())
         }
     }
+}
 
-    pub async fn get_auth_async(config: AuthConfig) -> Result<OAuth, AuthError> {
-        if let Some(mut auth) = cache::load_cached_auth() {
-            auth.refresh_async().await?;
-            Ok(auth)
-        } else {
-            let auth = OAuth::new(
-                config.get_user_agent(),
-                config.client_id.clone(),
-                None,
-                config.get_redirect_uri(),
-            );
-
-            let url = auth.get_auth_url();
-            open(url).unwrap();
-
-            let mut auth = redirect::Server::new(config.get_user_agent(), auth)
-                .go()
-                .unwrap();
-
-            auth.get_access_token_async().await.unwrap();
-
-            cache::cache_auth(&auth);
-
-            Ok(auth)
-        }
-    }
-    pub fn get_auth(config: AuthConfig) -> Result<OAuth, AuthError> {
-        if let Some(mut auth) = cache::load_cached_auth() {
-            auth.refresh()?;
-            Ok(auth)
-        } else {
-            let auth = OAuth::new(
-                config.get_user_agent(),
-                config.client_id.clone(),
-                None,
-                config.get_redirect_uri(),
-            );
-
-            let url = auth.get_auth_url();
-            open(url).unwrap();
-
-            let mut auth = redirect::Server::new(config.get_user_agent(), auth)
-                .go()
-                .unwrap();
-
-            auth.get_access_token().unwrap();
-
-            cache::cache_auth(&auth);
-
-            Ok(auth)
-        }
-    }
+/// Random url safe string
+fn random_string(len: usize) -> String {
+    let mut rng = thread_rng();
+    // needs to be url safe so we use Alphanumeric
+    iter::repeat(())
+        .map(|()| rng.sample(Alphanumeric))
+        .take(len)
+        .collect()
 }
 
 /// use webbrowser crate to open url in browser
 pub fn open(url: Url) -> Result<Output, Error> {
-    webbrowser::open(&url.to_string())
+    webbrowser::open(url.as_ref())
 }
 
 #[cfg(test)]
 pub mod tests {
     use super::*;
+    use std::path::PathBuf;
+
+    /// Loads the token cached by mal-cli (or the one at `$MAL_TOKEN_CACHE`)
     pub fn get_auth() -> OAuth {
-        let config = AuthConfig::load().unwrap();
-        let auth = OAuth::get_auth(config).unwrap();
+        let path = match std::env::var("MAL_TOKEN_CACHE") {
+            Ok(path) => PathBuf::from(path),
+            Err(_) => dirs::home_dir()
+                .unwrap()
+                .join(".config/mal-cli/.mal_token_cache.json"),
+        };
+        let cached = std::fs::read_to_string(path).unwrap();
+        let mut auth: OAuth = serde_json::from_str(&cached).unwrap();
+        auth.refresh().unwrap();
         auth
     }
-    #[test]
-    fn test_get_auth() {
-        // Get config from file
-        let config = AuthConfig::load().unwrap();
-
-        // make auth
-        let auth = OAuth::new(
-            config.get_user_agent(),
-            config.client_id.clone(),
-            None,
-            config.get_redirect_uri(),
-        );
-
-        println!("{}", auth.redirect_url);
 
-        // create and open url
-        let url = auth.get_auth_url();
-        open(url).unwrap();
-
-        // wait for redirect
-        let mut auth = redirect::Server::new(config.get_user_agent(), auth)
-            .go()
-            .unwrap();
-
-        // get access token
-        auth.get_access_token().unwrap();
-        println!("{}", serde_json::to_string(&auth).unwrap());
+    #[test]
+    fn test_parse_pasted_redirect() {
+        let new_auth = || OAuth::new("rmal", "client_id", None, "127.0.0.1:7878");
+
+        let mut auth = new_auth();
+        let url = format!("http://127.0.0.1:7878/?code=abc123&state={}", auth.state);
+        auth.parse_pasted_redirect(&format!("  {}\n", url)).unwrap();
+        assert_eq!(auth.auth_code.as_deref(), Some("abc123"));
+
+        let mut auth = new_auth();
+        let query = format!("?code=def456&state={}", auth.state);
+        auth.parse_pasted_redirect(&query).unwrap();
+        assert_eq!(auth.auth_code.as_deref(), Some("def456"));
+
+        let mut auth = new_auth();
+        auth.parse_pasted_redirect("ghi789").unwrap();
+        assert_eq!(auth.auth_code.as_deref(), Some("ghi789"));
+
+        let mut auth = new_auth();
+        assert!(auth
+            .parse_pasted_redirect("http://127.0.0.1:7878/?code=abc123&state=other")
+            .is_err());
+        assert!(auth.parse_pasted_redirect("   ").is_err());
+        assert!(auth.auth_code.is_none());
+    }
 
-        // get refresh token
-        auth.refresh().unwrap();
-        println!("{}", serde_json::to_string(&auth).unwrap());
+    #[test]
+    fn test_redirect_errors() {
+        let mut auth = OAuth::new("rmal", "client_id", None, "127.0.0.1:7878");
+        assert_eq!(auth.state.len(), STATE_LENGTH);
+        assert_ne!(
+            auth.state,
+            OAuth::new("rmal", "client_id", None, "127.0.0.1:7878").state
+        );
 
-        cache::cache_auth(&auth);
+        let denied = format!(
+            "error=access_denied&error_description=denied+by+user&state={}",
+            auth.state
+        );
+        match auth.parse_redirect_query_string(&denied) {
+            Err(AuthError::AccessDenied(reason)) => assert_eq!(reason, "denied by user"),
+            result => panic!("{:?}", result),
+        }
+        assert!(matches!(
+            auth.parse_redirect_query_string("error=access_denied&state=other"),
+            Err(AuthError::StateMismatch)
+        ));
+        assert!(matches!(
+            auth.parse_redirect_query_string("code=abc"),
+            Err(AuthError::StateMismatch)
+        ));
+        assert!(auth.auth_code.is_none());
     }
 
     #[test]
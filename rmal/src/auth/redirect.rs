@@ -1,9 +1,26 @@
+use super::{AuthError, OAuth};
+use std::io::prelude::*;
+use std::net::{SocketAddr, TcpListener, TcpStream};
+use std::time::{Duration, Instant};
+
+/// How often the listener is polled while waiting for the redirect
+const POLL_INTERVAL: Duration = Duration::from_millis(100);
+/// How long a connected client gets to send its request
+const READ_TIMEOUT: Duration = Duration::from_secs(5);
+/// Requests larger than this are not a redirect from the authorization page
+const MAX_REQUEST_SIZE: usize = 16 * 1024;
+const MAX_HEADERS: usize = 64;
+
 /// HTTP server on host system
 /// ex. 127.0.0.1:7878
-/// blocks until one request is recieved (auth redirect) and parses it to get the code
+/// waits for the auth redirect and parses it to get the code,
+/// answering any other request (e.g. `/favicon.ico`) with a 404
+/// and a redirect that isn't ours (wrong `state`) with a 400
 pub struct Server {
-    auth: super::OAuth,
+    auth: OAuth,
     app_name: String,
+    timeout: Option<Duration>,
+    listener: Option<TcpListener>,
 }
 
 /// Error type for server methods
@@ -12,7 +29,33 @@ pub enum ServerError {
     IOError(std::io::Error),
     HTTParseError(httparse::Error),
     InvalidRequestURL(String),
-    AuthError(super::AuthError),
+    AuthError(AuthError),
+    /// No redirect arrived before the timeout
+    Timeout,
+}
+
+impl std::fmt::Display for ServerError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            ServerError::IOError(e) => e.fmt(f),
+            ServerError::HTTParseError(e) => e.fmt(f),
+            ServerError::InvalidRequestURL(url) => write!(f, "Invalid redirect url: {}", url),
+            ServerError::AuthError(e) => e.fmt(f),
+            ServerError::Timeout => write!(f, "Timed out waiting for the redirect"),
+        }
+    }
+}
+
+impl std::error::Error for ServerError {}
+
+impl From<ServerError> for AuthError {
+    fn from(e: ServerError) -> Self {
+        match e {
+            ServerError::AuthError(e) => e,
+            ServerError::Timeout => AuthError::NetworkTimeout,
+            e => AuthError::InvalidResponse(e.to_string()),
+        }
+    }
 }
 
 impl From<std::io::Error> for ServerError {
@@ -27,83 +70,286 @@ impl From<httparse::Error> for ServerError {
     }
 }
 
-impl From<super::AuthError> for ServerError {
-    fn from(e: super::AuthError) -> Self {
+impl From<AuthError> for ServerError {
+    fn from(e: AuthError) -> Self {
         ServerError::AuthError(e)
     }
 }
 
 impl Server {
     /// Create the server
-    pub fn new<A: ToString>(app_name: A, auth: super::OAuth) -> Self {
+    pub fn new<A: ToString>(app_name: A, auth: OAuth) -> Self {
         Server {
             auth,
             app_name: app_name.to_string(),
+            timeout: None,
+            listener: None,
         }
     }
 
-    /// Run the server.
-    /// Blocks until it recieves exactly one request.
-    pub fn go(self) -> Result<super::OAuth, ServerError> {
-        use std::io::prelude::*;
-        use std::net::TcpListener;
+    /// Give up with `ServerError::Timeout` if no redirect arrives in time
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Start listening on the redirect address before `go` is called
+    pub fn bind(mut self) -> Result<Self, ServerError> {
+        if self.listener.is_none() {
+            self.listener = Some(TcpListener::bind(&self.auth.redirect_url)?);
+        }
+        Ok(self)
+    }
+
+    /// Address the server listens on, once bound
+    pub fn local_addr(&self) -> Option<SocketAddr> {
+        self.listener.as_ref().and_then(|l| l.local_addr().ok())
+    }
 
-        let listener = TcpListener::bind(&self.auth.redirect_url)?;
-        let mut socket_stream = listener.incoming().next().unwrap()?;
+    /// Run the server.
+    /// Blocks until the redirect arrives (or the timeout runs out).
+    pub fn go(self) -> Result<OAuth, ServerError> {
+        let server = self.bind()?;
+        let listener = server.listener.as_ref().unwrap();
+        let deadline = server.timeout.map(|timeout| Instant::now() + timeout);
+        listener.set_nonblocking(true)?;
 
-        // read all bytes of the request
-        let mut request_bytes = Vec::new();
+        let mut auth = server.auth.clone();
         loop {
-            const BUF_SIZE: usize = 4096;
-            let mut buf: [u8; BUF_SIZE] = [0; BUF_SIZE];
-            match socket_stream.read(&mut buf) {
-                Ok(val) => {
-                    if val > 0 {
-                        request_bytes.append(&mut Vec::from(&buf[0..val]));
-                        if val < BUF_SIZE {
-                            break;
-                        }
-                    } else {
-                        break;
-                    }
+            let mut stream = accept_before(listener, deadline)?;
+
+            // a client that breaks off mid request isn't the browser we're waiting for
+            let query = match read_redirect_query(&mut stream) {
+                Ok(Some(query)) => query,
+                Ok(None) => {
+                    respond(&mut stream, "404 Not Found", &page("Not Found", ""));
+                    continue;
                 }
-                Err(e) => panic!("{}", e),
+                Err(_) => continue,
             };
-        }
 
-        let mut headers = [httparse::EMPTY_HEADER; 16];
-        let mut parsed_request = httparse::Request::new(&mut headers);
-
-        parsed_request.parse(&request_bytes)?;
+            match auth.parse_redirect_query_string(&query) {
+                Ok(()) => {
+                    let title = format!("{} Authorized", server.app_name);
+                    let body = "You can close this tab and go back to the terminal.";
+                    respond(&mut stream, "200 OK", &page(&title, body));
+                    return Ok(auth);
+                }
+                Err(e) => {
+                    let title = format!("{} Authorization Failed", server.app_name);
+                    respond(
+                        &mut stream,
+                        "400 Bad Request",
+                        &page(&title, &e.to_string()),
+                    );
+                    // anyone can send us a request, only one with our state ends the login
+                    if has_state(&query, &auth.state) {
+                        return Err(e.into());
+                    }
+                }
+            }
+        }
+    }
+}
 
-        let raw_url = if let Some(path) = parsed_request.path {
-            format!("http://{}{}", self.auth.redirect_url, path)
-        } else {
-            return Err(ServerError::InvalidRequestURL("".to_string()));
-        };
+/// Waits for the next connection, until `deadline` if there is one
+fn accept_before(
+    listener: &TcpListener,
+    deadline: Option<Instant>,
+) -> Result<TcpStream, ServerError> {
+    loop {
+        match listener.accept() {
+            Ok((stream, _)) => {
+                stream.set_nonblocking(false)?;
+                return Ok(stream);
+            }
+            Err(e) if e.kind() == std::io::ErrorKind::WouldBlock => {
+                if deadline.is_some_and(|deadline| Instant::now() >= deadline) {
+                    return Err(ServerError::Timeout);
+                }
+                std::thread::sleep(POLL_INTERVAL);
+            }
+            Err(e) => return Err(e.into()),
+        }
+    }
+}
 
-        let parsed_url = match url::Url::parse(&raw_url) {
-            Ok(url) => url,
-            Err(_) => return Err(ServerError::InvalidRequestURL(raw_url)),
-        };
+/// Reads a request and returns its query string if it carries an oauth2 response
+fn read_redirect_query(stream: &mut TcpStream) -> Result<Option<String>, ServerError> {
+    stream.set_read_timeout(Some(READ_TIMEOUT))?;
 
-        let query = if let Some(query) = parsed_url.query() {
-            query
-        } else {
+    // only the request line and headers matter, a redirect has no body
+    let mut request_bytes = Vec::new();
+    let mut buf = [0; 4096];
+    while !request_bytes.windows(4).any(|w| w == b"\r\n\r\n") {
+        let read = stream.read(&mut buf)?;
+        if read == 0 {
+            break;
+        }
+        request_bytes.extend_from_slice(&buf[..read]);
+        if request_bytes.len() > MAX_REQUEST_SIZE {
             return Err(ServerError::InvalidRequestURL(
-                "No query string".to_string(),
+                "Request too large".to_string(),
             ));
-        };
+        }
+    }
 
-        let mut ret_auth = self.auth;
+    let mut headers = [httparse::EMPTY_HEADER; MAX_HEADERS];
+    let mut request = httparse::Request::new(&mut headers);
+    if request.parse(&request_bytes)?.is_partial() {
+        return Err(ServerError::InvalidRequestURL(
+            "Incomplete request".to_string(),
+        ));
+    }
 
-        ret_auth.parse_redirect_query_string(query)?;
+    let path = request
+        .path
+        .ok_or_else(|| ServerError::InvalidRequestURL("".to_string()))?;
+    let url = url::Url::parse("http://localhost")
+        .and_then(|base| base.join(path))
+        .map_err(|_| ServerError::InvalidRequestURL(path.to_string()))?;
+
+    let is_oauth_response = url
+        .query_pairs()
+        .any(|(key, _)| key == "code" || key == "error");
+    Ok(url.query().filter(|_| is_oauth_response).map(String::from))
+}
+
+/// Whether `query` carries `state`, i.e. it answers our authorization request
+fn has_state(query: &str, state: &str) -> bool {
+    url::form_urlencoded::parse(query.as_bytes())
+        .any(|(key, value)| key == "state" && value == state)
+}
+
+/// Sends a complete html response, the browser closing early isn't our problem
+fn respond(stream: &mut TcpStream, status: &str, html: &str) {
+    let response = format!(
+        "HTTP/1.1 {}\r\nContent-Type: text/html; charset=utf-8\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        status,
+        html.len(),
+        html
+    );
+    let _ = stream.write_all(response.as_bytes());
+    let _ = stream.flush();
+}
 
-        // return a minimal http response to the browser
-        let r = format!("HTTP/1.1 200 OK\r\n\r\n<html><head><title>{} Authorized</title></head><body>{} Authorized</body></html>", self.app_name, self.app_name);
-        socket_stream.write(r.as_bytes())?;
-        socket_stream.flush()?;
+fn page(title: &str, message: &str) -> String {
+    format!(
+        "<!DOCTYPE html>\n<html><head><meta charset=\"utf-8\"><title>{title}</title></head>\
+         <body style=\"font-family: sans-serif; text-align: center; margin-top: 4em\">\
+         <h1>{title}</h1><p>{message}</p></body></html>",
+        title = escape_html(title),
+        message = escape_html(message)
+    )
+}
+
+fn escape_html(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::thread;
+
+    /// Sends `path` to the server and returns the raw response
+    fn get(addr: SocketAddr, path: &str) -> String {
+        let mut stream = TcpStream::connect(addr).unwrap();
+        write!(stream, "GET {} HTTP/1.1\r\nHost: {}\r\n\r\n", path, addr).unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).unwrap();
+        response
+    }
+
+    fn bound_server() -> (Server, SocketAddr, String) {
+        let auth = OAuth::new("rmal", "client_id", None, "127.0.0.1:0");
+        let state = auth.state.clone();
+        let server = Server::new("rmal", auth)
+            .timeout(Duration::from_secs(10))
+            .bind()
+            .unwrap();
+        let addr = server.local_addr().unwrap();
+        (server, addr, state)
+    }
+
+    #[test]
+    fn test_redirect_after_stray_requests() {
+        let (server, addr, state) = bound_server();
+        let handle = thread::spawn(move || server.go());
+
+        // a client that connects and hangs up without a request
+        drop(TcpStream::connect(addr).unwrap());
+        assert!(get(addr, "/favicon.ico").starts_with("HTTP/1.1 404"));
+        assert!(get(addr, "/?foo=bar").starts_with("HTTP/1.1 404"));
+
+        let response = get(addr, &format!("/?code=abc123&state={}", state));
+        assert!(response.starts_with("HTTP/1.1 200 OK"));
+        assert!(response.contains("rmal Authorized"));
+
+        let auth = handle.join().unwrap().unwrap();
+        assert_eq!(auth.auth_code.as_deref(), Some("abc123"));
+    }
+
+    #[test]
+    fn test_access_denied() {
+        let (server, addr, state) = bound_server();
+        let handle = thread::spawn(move || server.go());
+
+        let response = get(
+            addr,
+            &format!(
+                "/?error=access_denied&error_description=The+user+denied+the+request&state={}",
+                state
+            ),
+        );
+        assert!(response.starts_with("HTTP/1.1 400"));
+        assert!(response.contains("rmal Authorization Failed"));
+        assert!(response.contains("The user denied the request"));
+
+        let result = handle.join().unwrap();
+        assert!(matches!(
+            result,
+            Err(ServerError::AuthError(AuthError::AccessDenied(_)))
+        ));
+    }
+
+    #[test]
+    fn test_state_mismatch() {
+        let (server, addr, state) = bound_server();
+        let handle = thread::spawn(move || server.go());
+
+        // forged redirects are turned away without ending the login
+        let response = get(addr, "/?code=forged&state=AUTHSTART");
+        assert!(response.starts_with("HTTP/1.1 400"));
+        assert!(get(addr, "/?code=forged").starts_with("HTTP/1.1 400"));
+        assert!(get(addr, "/?error=access_denied").starts_with("HTTP/1.1 400"));
+
+        let response = get(addr, &format!("/?code=abc123&state={}", state));
+        assert!(response.starts_with("HTTP/1.1 200 OK"));
+
+        let auth = handle.join().unwrap().unwrap();
+        assert_eq!(auth.auth_code.as_deref(), Some("abc123"));
+    }
+
+    #[test]
+    fn test_timeout() {
+        let auth = OAuth::new("rmal", "client_id", None, "127.0.0.1:0");
+        let start = Instant::now();
+        let result = Server::new("rmal", auth)
+            .timeout(Duration::from_millis(300))
+            .go();
+        assert!(matches!(result, Err(ServerError::Timeout)));
+        assert!(start.elapsed() >= Duration::from_millis(300));
+    }
 
-        Ok(ret_auth)
+    #[test]
+    fn test_escape_html() {
+        assert_eq!(
+            escape_html("<script>\"a\" & b</script>"),
+            "&lt;script&gt;&quot;a&quot; &amp; b&lt;/script&gt;"
+        );
     }
 }
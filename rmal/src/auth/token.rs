@@ -14,6 +14,25 @@ pub struct Token {
     pub refresh_token: String,
 }
 
+impl Token {
+    /// The scopes granted to the access token, read from its jwt claims.
+    /// Returns `None` if the access token isn't a jwt.
+    pub fn scopes(&self) -> Option<Vec<String>> {
+        #[derive(Deserialize)]
+        struct Claims {
+            #[serde(default)]
+            scopes: Vec<String>,
+        }
+
+        let payload = self.access_token.split('.').nth(1)?;
+        let payload =
+            base64::decode_config(payload.trim_end_matches('='), base64::URL_SAFE_NO_PAD).ok()?;
+        serde_json::from_slice::<Claims>(&payload)
+            .ok()
+            .map(|claims| claims.scopes)
+    }
+}
+
 /// Holds token and timestamp
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TokenWrapper {
@@ -56,10 +75,48 @@ impl TokenWrapper {
     }
     /// Get the time that the token will expire (None if already expired)
     pub fn expire_time(&self) -> Option<time::SystemTime> {
-        if let Some(secs) = self.expires_in_secs() {
-            Some(time::SystemTime::now() + time::Duration::from_secs(secs))
-        } else {
-            None
+        self.expires_in_secs()
+            .map(|secs| time::SystemTime::now() + time::Duration::from_secs(secs))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn token(access_token: &str) -> Token {
+        Token {
+            token_type: "Bearer".to_string(),
+            expires_in: 2678400,
+            access_token: access_token.to_string(),
+            refresh_token: "refresh".to_string(),
         }
     }
+
+    #[test]
+    fn test_scopes() {
+        let claims = base64::encode_config(
+            r#"{"aud":"client_id","sub":"1234","scopes":["write:users"]}"#,
+            base64::URL_SAFE_NO_PAD,
+        );
+        let jwt = format!("eyJ0eXAiOiJKV1QifQ.{}.signature", claims);
+        assert_eq!(token(&jwt).scopes(), Some(vec!["write:users".to_string()]));
+
+        let claims = base64::encode_config(r#"{"sub":"1234"}"#, base64::URL_SAFE_NO_PAD);
+        let jwt = format!("eyJ0eXAiOiJKV1QifQ.{}.signature", claims);
+        assert_eq!(token(&jwt).scopes(), Some(vec![]));
+
+        assert_eq!(token("not a jwt").scopes(), None);
+    }
+
+    #[test]
+    fn test_expire_time() {
+        let mut wrapper = TokenWrapper::new(token("access"));
+        assert!(!wrapper.expired());
+        assert!(wrapper.expire_time().unwrap() > time::SystemTime::now());
+
+        wrapper.generate_time -= wrapper.token.expires_in;
+        assert!(wrapper.expired());
+        assert!(wrapper.expire_time().is_none());
+    }
 }
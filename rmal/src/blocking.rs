@@ -0,0 +1,158 @@
+use crate::api::{self, model::*, ApiResult};
+use crate::auth::AuthError;
+use serde::de::DeserializeOwned;
+use std::fmt::Debug;
+use std::future::Future;
+use std::sync::Mutex;
+use tokio::runtime::{Builder, Runtime};
+
+/// Blocking MAL api client
+///
+/// Runs the async `crate::MAL` on its own single threaded runtime, so it can be
+/// used from code that isn't async. Don't call it from inside another runtime.
+pub struct MAL {
+    inner: crate::MAL,
+    runtime: Mutex<Runtime>,
+}
+
+impl MAL {
+    pub fn new(inner: crate::MAL) -> std::io::Result<Self> {
+        let runtime = Builder::new().basic_scheduler().enable_all().build()?;
+        Ok(Self {
+            inner,
+            runtime: Mutex::new(runtime),
+        })
+    }
+
+    pub fn inner(&self) -> &crate::MAL {
+        &self.inner
+    }
+
+    fn block_on<F: Future>(&self, future: F) -> F::Output {
+        self.runtime.lock().unwrap().block_on(future)
+    }
+
+    /// Refresh the access token if it has expired
+    pub fn refresh(&mut self) -> Result<(), AuthError> {
+        let runtime = self.runtime.get_mut().unwrap();
+        runtime.block_on(self.inner.refresh())
+    }
+
+    /// Walk every page after `first` and collect the results
+    pub fn collect_pages<D>(
+        &self,
+        first: PageableData<Vec<D>>,
+        max_items: Option<usize>,
+    ) -> ApiResult<Vec<D>>
+    where
+        D: DeserializeOwned + Clone + Debug,
+    {
+        self.block_on(api::collect_pages(first, max_items, self.inner.auth()))
+    }
+
+    pub fn search_anime<T: ToString>(&self, q: T) -> ApiResult<Page<Anime>> {
+        self.block_on(self.inner.search_anime(q))
+    }
+
+    pub fn anime(&self, anime_id: u64) -> ApiResult<Anime> {
+        self.block_on(self.inner.anime(anime_id))
+    }
+
+    pub fn anime_ranking(
+        &self,
+        ranking_type: AnimeRankingType,
+    ) -> ApiResult<Ranking<RankingAnimePair>> {
+        self.block_on(self.inner.anime_ranking(ranking_type))
+    }
+
+    pub fn seasonal_anime(
+        &self,
+        season: &AnimeSeason,
+        sort: Option<SortStyle>,
+    ) -> ApiResult<Page<Anime>> {
+        self.block_on(self.inner.seasonal_anime(season, sort))
+    }
+
+    pub fn suggested_anime(&self) -> ApiResult<Page<Anime>> {
+        self.block_on(self.inner.suggested_anime())
+    }
+
+    pub fn user_anime_list<U: ToString>(
+        &self,
+        user: U,
+        status: Option<UserStatus>,
+        sort: Option<SortStyle>,
+    ) -> ApiResult<Page<Anime>> {
+        self.block_on(self.inner.user_anime_list(user, status, sort))
+    }
+
+    pub fn update_anime_list_status(
+        &self,
+        anime_id: u64,
+        update: &api::UpdateUserAnimeListStatusQuery,
+    ) -> ApiResult<UserAnimeListStatus> {
+        self.block_on(self.inner.update_anime_list_status(anime_id, update))
+    }
+
+    pub fn delete_anime_from_list(&self, anime_id: u64) -> ApiResult<()> {
+        self.block_on(self.inner.delete_anime_from_list(anime_id))
+    }
+
+    pub fn search_manga<T: ToString>(&self, q: T) -> ApiResult<Page<Manga>> {
+        self.block_on(self.inner.search_manga(q))
+    }
+
+    pub fn manga(&self, manga_id: u64) -> ApiResult<Manga> {
+        self.block_on(self.inner.manga(manga_id))
+    }
+
+    pub fn manga_ranking(
+        &self,
+        ranking_type: MangaRankingType,
+    ) -> ApiResult<Ranking<RankingMangaPair>> {
+        self.block_on(self.inner.manga_ranking(ranking_type))
+    }
+
+    pub fn user_manga_list<U: ToString>(
+        &self,
+        user: U,
+        status: Option<UserStatus>,
+        sort: Option<SortStyle>,
+    ) -> ApiResult<Page<Manga>> {
+        self.block_on(self.inner.user_manga_list(user, status, sort))
+    }
+
+    pub fn update_manga_list_status(
+        &self,
+        manga_id: u64,
+        update: &api::UpdateUserMangaStatus,
+    ) -> ApiResult<UserMangaListStatus> {
+        self.block_on(self.inner.update_manga_list_status(manga_id, update))
+    }
+
+    pub fn delete_manga_from_list(&self, manga_id: u64) -> ApiResult<()> {
+        self.block_on(self.inner.delete_manga_from_list(manga_id))
+    }
+
+    pub fn user_information<U: ToString>(&self, user: U) -> ApiResult<UserInfo> {
+        self.block_on(self.inner.user_information(user))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_blocking_search_manga() {
+        let mal = crate::MAL::builder()
+            .auth(crate::auth::tests::get_auth())
+            .limit(4)
+            .build()
+            .unwrap();
+        let mal = MAL::new(mal).unwrap();
+        let result = mal.search_manga("Grand Blue").unwrap();
+        println!("{:#?}", result);
+        assert!(!result.data.is_empty());
+    }
+}
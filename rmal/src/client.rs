@@ -1,97 +1,289 @@
-////! MAL api endpoints
-//// 3rd Party crates
-//use log::{error, trace};
-//use reqwest::header::{HeaderMap, AUTHORIZATION, CONTENT_TYPE};
-//use reqwest::{Client, Method, Response, StatusCode};
-//use serde::Deserialize;
-//use serde_json::map::Map;
-//use serde_json::{json, Value};
-//use thiserror::Error;
-
-//// Crate
-//use super::oauth2::MALClientCredentials;
-
-//pub const API_URL: &str = "https://api.myanimelist.net/v2";
-
-///// All errors returned from `rmal` client.
-//#[derive(Debug, Error)]
-//pub enum ClientError {
-//    #[error("request unauthorized")]
-//    Unauthorized,
-//    #[error("exceeded request limit")]
-//    RateLimited(Option<usize>),
-//    #[error("MAL error: {0}")]
-//    Api(#[from] ApiError),
-//    #[error("request error: {0}")]
-//    ParseJSON(#[from] serde_json::Error),
-//    #[error("request error: {0}")]
-//    Request(#[from] reqwest::Error),
-//    #[error("status code: {0}")]
-//    StatusCode(StatusCode),
-//}
-
-//impl ClientError {
-//    async fn from_response(response: Response) -> Self {
-//        match response.status() {
-//            StatusCode::UNAUTHORIZED => Self::Unauthorized,
-//            StatusCode::TOO_MANY_REQUESTS => Self::RateLimited(
-//                response
-//                    .headers()
-//                    .get(reqwest::header::RETRY_AFTER)
-//                    .and_then(|header| header.to_str().ok())
-//                    .and_then(|duration| duration.parse().ok()),
-//            ),
-//            status @ StatusCode::FORBIDDEN | status @ StatusCode::NOT_FOUND => response
-//                .json::<ApiError>()
-//                .await
-//                .map(Into::into)
-//                .unwrap_or_else(|_| status.into()),
-//        }
-//    }
-//}
-
-//impl From<StatusCode> for ClientError {
-//    fn from(code: StatusCode) -> Self {
-//        Self::StatusCode(code)
-//    }
-//}
-
-//type ClientResult<T> = Result<T, ClientError>;
-
-///// MAL API object
-//#[derive(Debug, Clone)]
-//pub struct MAL {
-//    client: Client,
-//    pub access_token: Option<String>,
-//    pub client_credentials_manager: Option<MALClientCredentials>,
-//}
-
-//impl MAL {
-//    pub fn default() -> Self {
-//        Self {
-//            client: Client::new(),
-//            access_token: None,
-//            client_credentials_manager: None,
-//        }
-//    }
-
-//    pub fn access_token(mut self, access_token: &str) -> MAL {
-//        self.access_token = Some(access_token.to_owned());
-//        self
-//    }
-
-//    pub fn client_credentials_manager(
-//        mut self,
-//        client_credentials_manager: MALClientCredentials,
-//    ) -> MAL {
-//        self.client_credentials_manager = Some(client_credentials_manager);
-//        self
-//    }
-
-//    pub fn build(self) -> Self {
-//        if self.access_token.is_none() && self.client_credentials_manager.is_none() {
-//            panic!("access token and credentials are not found!");
-//        }
-//        self
-//    }
-//}
+use crate::api::{self, model::*, ApiResult, Error};
+use crate::auth::{AuthError, OAuth};
+use futures::stream::Stream;
+use serde::de::DeserializeOwned;
+use std::fmt::Debug;
+
+const DEFAULT_LIMIT: u64 = 100;
+
+/// MAL api client
+///
+/// Wraps the endpoint functions in `api` and fills in the query options
+/// (`limit`, `nsfw` and `fields`) configured on the builder.
+#[derive(Clone, Debug)]
+pub struct MAL {
+    auth: OAuth,
+    limit: u64,
+    nsfw: bool,
+    fields: Option<String>,
+}
+
+/// Builder for `MAL`
+#[derive(Clone, Debug)]
+pub struct MALBuilder {
+    auth: Option<OAuth>,
+    limit: u64,
+    nsfw: bool,
+    fields: Option<String>,
+}
+
+impl Default for MALBuilder {
+    fn default() -> Self {
+        Self {
+            auth: None,
+            limit: DEFAULT_LIMIT,
+            nsfw: false,
+            fields: None,
+        }
+    }
+}
+
+impl MALBuilder {
+    /// Authorized oauth2 state used for every request
+    pub fn auth(mut self, auth: OAuth) -> Self {
+        self.auth = Some(auth);
+        self
+    }
+
+    /// Number of results per page
+    pub fn limit(mut self, limit: u64) -> Self {
+        self.limit = limit;
+        self
+    }
+
+    /// Include nsfw results
+    pub fn nsfw(mut self, nsfw: bool) -> Self {
+        self.nsfw = nsfw;
+        self
+    }
+
+    /// Fields requested by default (MAL returns `id`, `title` and `main_picture` otherwise)
+    pub fn fields<T: ToString>(mut self, fields: T) -> Self {
+        self.fields = Some(fields.to_string());
+        self
+    }
+
+    pub fn build(self) -> ApiResult<MAL> {
+        match self.auth {
+            Some(auth) if auth.token().is_some() => Ok(MAL {
+                auth,
+                limit: self.limit,
+                nsfw: self.nsfw,
+                fields: self.fields,
+            }),
+            _ => Err(Error::NoAuth),
+        }
+    }
+}
+
+impl MAL {
+    pub fn builder() -> MALBuilder {
+        MALBuilder::default()
+    }
+
+    pub fn auth(&self) -> &OAuth {
+        &self.auth
+    }
+
+    /// Refresh the access token if it has expired
+    pub async fn refresh(&mut self) -> Result<(), AuthError> {
+        self.auth.refresh_async().await
+    }
+
+    /// Lazily walk every page after `first`
+    pub fn paginate<'a, D>(
+        &'a self,
+        first: PageableData<Vec<D>>,
+        max_items: Option<usize>,
+    ) -> impl Stream<Item = ApiResult<D>> + 'a
+    where
+        D: DeserializeOwned + Clone + Debug + 'a,
+    {
+        api::paginate(first, max_items, &self.auth)
+    }
+
+    pub async fn search_anime<T: ToString>(&self, q: T) -> ApiResult<Page<Anime>> {
+        let query = api::GetAnimeListQuery {
+            q: q.to_string(),
+            limit: self.limit,
+            offset: 0,
+            nsfw: self.nsfw,
+            fields: self.fields.clone(),
+        };
+        api::get_anime_list(&query, &self.auth).await
+    }
+
+    pub async fn anime(&self, anime_id: u64) -> ApiResult<Anime> {
+        let query = api::GetAnimeDetailQuery {
+            fields: self.fields.clone(),
+            nsfw: self.nsfw,
+        };
+        api::get_anime_details(anime_id, &query, &self.auth).await
+    }
+
+    pub async fn anime_ranking(
+        &self,
+        ranking_type: AnimeRankingType,
+    ) -> ApiResult<Ranking<RankingAnimePair>> {
+        let query = api::GetAnimeRankingQuery {
+            ranking_type,
+            limit: self.limit,
+            offset: 0,
+            nsfw: self.nsfw,
+            fields: self.fields.clone(),
+        };
+        api::get_anime_ranking(&query, &self.auth).await
+    }
+
+    pub async fn seasonal_anime(
+        &self,
+        season: &AnimeSeason,
+        sort: Option<SortStyle>,
+    ) -> ApiResult<Page<Anime>> {
+        let query = api::GetSeasonalAnimeQuery {
+            sort,
+            limit: self.limit,
+            offset: 0,
+            nsfw: self.nsfw,
+            fields: self.fields.clone(),
+        };
+        api::get_seasonal_anime(season, &query, &self.auth).await
+    }
+
+    pub async fn suggested_anime(&self) -> ApiResult<Page<Anime>> {
+        let query = api::GetSuggestedAnimeQuery {
+            limit: self.limit,
+            offset: 0,
+            nsfw: self.nsfw,
+            fields: self.fields.clone(),
+        };
+        api::get_suggested_anime(&query, &self.auth).await
+    }
+
+    pub async fn user_anime_list<U: ToString>(
+        &self,
+        user: U,
+        status: Option<UserStatus>,
+        sort: Option<SortStyle>,
+    ) -> ApiResult<Page<Anime>> {
+        let query = api::GetUserAnimeListQuery {
+            fields: self.fields.clone(),
+            status,
+            sort,
+            limit: self.limit,
+            offset: 0,
+            nsfw: self.nsfw,
+        };
+        api::get_user_anime_list(user, &query, &self.auth).await
+    }
+
+    pub async fn update_anime_list_status(
+        &self,
+        anime_id: u64,
+        update: &api::UpdateUserAnimeListStatusQuery,
+    ) -> ApiResult<UserAnimeListStatus> {
+        api::update_anime_list_status(anime_id, update, &self.auth).await
+    }
+
+    pub async fn delete_anime_from_list(&self, anime_id: u64) -> ApiResult<()> {
+        api::delete_anime_from_list(anime_id, &self.auth).await
+    }
+
+    pub async fn search_manga<T: ToString>(&self, q: T) -> ApiResult<Page<Manga>> {
+        let query = api::GetMangaListQuery {
+            q: q.to_string(),
+            limit: self.limit,
+            offset: 0,
+            nsfw: self.nsfw,
+            fields: self.fields.clone(),
+        };
+        api::get_manga_list(&query, &self.auth).await
+    }
+
+    pub async fn manga(&self, manga_id: u64) -> ApiResult<Manga> {
+        let query = api::GetMangaDetailQuery {
+            fields: self.fields.clone(),
+            nsfw: self.nsfw,
+        };
+        api::get_manga_details(manga_id, &query, &self.auth).await
+    }
+
+    pub async fn manga_ranking(
+        &self,
+        ranking_type: MangaRankingType,
+    ) -> ApiResult<Ranking<RankingMangaPair>> {
+        let query = api::GetMangaRankingQuery {
+            ranking_type,
+            limit: self.limit,
+            offset: 0,
+            nsfw: self.nsfw,
+            fields: self.fields.clone(),
+        };
+        api::get_manga_ranking(&query, &self.auth).await
+    }
+
+    pub async fn user_manga_list<U: ToString>(
+        &self,
+        user: U,
+        status: Option<UserStatus>,
+        sort: Option<SortStyle>,
+    ) -> ApiResult<Page<Manga>> {
+        let query = api::GetUserMangaListQuery {
+            fields: self.fields.clone(),
+            status,
+            sort,
+            limit: self.limit,
+            offset: 0,
+            nsfw: self.nsfw,
+        };
+        api::get_user_manga_list(user, &query, &self.auth).await
+    }
+
+    pub async fn update_manga_list_status(
+        &self,
+        manga_id: u64,
+        update: &api::UpdateUserMangaStatus,
+    ) -> ApiResult<UserMangaListStatus> {
+        api::update_manga_list_status(manga_id, update, &self.auth).await
+    }
+
+    pub async fn delete_manga_from_list(&self, manga_id: u64) -> ApiResult<()> {
+        api::delete_manga_from_list(manga_id, &self.auth).await
+    }
+
+    pub async fn user_information<U: ToString>(&self, user: U) -> ApiResult<UserInfo> {
+        let query = api::GetUserInformationQuery {
+            fields: Some(ALL_USER_FIELDS.to_string()),
+        };
+        api::get_my_user_information(user, &query, &self.auth).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_build_without_auth() {
+        let result = MAL::builder().build();
+        assert!(matches!(result, Err(Error::NoAuth)));
+
+        // an oauth flow that hasn't been completed has no token either
+        let auth = OAuth::new("rmal", "client_id", None, "127.0.0.1:7878");
+        let result = MAL::builder().auth(auth).build();
+        assert!(matches!(result, Err(Error::NoAuth)));
+    }
+
+    #[tokio::test]
+    async fn test_search_anime() {
+        let mal = MAL::builder()
+            .auth(crate::auth::tests::get_auth())
+            .limit(4)
+            .fields(ALL_ANIME_AND_MANGA_FIELDS)
+            .build()
+            .unwrap();
+        let result = mal.search_anime("Code Geass").await.unwrap();
+        println!("{:#?}", result);
+        assert!(!result.data.is_empty());
+    }
+}
@@ -1,11 +1,23 @@
-pub const banner: &str = "
- __  __    _    _           ____ _     ___ 
-|  \\/  |  / \\  | |         / ___| |   |_ _|
-| |\\/| | / _ \\ | |   _____| |   | |    | | 
-| |  | |/ ___ \\| |__|_____| |___| |___ | | 
-|_|  |_/_/   \\_\\_____|     \\____|_____|___|
-";
+//! Client for the myanimelist.net v2 api
+//!
+//! ```no_run
+//! # async fn run(auth: rmal::auth::OAuth) -> Result<(), rmal::api::Error> {
+//! let mal = rmal::MAL::builder().auth(auth).nsfw(false).build()?;
+//! let results = mal.search_anime("Cowboy Bebop").await?;
+//! # Ok(())
+//! # }
+//! ```
 
+/// API request functions
+pub mod api;
+
+/// Authorization
+pub mod auth;
+
+/// Async client
 pub mod client;
-pub mod oauth2;
-pub mod util;
+
+/// Blocking client
+pub mod blocking;
+
+pub use client::{MALBuilder, MAL};
@@ -1,9 +1,21 @@
 use crate::api::model::*;
-use crate::config::AppConfig;
-use crate::network::IoEvent;
-use std::sync::mpsc::Sender;
+use crate::backlog::{self, Pace};
+use crate::batch::{BatchAction, BatchProgress, ANIME_STATUSES, MANGA_STATUSES, PRIORITIES};
+use crate::config::{AppConfig, TitleLanguage};
+use crate::cover::{Cover, Protocol};
+use crate::network::{IoEvent, IoResponse, RequestId};
+use crate::schedule::{self, Airing, CatchUp, Zone};
+use crate::search::{fuzzy, SavedSearch, SearchQuery, SearchScope, Searches, MIN_QUERY_LENGTH};
+use crate::ui::{ColumnId, Sort, TableId};
+use chrono::{DateTime, Datelike, Utc};
+use std::cmp::Ordering;
+use std::collections::{HashMap, HashSet};
+use std::convert::TryInto;
+use std::mem::Discriminant;
+use std::time::{Duration, Instant};
+use tokio::sync::mpsc::UnboundedSender;
 use tui::layout::Rect;
-use tui::widgets::ListItem;
+use unicode_width::UnicodeWidthStr;
 
 const DEFAULT_ROUTE: Route = Route {
     id: RouteId::Home,
@@ -15,7 +27,17 @@ pub const ANIME_OPTIONS: [&str; 4] = ["Seasonal", "Ranking", "Suggested", "Searc
 
 pub const MANGA_OPTIONS: [&str; 2] = ["Ranking", "Search"];
 
-pub const USER_OPTIONS: [&str; 3] = ["Stats", "AnimeList", "MangaList"];
+pub const USER_OPTIONS: [&str; 6] = [
+    "Stats",
+    "AnimeList",
+    "MangaList",
+    "Schedule",
+    "CatchUp",
+    "Backlog",
+];
+
+/// How long typing has to pause before suggestions are fetched
+pub const SUGGESTION_DELAY: Duration = Duration::from_millis(300);
 
 #[derive(Clone, PartialEq, Debug)]
 pub enum RouteId {
@@ -24,6 +46,15 @@ pub enum RouteId {
     Seasonal,
     Recommendations,
     Ranking,
+    AnimeDetails,
+    MangaDetails,
+    AnimeRanking,
+    MangaRanking,
+    AnimeList,
+    MangaList,
+    Schedule,
+    CatchUp,
+    Backlog,
     Error,
 }
 
@@ -39,6 +70,18 @@ pub enum ActiveBlock {
     Anime,
     Manga,
     User,
+    ProfileSwitcher,
+    Details,
+    SearchHistory,
+    SaveSearch,
+    SavedSearches,
+    /// A ranking or user list, see `App::focused_view`
+    Entries,
+    Schedule,
+    CatchUp,
+    Backlog,
+    /// The popup for what to do to the marked entries
+    BatchEdit,
 }
 
 #[derive(Debug)]
@@ -57,6 +100,306 @@ pub struct SearchResult {
     pub selected_block: SearchResultBlock,
 }
 
+/// Top matches shown under the search box while typing
+#[derive(Default)]
+pub struct Suggestions {
+    /// What was typed, the suggestions shown are for it
+    pub query: String,
+    pub anime: Vec<Anime>,
+    pub manga: Vec<Manga>,
+    /// Index into the anime followed by the manga
+    pub selected_index: Option<usize>,
+    /// When the query last changed, it's sent once typing pauses
+    changed_at: Option<Instant>,
+}
+
+/// A suggestion to open
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Suggestion {
+    Anime(u64),
+    Manga(u64),
+}
+
+impl Suggestions {
+    pub fn len(&self) -> usize {
+        self.anime.len() + self.manga.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// When the query should be sent, if it hasn't been yet
+    pub fn deadline(&self) -> Option<Instant> {
+        self.changed_at
+            .map(|changed_at| changed_at + SUGGESTION_DELAY)
+    }
+
+    pub fn selected(&self) -> Option<Suggestion> {
+        let index = self.selected_index?;
+        match self.anime.get(index) {
+            Some(anime) => Some(Suggestion::Anime(anime.id)),
+            None => self
+                .manga
+                .get(index - self.anime.len())
+                .map(|manga| Suggestion::Manga(manga.id)),
+        }
+    }
+
+    pub fn clear(&mut self) {
+        *self = Self::default();
+    }
+}
+
+/// Past searches matching what's typed in the history popup
+#[derive(Default)]
+pub struct HistoryPopup {
+    pub filter: String,
+    /// Indices into the history, best match first
+    pub matches: Vec<usize>,
+    pub selected_index: usize,
+}
+
+/// Name and scope of the search about to be saved
+#[derive(Default)]
+pub struct SavePrompt {
+    pub name: String,
+    pub scope: SearchScope,
+}
+
+/// Show under the cursor in the airing schedule
+#[derive(Debug, Default)]
+pub struct ScheduleCursor {
+    /// Day of the week from Monday, today's if `None`
+    pub day: Option<usize>,
+    pub index: usize,
+}
+
+/// The order the user put the backlog in, the shows left out of it follow
+#[derive(Debug, Default)]
+pub struct BacklogState {
+    pub order: Vec<u64>,
+    pub selected_index: usize,
+}
+
+/// What the batch popup lets the user do
+pub const BATCH_ACTIONS: [&str; 6] = [
+    "Set status",
+    "Set score",
+    "Add tags",
+    "Remove tags",
+    "Set priority",
+    "Delete",
+];
+
+/// Where the batch popup is at
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum BatchStep {
+    /// Picking one of `BATCH_ACTIONS`
+    #[default]
+    Action,
+    Status,
+    Score,
+    AddTags,
+    RemoveTags,
+    Priority,
+    ConfirmDelete,
+    /// The batch was sent, showing how it's going
+    Progress,
+}
+
+/// Choosing what to do to the marked entries of a user list
+#[derive(Debug, Default)]
+pub struct BatchMenu {
+    pub view: Option<EntryView>,
+    pub step: BatchStep,
+    pub selected_index: usize,
+    /// What's typed for a score or tags
+    pub input: String,
+}
+
+impl BatchMenu {
+    /// What can be picked at this step, nothing if it's typed in
+    pub fn options(&self) -> &'static [&'static str] {
+        match self.step {
+            BatchStep::Action => &BATCH_ACTIONS,
+            BatchStep::Status if self.view == Some(EntryView::MangaList) => &MANGA_STATUSES,
+            BatchStep::Status => &ANIME_STATUSES,
+            BatchStep::Priority => &PRIORITIES,
+            _ => &[],
+        }
+    }
+}
+
+/// A batch action sent to the network thread
+#[derive(Debug)]
+pub struct Batch {
+    pub id: RequestId,
+    pub view: EntryView,
+    pub progress: BatchProgress,
+}
+
+/// The lists of anime or manga the main view shows
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum EntryView {
+    AnimeSearch,
+    MangaSearch,
+    AnimeRanking,
+    MangaRanking,
+    AnimeList,
+    MangaList,
+}
+
+impl EntryView {
+    /// The table configuring the view's columns
+    pub fn table_id(self) -> TableId {
+        match self {
+            EntryView::AnimeSearch | EntryView::AnimeRanking => TableId::Anime,
+            EntryView::MangaSearch | EntryView::MangaRanking => TableId::Manga,
+            EntryView::AnimeList => TableId::AnimeList,
+            EntryView::MangaList => TableId::MangaList,
+        }
+    }
+}
+
+/// What an entry has in a column
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Value<'a> {
+    Text(&'a str),
+    Number(f64),
+}
+
+impl Value<'_> {
+    fn compare(self, other: Self) -> Ordering {
+        match (self, other) {
+            (Value::Number(a), Value::Number(b)) => a.partial_cmp(&b).unwrap_or(Ordering::Equal),
+            (Value::Text(a), Value::Text(b)) => a.to_lowercase().cmp(&b.to_lowercase()),
+            (Value::Number(_), Value::Text(_)) => Ordering::Less,
+            (Value::Text(_), Value::Number(_)) => Ordering::Greater,
+        }
+    }
+}
+
+/// An anime or a manga in an entry view
+#[derive(Clone, Copy, Debug)]
+pub enum Entry<'a> {
+    Anime(&'a Anime),
+    Manga(&'a Manga),
+}
+
+impl<'a> Entry<'a> {
+    pub fn id(self) -> u64 {
+        match self {
+            Entry::Anime(anime) => anime.id,
+            Entry::Manga(manga) => manga.id,
+        }
+    }
+
+    /// Every title of the entry, the one in `language` first
+    pub fn titles(self, language: TitleLanguage) -> Vec<&'a str> {
+        let (title, alternative_titles) = match self {
+            Entry::Anime(anime) => (&anime.title, &anime.alternative_titles),
+            Entry::Manga(manga) => (&manga.title, &manga.alternative_titles),
+        };
+        language.titles(title, alternative_titles.as_ref())
+    }
+
+    /// The entry's value in `column`, `None` if it doesn't have one. The title is the
+    /// one in `language`.
+    pub fn value(self, column: ColumnId, language: TitleLanguage) -> Option<Value<'a>> {
+        fn number<N: Into<u64>>(n: Option<N>) -> Option<Value<'static>> {
+            // MAL has 0 for unknown counts and unscored entries
+            n.map(Into::into)
+                .filter(|&n| n > 0)
+                .map(|n| Value::Number(n as f64))
+        }
+        fn text<T: Into<&'static str>>(value: Option<T>) -> Option<Value<'static>> {
+            value.map(|value| Value::Text(value.into()))
+        }
+
+        match (self, column) {
+            (_, ColumnId::Title) => self.titles(language).first().copied().map(Value::Text),
+            (Entry::Anime(anime), ColumnId::Type) => text(anime.media_type.clone()),
+            (Entry::Manga(manga), ColumnId::Type) => text(manga.media_type.clone()),
+            (Entry::Anime(anime), ColumnId::Status) => text(anime.status.clone()),
+            (Entry::Manga(manga), ColumnId::Status) => text(manga.status.clone()),
+            (Entry::Anime(anime), ColumnId::Year) => {
+                number(anime.start_date.map(|d| d.year as u64))
+            }
+            (Entry::Manga(manga), ColumnId::Year) => {
+                number(manga.start_date.map(|d| d.year as u64))
+            }
+            (Entry::Anime(anime), ColumnId::Episodes) => number(anime.num_episodes),
+            (Entry::Manga(manga), ColumnId::Volumes) => number(manga.num_volumes),
+            (Entry::Manga(manga), ColumnId::Chapters) => number(manga.num_chapters),
+            (Entry::Anime(_), ColumnId::Volumes | ColumnId::Chapters)
+            | (Entry::Manga(_), ColumnId::Episodes) => None,
+            (Entry::Anime(anime), ColumnId::Score) => anime.mean.map(Value::Number),
+            (Entry::Manga(manga), ColumnId::Score) => manga.mean.map(Value::Number),
+            (Entry::Anime(anime), ColumnId::Rank) => number(anime.rank),
+            (Entry::Manga(manga), ColumnId::Rank) => number(manga.rank),
+            (Entry::Anime(anime), ColumnId::Popularity) => number(anime.popularity),
+            (Entry::Manga(manga), ColumnId::Popularity) => number(manga.popularity),
+            (Entry::Anime(anime), ColumnId::Members) => number(anime.num_list_users),
+            (Entry::Manga(manga), ColumnId::Members) => number(manga.num_list_users),
+            (Entry::Anime(anime), ColumnId::MyStatus) => {
+                text(anime.my_list_status.as_ref().map(|s| s.status.clone()))
+            }
+            (Entry::Manga(manga), ColumnId::MyStatus) => {
+                text(manga.my_list_status.as_ref().map(|s| s.status.clone()))
+            }
+            (Entry::Anime(anime), ColumnId::MyScore) => {
+                number(anime.my_list_status.as_ref().map(|s| s.score))
+            }
+            (Entry::Manga(manga), ColumnId::MyScore) => {
+                number(manga.my_list_status.as_ref().map(|s| s.score))
+            }
+            (Entry::Anime(anime), ColumnId::Progress) => anime
+                .my_list_status
+                .as_ref()
+                .map(|s| Value::Number(s.num_episodes_watched as f64)),
+            (Entry::Manga(manga), ColumnId::Progress) => manga
+                .my_list_status
+                .as_ref()
+                .map(|s| Value::Number(s.num_chapters_read as f64)),
+        }
+    }
+}
+
+/// Rows of an entry view matching what was typed after `/`
+#[derive(Debug, Default)]
+pub struct ListFilter {
+    pub query: String,
+    /// Whether the query is still being typed
+    pub editing: bool,
+    /// Best match first
+    pub matches: Vec<RowMatch>,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct RowMatch {
+    /// Index of the row in the unfiltered list
+    pub index: usize,
+    /// Which of `Entry::titles` matched best
+    pub title_index: usize,
+    /// The matched chars of that title
+    pub positions: Vec<usize>,
+}
+
+/// Cursor and filter of an entry view
+#[derive(Debug, Default)]
+pub struct ViewState {
+    /// Index into the visible rows
+    pub selected_index: usize,
+    pub filter: Option<ListFilter>,
+    /// Column the rows are sorted by, on top of the filter's order
+    pub sort: Option<Sort>,
+    /// Entries marked for a batch action, by id
+    pub marked: HashSet<u64>,
+    /// The entry a `V` range starts at, it runs to the cursor
+    pub range_start: Option<u64>,
+}
+
 #[derive(PartialEq, Debug)]
 pub enum SearchResultBlock {
     AnimeSearch,
@@ -70,6 +413,12 @@ pub struct ScrollablePages<T> {
     pages: Vec<T>,
 }
 
+impl<T> Default for ScrollablePages<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl<T> ScrollablePages<T> {
     pub fn new() -> Self {
         Self {
@@ -98,30 +447,76 @@ pub struct Library {
     pub saved_manga: ScrollablePages<Page<Manga>>,
 }
 
+/// Accounts that can be switched between in the tui
+pub struct Profiles {
+    /// Name of the profile in use
+    pub active: String,
+    pub names: Vec<String>,
+    pub selected_index: usize,
+    /// The user logged in with the active profile, once fetched
+    pub user: Option<UserInfo>,
+}
+
 pub struct App {
-    pub io_tx: Option<Sender<IoEvent>>,
+    pub io_tx: Option<UnboundedSender<(RequestId, IoEvent)>>,
     pub app_config: AppConfig,
-    pub is_loading: bool,
     pub api_error: String,
     pub search_results: SearchResult,
+    pub suggestions: Suggestions,
+    pub searches: Searches,
+    /// The past search shown in the search box while going through them with up and down
+    pub history_index: Option<usize>,
+    pub history_popup: HistoryPopup,
+    pub save_prompt: SavePrompt,
+    pub saved_searches_index: usize,
+    pub anime_details: Option<Anime>,
+    pub manga_details: Option<Manga>,
+    /// How covers are drawn, `None` if they're off
+    pub cover_protocol: Option<Protocol>,
+    /// Cover of the anime or manga in the details view
+    pub cover: Option<Cover>,
+    pub anime_ranking: Option<Ranking<RankingAnimePair>>,
+    pub manga_ranking: Option<Ranking<RankingMangaPair>>,
+    /// The user's whole anime list
+    pub anime_list: Option<Page<Anime>>,
+    /// The user's whole manga list
+    pub manga_list: Option<Page<Manga>>,
+    pub views: HashMap<EntryView, ViewState>,
+    pub schedule: ScheduleCursor,
+    /// Show under the cursor in the catch up view
+    pub catch_up_index: usize,
+    pub backlog: BacklogState,
+    pub batch_menu: BatchMenu,
+    /// The batch being sent, or the last one
+    pub batch: Option<Batch>,
     pub size: Rect,
     pub input: Vec<char>,
     pub input_cursor_position: u16,
     pub input_idx: usize,
     pub library: Library,
+    pub profiles: Profiles,
     pub help_menu_offset: u32,
     pub help_menu_page: u32,
     pub help_menu_max_lines: u32,
     pub help_docs_size: u32,
     navigation_stack: Vec<Route>,
+    next_request_id: RequestId,
+    /// Dispatched events the network hasn't finished yet
+    pending_requests: HashSet<RequestId>,
+    /// The newest request of each kind that supersedes older ones
+    latest_requests: HashMap<Discriminant<IoEvent>, RequestId>,
 }
 
 impl App {
-    pub fn new(io_tx: Sender<IoEvent>, app_config: AppConfig) -> Self {
+    pub fn new(
+        io_tx: UnboundedSender<(RequestId, IoEvent)>,
+        app_config: AppConfig,
+        profile: String,
+    ) -> Self {
         Self {
             io_tx: Some(io_tx),
+            cover_protocol: app_config.covers.protocol(),
             app_config,
-            is_loading: false,
             api_error: String::new(),
             search_results: SearchResult {
                 hovered_block: SearchResultBlock::AnimeSearch,
@@ -131,6 +526,25 @@ impl App {
                 selected_anime_index: None,
                 selected_manga_index: None,
             },
+            suggestions: Suggestions::default(),
+            searches: Searches::default(),
+            history_index: None,
+            history_popup: HistoryPopup::default(),
+            save_prompt: SavePrompt::default(),
+            saved_searches_index: 0,
+            anime_details: None,
+            manga_details: None,
+            cover: None,
+            anime_ranking: None,
+            manga_ranking: None,
+            anime_list: None,
+            manga_list: None,
+            views: HashMap::new(),
+            schedule: ScheduleCursor::default(),
+            catch_up_index: 0,
+            backlog: BacklogState::default(),
+            batch_menu: BatchMenu::default(),
+            batch: None,
             size: Rect::default(),
             input: vec![],
             input_cursor_position: 0,
@@ -140,28 +554,866 @@ impl App {
                 saved_manga: ScrollablePages::new(),
                 selected_index: 0,
             },
+            profiles: Profiles {
+                names: vec![profile.clone()],
+                active: profile,
+                selected_index: 0,
+                user: None,
+            },
             help_menu_offset: 0,
             help_menu_page: 0,
             help_menu_max_lines: 0,
             help_docs_size: 0,
             navigation_stack: vec![DEFAULT_ROUTE],
+            next_request_id: 0,
+            pending_requests: HashSet::new(),
+            latest_requests: HashMap::new(),
         }
     }
 
-    // TODO:  <05-09-20, yourname>  Handle errors in TUI //
-    pub fn handle_error(&mut self) {
-        self.api_error = "Error!".to_string();
+    /// Shows `error` on the error screen
+    pub fn handle_error(&mut self, error: String) {
+        self.api_error = error;
+        if self.get_current_route().active_block != ActiveBlock::Error {
+            self.push_navigation_stack(RouteId::Error, ActiveBlock::Error);
+        }
     }
 
     // Send a network event to the network thread
     pub fn dispatch(&mut self, event: IoEvent) {
-        self.is_loading = true;
+        let id = self.next_request_id;
+        self.next_request_id += 1;
+
+        // whatever the older request still sends back is stale now
+        if event.supersedes_previous() {
+            if let Some(previous) = self.latest_requests.insert(event.kind(), id) {
+                self.pending_requests.remove(&previous);
+            }
+        }
+
         if let Some(io_tx) = &self.io_tx {
-            if let Err(e) = io_tx.send(event) {
-                self.is_loading = false;
-                println!("Error from dispatch {}", e);
+            match io_tx.send((id, event)) {
+                Ok(()) => {
+                    self.pending_requests.insert(id);
+                }
+                // printing would draw over the tui
+                Err(e) => self.handle_error(format!("Could not send the request: {}", e)),
+            }
+        };
+    }
+
+    /// Whether any dispatched request is still running
+    pub fn is_loading(&self) -> bool {
+        !self.pending_requests.is_empty()
+    }
+
+    /// Applies what the network thread sent back, this is the only place its results
+    /// reach the app. Responses to superseded requests are dropped.
+    pub fn handle_io_response(&mut self, id: RequestId, response: IoResponse) {
+        if !self.pending_requests.contains(&id) {
+            return;
+        }
+
+        match response {
+            IoResponse::AnimeSearchResults(results) => {
+                self.search_results.anime = Some(results);
+                self.refresh_view(EntryView::AnimeSearch);
+            }
+            IoResponse::MangaSearchResults(results) => {
+                self.search_results.manga = Some(results);
+                self.refresh_view(EntryView::MangaSearch);
+            }
+            IoResponse::AnimeSuggestions(query, results) => {
+                if query == self.suggestions.query {
+                    self.suggestions.anime =
+                        results.data.into_iter().map(|node| node.node).collect();
+                }
+            }
+            IoResponse::MangaSuggestions(query, results) => {
+                if query == self.suggestions.query {
+                    self.suggestions.manga =
+                        results.data.into_iter().map(|node| node.node).collect();
+                }
+            }
+            IoResponse::AnimeDetails(anime) => {
+                self.request_cover(anime.main_picture.as_ref());
+                self.anime_details = Some(anime);
+            }
+            IoResponse::MangaDetails(manga) => {
+                self.request_cover(manga.main_picture.as_ref());
+                self.manga_details = Some(manga);
+            }
+            IoResponse::Cover(cover) => {
+                if self.cover_url() == Some(cover.url.as_str()) {
+                    self.cover = Some(cover);
+                }
+            }
+            IoResponse::AnimeRanking(ranking) => {
+                self.anime_ranking = Some(ranking);
+                self.refresh_view(EntryView::AnimeRanking);
+            }
+            IoResponse::MangaRanking(ranking) => {
+                self.manga_ranking = Some(ranking);
+                self.refresh_view(EntryView::MangaRanking);
+            }
+            IoResponse::AnimeList(list) => {
+                self.anime_list = Some(list);
+                self.refresh_view(EntryView::AnimeList);
+            }
+            IoResponse::MangaList(list) => {
+                self.manga_list = Some(list);
+                self.refresh_view(EntryView::MangaList);
+            }
+            IoResponse::AnimeListStatus(anime_id, status) => {
+                self.set_anime_list_status(anime_id, status)
+            }
+            IoResponse::MangaListStatus(manga_id, status) => {
+                self.set_manga_list_status(manga_id, status)
+            }
+            IoResponse::UserInfo(user) => self.profiles.user = Some(user),
+            IoResponse::ProfileSwitched {
+                name,
+                app_config,
+                user,
+                searches,
+            } => {
+                self.cover_protocol = app_config.covers.protocol();
+                self.app_config = app_config;
+                self.profiles.active = name;
+                self.profiles.user = Some(user);
+                self.searches = searches;
+                self.history_index = None;
+                self.saved_searches_index = 0;
+                // what's still running was asked for with the other account
+                self.pending_requests.retain(|&pending| pending >= id);
+                self.latest_requests.clear();
+                self.clear_account_data();
+            }
+            IoResponse::BatchItem(entry_id, error) => self.batch_item_done(id, entry_id, error),
+            IoResponse::Error(error) => self.handle_error(error),
+            IoResponse::Done => {
+                self.pending_requests.remove(&id);
+            }
+        }
+    }
+
+    /// Drops everything fetched with the account, it all carries its list statuses
+    fn clear_account_data(&mut self) {
+        self.search_results.anime = None;
+        self.search_results.manga = None;
+        self.suggestions = Suggestions::default();
+        self.anime_details = None;
+        self.manga_details = None;
+        self.cover = None;
+        self.anime_ranking = None;
+        self.manga_ranking = None;
+        self.anime_list = None;
+        self.manga_list = None;
+        self.views.clear();
+        self.schedule = ScheduleCursor::default();
+        self.catch_up_index = 0;
+        self.backlog = BacklogState::default();
+        self.batch_menu = BatchMenu::default();
+        self.batch = None;
+        if self.get_current_route().active_block == ActiveBlock::BatchEdit {
+            self.set_current_route_state(Some(ActiveBlock::Entries), None);
+        }
+    }
+
+    /// The entry view shown in the main view, if it has the focus
+    pub fn focused_view(&self) -> Option<EntryView> {
+        let route = self.get_current_route();
+        if !matches!(
+            route.active_block,
+            ActiveBlock::Entries | ActiveBlock::SearchResultBlock
+        ) {
+            return None;
+        }
+        match route.id {
+            RouteId::Search => match self.search_results.hovered_block {
+                SearchResultBlock::AnimeSearch => Some(EntryView::AnimeSearch),
+                SearchResultBlock::MangaSearch => Some(EntryView::MangaSearch),
+                SearchResultBlock::Empty => None,
+            },
+            RouteId::AnimeRanking => Some(EntryView::AnimeRanking),
+            RouteId::MangaRanking => Some(EntryView::MangaRanking),
+            RouteId::AnimeList => Some(EntryView::AnimeList),
+            RouteId::MangaList => Some(EntryView::MangaList),
+            _ => None,
+        }
+    }
+
+    /// Every row of an entry view, filtered or not
+    pub fn entries(&self, view: EntryView) -> Vec<Entry<'_>> {
+        fn anime(page: &Option<Page<Anime>>) -> Vec<Entry<'_>> {
+            page.iter()
+                .flat_map(|page| page.data.iter().map(|node| Entry::Anime(&node.node)))
+                .collect()
+        }
+        fn manga(page: &Option<Page<Manga>>) -> Vec<Entry<'_>> {
+            page.iter()
+                .flat_map(|page| page.data.iter().map(|node| Entry::Manga(&node.node)))
+                .collect()
+        }
+
+        match view {
+            EntryView::AnimeSearch => anime(&self.search_results.anime),
+            EntryView::MangaSearch => manga(&self.search_results.manga),
+            EntryView::AnimeRanking => self
+                .anime_ranking
+                .iter()
+                .flat_map(|ranking| ranking.data.iter().map(|pair| Entry::Anime(&pair.node)))
+                .collect(),
+            EntryView::MangaRanking => self
+                .manga_ranking
+                .iter()
+                .flat_map(|ranking| ranking.data.iter().map(|pair| Entry::Manga(&pair.node)))
+                .collect(),
+            EntryView::AnimeList => anime(&self.anime_list),
+            EntryView::MangaList => manga(&self.manga_list),
+        }
+    }
+
+    /// Indices of the rows shown, the matches if the view is filtered, in the view's
+    /// sort order
+    pub fn visible_rows(&self, view: EntryView) -> Vec<usize> {
+        let state = self.views.get(&view);
+        let entries = self.entries(view);
+        let language = self.app_config.title_language;
+        let mut rows: Vec<usize> = match state.and_then(|state| state.filter.as_ref()) {
+            Some(filter) => filter.matches.iter().map(|row| row.index).collect(),
+            None => (0..entries.len()).collect(),
+        };
+
+        if let Some(sort) = state.and_then(|state| state.sort) {
+            rows.sort_by(|&a, &b| {
+                match (
+                    entries[a].value(sort.column, language),
+                    entries[b].value(sort.column, language),
+                ) {
+                    (Some(a), Some(b)) if sort.descending => b.compare(a),
+                    (Some(a), Some(b)) => a.compare(b),
+                    // missing values go last either way
+                    (Some(_), None) => Ordering::Less,
+                    (None, Some(_)) => Ordering::Greater,
+                    (None, None) => Ordering::Equal,
+                }
+            });
+        }
+        rows
+    }
+
+    /// The entry under the cursor
+    pub fn selected_entry(&self, view: EntryView) -> Option<Entry<'_>> {
+        // the cursor is on the first row until it's moved
+        let selected_index = self
+            .views
+            .get(&view)
+            .map_or(0, |state| state.selected_index);
+        let index = *self.visible_rows(view).get(selected_index)?;
+        self.entries(view).get(index).copied()
+    }
+
+    /// Marks the entry under the cursor for a batch action, or unmarks it
+    pub fn toggle_mark(&mut self, view: EntryView) {
+        let id = match self.selected_entry(view) {
+            Some(entry) => entry.id(),
+            None => return,
+        };
+        let state = self.views.entry(view).or_default();
+        if !state.marked.remove(&id) {
+            state.marked.insert(id);
+        }
+    }
+
+    /// Starts a range at the cursor, or marks the one started
+    pub fn toggle_range(&mut self, view: EntryView) {
+        let range = self.range(view);
+        let selected = self.selected_entry(view).map(Entry::id);
+        let state = self.views.entry(view).or_default();
+        match state.range_start.take() {
+            Some(_) => state.marked.extend(range),
+            None => state.range_start = selected,
+        }
+    }
+
+    /// The entries from the start of the `V` range to the cursor
+    fn range(&self, view: EntryView) -> Vec<u64> {
+        let state = match self.views.get(&view) {
+            Some(state) => state,
+            None => return vec![],
+        };
+        let entries = self.entries(view);
+        let ids: Vec<u64> = self
+            .visible_rows(view)
+            .into_iter()
+            .map(|index| entries[index].id())
+            .collect();
+        let start = match state
+            .range_start
+            .and_then(|start| ids.iter().position(|&id| id == start))
+        {
+            Some(start) => start,
+            None => return vec![],
+        };
+        let end = state.selected_index.min(ids.len().saturating_sub(1));
+        ids[start.min(end)..=start.max(end)].to_vec()
+    }
+
+    /// The entries marked, with the ones in the `V` range being picked
+    pub fn marked(&self, view: EntryView) -> HashSet<u64> {
+        let mut marked: HashSet<u64> = self
+            .views
+            .get(&view)
+            .map(|state| state.marked.clone())
+            .unwrap_or_default();
+        marked.extend(self.range(view));
+        marked
+    }
+
+    /// What a batch action applies to: the marked entries in the order they're
+    /// shown, or the one under the cursor if none are
+    pub fn batch_targets(&self, view: EntryView) -> Vec<u64> {
+        let marked = self.marked(view);
+        let entries = self.entries(view);
+        let targets: Vec<u64> = self
+            .visible_rows(view)
+            .into_iter()
+            .map(|index| entries[index].id())
+            .filter(|id| marked.contains(id))
+            .collect();
+        if targets.is_empty() {
+            self.selected_entry(view)
+                .map(Entry::id)
+                .into_iter()
+                .collect()
+        } else {
+            targets
+        }
+    }
+
+    /// Unmarks everything and drops the `V` range, `false` if there was nothing to
+    pub fn clear_marks(&mut self, view: EntryView) -> bool {
+        match self.views.get_mut(&view) {
+            Some(state) if !state.marked.is_empty() || state.range_start.is_some() => {
+                state.marked.clear();
+                state.range_start = None;
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Opens the batch popup for the focused user list
+    pub fn open_batch_menu(&mut self) {
+        let view = match self.focused_view() {
+            Some(view @ (EntryView::AnimeList | EntryView::MangaList)) => view,
+            _ => return,
+        };
+        if self.batch_targets(view).is_empty() {
+            return;
+        }
+        self.batch_menu = BatchMenu {
+            view: Some(view),
+            ..BatchMenu::default()
+        };
+        self.set_current_route_state(Some(ActiveBlock::BatchEdit), None);
+    }
+
+    /// Sends `action` for every target of the batch popup's list, one request each
+    pub fn run_batch(&mut self, action: BatchAction) {
+        let view = match self.batch_menu.view {
+            Some(view) => view,
+            None => return,
+        };
+        let targets = self.batch_targets(view);
+        let entries = self.entries(view);
+        let entries: Vec<Entry<'_>> = targets
+            .iter()
+            .filter_map(|&id| entries.iter().find(|entry| entry.id() == id).copied())
+            .collect();
+        let event = match view {
+            EntryView::AnimeList => IoEvent::UpdateAnimeListBatch(
+                entries
+                    .iter()
+                    .filter_map(|entry| match entry {
+                        Entry::Anime(anime) => {
+                            Some((anime.id, action.anime_update(anime.my_list_status.as_ref())))
+                        }
+                        Entry::Manga(_) => None,
+                    })
+                    .collect(),
+            ),
+            _ => IoEvent::UpdateMangaListBatch(
+                entries
+                    .iter()
+                    .filter_map(|entry| match entry {
+                        Entry::Manga(manga) => {
+                            Some((manga.id, action.manga_update(manga.my_list_status.as_ref())))
+                        }
+                        Entry::Anime(_) => None,
+                    })
+                    .collect(),
+            ),
+        };
+
+        self.batch = Some(Batch {
+            id: self.next_request_id,
+            view,
+            progress: BatchProgress::new(entries.len()),
+        });
+        self.dispatch(event);
+        self.clear_marks(view);
+        self.batch_menu.step = BatchStep::Progress;
+    }
+
+    /// Counts an entry of the batch as done, and keeps why if it failed
+    fn batch_item_done(&mut self, id: RequestId, entry_id: u64, error: Option<String>) {
+        let language = self.app_config.title_language;
+        let title = match &self.batch {
+            Some(batch) if batch.id == id => self
+                .entries(batch.view)
+                .into_iter()
+                .find(|entry| entry.id() == entry_id)
+                .and_then(|entry| {
+                    entry
+                        .titles(language)
+                        .first()
+                        .map(|title| title.to_string())
+                })
+                .unwrap_or_else(|| format!("#{}", entry_id)),
+            _ => return,
+        };
+        if let Some(batch) = &mut self.batch {
+            batch.progress.done += 1;
+            if let Some(error) = error {
+                batch.progress.failures.push((title, error));
+            }
+        }
+    }
+
+    /// Whether a `/` filter is being typed in the focused view
+    pub fn is_editing_filter(&self) -> bool {
+        self.focused_view()
+            .and_then(|view| self.views.get(&view))
+            .and_then(|state| state.filter.as_ref())
+            .is_some_and(|filter| filter.editing)
+    }
+
+    /// Starts typing a filter for `view`, editing the current one if it has one
+    pub fn start_filter(&mut self, view: EntryView) {
+        let state = self.views.entry(view).or_default();
+        state.filter.get_or_insert_with(ListFilter::default).editing = true;
+        self.refresh_view(view);
+    }
+
+    /// Call when the filter's query changed
+    pub fn set_filter_query(&mut self, view: EntryView, query: String) {
+        let state = self.views.entry(view).or_default();
+        if let Some(filter) = &mut state.filter {
+            filter.query = query;
+            state.selected_index = 0;
+        }
+        self.refresh_view(view);
+    }
+
+    pub fn clear_filter(&mut self, view: EntryView) {
+        if let Some(state) = self.views.get_mut(&view) {
+            state.filter = None;
+            state.selected_index = 0;
+        }
+    }
+
+    /// Downloads the cover of the details about to be shown, unless it's the one there
+    fn request_cover(&mut self, picture: Option<&Picture>) {
+        let url = match (self.cover_protocol, picture) {
+            (Some(_), Some(picture)) => picture.medium.clone(),
+            _ => {
+                self.cover = None;
+                return;
             }
         };
+        if self.cover.as_ref().is_some_and(|cover| cover.url == url) {
+            return;
+        }
+        self.cover = None;
+        self.dispatch(IoEvent::GetCover(url));
+    }
+
+    /// Url of the cover the details view shows
+    pub fn cover_url(&self) -> Option<&str> {
+        let picture = match self.get_current_route().id {
+            RouteId::AnimeDetails => self.anime_details.as_ref()?.main_picture.as_ref(),
+            RouteId::MangaDetails => self.manga_details.as_ref()?.main_picture.as_ref(),
+            _ => None,
+        };
+        picture.map(|picture| picture.medium.as_str())
+    }
+
+    /// Time zone of the airing schedule
+    pub fn schedule_zone(&self) -> Zone {
+        let profile_time_zone = self
+            .profiles
+            .user
+            .as_ref()
+            .and_then(|user| user.time_zone.as_deref());
+        self.app_config.time_zone.zone(profile_time_zone)
+    }
+
+    /// The shows being watched that are airing, by the day they air on
+    pub fn schedule_week(&self, now: DateTime<Utc>) -> [Vec<Airing<'_>>; 7] {
+        let anime = self
+            .anime_list
+            .iter()
+            .flat_map(|list| list.data.iter().map(|node| &node.node));
+        schedule::week(anime, now, self.schedule_zone())
+    }
+
+    /// Day of the schedule under the cursor, from Monday
+    pub fn schedule_day(&self, now: DateTime<Utc>) -> usize {
+        self.schedule.day.unwrap_or_else(|| {
+            let today = self.schedule_zone().localize(now).weekday();
+            today.num_days_from_monday() as usize
+        })
+    }
+
+    /// The shows being watched that have episodes out the user hasn't seen
+    pub fn catch_up(&self, now: DateTime<Utc>) -> Vec<CatchUp<'_>> {
+        let anime = self
+            .anime_list
+            .iter()
+            .flat_map(|list| list.data.iter().map(|node| &node.node));
+        let weeks = self.app_config.behavior.stalled_after_weeks;
+        schedule::catch_up(anime, now, chrono::Duration::weeks(weeks.into()))
+    }
+
+    /// The shows being watched then the ones planned, in the user's order
+    pub fn backlog_queue(&self) -> Vec<&Anime> {
+        let anime = self
+            .anime_list
+            .iter()
+            .flat_map(|list| list.data.iter().map(|node| &node.node));
+        backlog::queue(anime, &self.backlog.order)
+    }
+
+    /// The user's recent pace, or the one over their whole account if they haven't
+    /// watched anything lately
+    pub fn backlog_pace(&self, now: DateTime<Utc>) -> Option<Pace> {
+        let anime = self
+            .anime_list
+            .iter()
+            .flat_map(|list| list.data.iter().map(|node| &node.node));
+        backlog::recent_pace(anime, now).or_else(|| {
+            let user = self.profiles.user.as_ref()?;
+            backlog::lifetime_pace(user.anime_statistics.as_ref()?, &user.joined_at, now)
+        })
+    }
+
+    /// Swaps the show under the cursor with the one before it, or after it, keeping
+    /// the cursor on it
+    pub fn move_backlog_item(&mut self, up: bool) {
+        let mut order: Vec<u64> = self.backlog_queue().iter().map(|anime| anime.id).collect();
+        let index = self.backlog.selected_index;
+        let other = match up {
+            true if index > 0 => index - 1,
+            false if index + 1 < order.len() => index + 1,
+            _ => return,
+        };
+        order.swap(index, other);
+        self.backlog.order = order;
+        self.backlog.selected_index = other;
+    }
+
+    /// Sorts `view` by the next of its table's columns, back to the unsorted order after
+    /// the last one
+    pub fn cycle_sort(&mut self, view: EntryView) {
+        let columns = &self.app_config.table(view.table_id()).columns;
+        let state = self.views.entry(view).or_default();
+        let next = match state.sort {
+            Some(sort) => columns
+                .iter()
+                .position(|&column| column == sort.column)
+                .and_then(|index| columns.get(index + 1)),
+            None => columns.first(),
+        };
+        state.sort = next.map(|&column| Sort {
+            column,
+            descending: column.sorts_descending(),
+        });
+        state.selected_index = 0;
+    }
+
+    pub fn reverse_sort(&mut self, view: EntryView) {
+        let state = self.views.entry(view).or_default();
+        if let Some(sort) = &mut state.sort {
+            sort.descending = !sort.descending;
+            state.selected_index = 0;
+        }
+    }
+
+    /// Matches the filter against the view's rows again and keeps the cursor on a row,
+    /// call after the rows changed
+    pub fn refresh_view(&mut self, view: EntryView) {
+        let query = self
+            .views
+            .get(&view)
+            .and_then(|state| state.filter.as_ref())
+            .map(|filter| filter.query.clone());
+
+        let entries = self.entries(view);
+        let len = entries.len();
+        let language = self.app_config.title_language;
+        let matches = query.map(|query| {
+            let mut matches: Vec<(i64, RowMatch)> = entries
+                .iter()
+                .enumerate()
+                .filter_map(|(index, entry)| {
+                    entry
+                        .titles(language)
+                        .into_iter()
+                        .enumerate()
+                        .filter_map(|(title_index, title)| {
+                            fuzzy::find(&query, title).map(|found| {
+                                let row = RowMatch {
+                                    index,
+                                    title_index,
+                                    positions: found.positions,
+                                };
+                                (found.score, row)
+                            })
+                        })
+                        // the first title to score best, so the preferred title wins ties
+                        .fold(None, |best: Option<(i64, RowMatch)>, found| match best {
+                            Some(best) if best.0 >= found.0 => Some(best),
+                            _ => Some(found),
+                        })
+                })
+                .collect();
+            matches.sort_by_key(|&(score, _)| std::cmp::Reverse(score));
+            matches.into_iter().map(|(_, row)| row).collect::<Vec<_>>()
+        });
+
+        let state = self.views.entry(view).or_default();
+        let visible = match (&mut state.filter, matches) {
+            (Some(filter), Some(matches)) => {
+                filter.matches = matches;
+                filter.matches.len()
+            }
+            _ => len,
+        };
+        state.selected_index = state.selected_index.min(visible.saturating_sub(1));
+    }
+
+    /// Replaces what's in the search box, with the cursor at the end
+    pub fn set_input(&mut self, text: &str) {
+        self.input = text.chars().collect();
+        self.input_idx = self.input.len();
+        self.input_cursor_position = UnicodeWidthStr::width(text).try_into().unwrap_or(u16::MAX);
+    }
+
+    /// Runs a search and remembers it in the history
+    pub fn search(&mut self, query: String, scope: SearchScope) {
+        self.history_index = None;
+        self.searches.push_history(&query);
+
+        if !scope.includes_anime() {
+            self.search_results.anime = None;
+        }
+        if !scope.includes_manga() {
+            self.search_results.manga = None;
+        }
+        self.dispatch(IoEvent::GetSearchResults(query, scope));
+        self.push_navigation_stack(RouteId::Search, ActiveBlock::SearchResultBlock);
+
+        if let Err(e) = self.searches.save() {
+            self.handle_error(format!("Could not save the search history: {}", e));
+        }
+    }
+
+    /// Shows the previous (`older`) or next past search in the search box. Going past
+    /// the newest one empties the box.
+    pub fn recall_history(&mut self, older: bool) {
+        let len = self.searches.history.len();
+        self.history_index = match (self.history_index, older) {
+            (None, true) if len > 0 => Some(len - 1),
+            (Some(index), true) => Some(index.saturating_sub(1)),
+            (Some(index), false) if index + 1 < len => Some(index + 1),
+            _ => None,
+        };
+        let text = match self.history_index {
+            Some(index) => self.searches.history[index].clone(),
+            None => String::new(),
+        };
+        self.set_input(&text);
+    }
+
+    pub fn open_history_popup(&mut self) {
+        self.history_popup = HistoryPopup::default();
+        self.update_history_popup();
+        self.set_current_route_state(
+            Some(ActiveBlock::SearchHistory),
+            Some(ActiveBlock::SearchHistory),
+        );
+    }
+
+    /// Call when the popup's filter changed, newest searches come first among equal matches
+    pub fn update_history_popup(&mut self) {
+        let newest_first: Vec<&String> = self.searches.history.iter().rev().collect();
+        let last = newest_first.len().saturating_sub(1);
+        self.history_popup.matches = fuzzy::rank(&self.history_popup.filter, &newest_first)
+            .into_iter()
+            .map(|index| last - index)
+            .collect();
+        self.history_popup.selected_index = 0;
+    }
+
+    /// The past search highlighted in the history popup
+    pub fn selected_history_entry(&self) -> Option<&String> {
+        let index = self
+            .history_popup
+            .matches
+            .get(self.history_popup.selected_index)?;
+        self.searches.history.get(*index)
+    }
+
+    /// Saves what's in the search box under the name typed in the save prompt
+    pub fn save_search(&mut self) {
+        let name = self.save_prompt.name.trim().to_string();
+        let query: String = self.input.iter().collect();
+        if name.is_empty() || query.trim().is_empty() {
+            return;
+        }
+        self.searches.save_search(SavedSearch {
+            name,
+            query: query.trim().to_string(),
+            scope: self.save_prompt.scope,
+        });
+        if let Err(e) = self.searches.save() {
+            self.handle_error(format!("Could not save the search: {}", e));
+        }
+    }
+
+    /// Runs the saved search selected in the sidebar
+    pub fn run_saved_search(&mut self) {
+        if let Some(saved) = self.searches.saved.get(self.saved_searches_index).cloned() {
+            self.set_input(&saved.query);
+            self.search(saved.query, saved.scope);
+        }
+    }
+
+    pub fn delete_saved_search(&mut self) {
+        if self.saved_searches_index < self.searches.saved.len() {
+            self.searches.saved.remove(self.saved_searches_index);
+            self.saved_searches_index = self
+                .saved_searches_index
+                .min(self.searches.saved.len().saturating_sub(1));
+            if let Err(e) = self.searches.save() {
+                self.handle_error(format!("Could not save the searches: {}", e));
+            }
+        }
+    }
+
+    /// Call when the search input changed, the suggestions for it are fetched
+    /// once typing pauses
+    pub fn update_suggestions(&mut self) {
+        let input: String = self.input.iter().collect();
+        // suggest titles for the free text, leaving out the filter terms.
+        // A half typed term like `score>` keeps the current suggestions
+        let query = match SearchQuery::parse(&input) {
+            Ok(search) => search.text,
+            Err(_) => return,
+        };
+        if query == self.suggestions.query {
+            return;
+        }
+
+        self.suggestions.clear();
+        if query.chars().count() >= MIN_QUERY_LENGTH {
+            self.suggestions.changed_at = Some(Instant::now());
+        }
+        self.suggestions.query = query;
+    }
+
+    /// Fetches the suggestions once typing has paused
+    pub fn send_suggestions_query(&mut self, now: Instant) {
+        if self
+            .suggestions
+            .deadline()
+            .is_some_and(|deadline| now >= deadline)
+        {
+            self.suggestions.changed_at = None;
+            let query = self.suggestions.query.clone();
+            self.dispatch(IoEvent::GetAnimeSearchResults(query.clone()));
+            self.dispatch(IoEvent::GetMangaSearchResults(query));
+        }
+    }
+
+    /// Shows the new list status everywhere the anime is shown
+    fn set_anime_list_status(&mut self, anime_id: u64, status: Option<UserAnimeListStatus>) {
+        let search = self
+            .search_results
+            .anime
+            .iter_mut()
+            .flat_map(|page| page.data.iter_mut().map(|node| &mut node.node));
+        let ranking = self
+            .anime_ranking
+            .iter_mut()
+            .flat_map(|ranking| ranking.data.iter_mut().map(|pair| &mut pair.node));
+        let list = self
+            .anime_list
+            .iter_mut()
+            .flat_map(|page| page.data.iter_mut().map(|node| &mut node.node));
+        for anime in search
+            .chain(ranking)
+            .chain(list)
+            .chain(self.anime_details.iter_mut())
+        {
+            if anime.id == anime_id {
+                anime.my_list_status = status.clone();
+            }
+        }
+
+        // it's gone from the user's list once deleted
+        if status.is_none() {
+            if let Some(list) = &mut self.anime_list {
+                list.data.retain(|node| node.node.id != anime_id);
+            }
+            self.refresh_view(EntryView::AnimeList);
+        }
+    }
+
+    /// Shows the new list status everywhere the manga is shown
+    fn set_manga_list_status(&mut self, manga_id: u64, status: Option<UserMangaListStatus>) {
+        let search = self
+            .search_results
+            .manga
+            .iter_mut()
+            .flat_map(|page| page.data.iter_mut().map(|node| &mut node.node));
+        let ranking = self
+            .manga_ranking
+            .iter_mut()
+            .flat_map(|ranking| ranking.data.iter_mut().map(|pair| &mut pair.node));
+        let list = self
+            .manga_list
+            .iter_mut()
+            .flat_map(|page| page.data.iter_mut().map(|node| &mut node.node));
+        for manga in search
+            .chain(ranking)
+            .chain(list)
+            .chain(self.manga_details.iter_mut())
+        {
+            if manga.id == manga_id {
+                manga.my_list_status = status.clone();
+            }
+        }
+
+        // it's gone from the user's list once deleted
+        if status.is_none() {
+            if let Some(list) = &mut self.manga_list {
+                list.data.retain(|node| node.node.id != manga_id);
+            }
+            self.refresh_view(EntryView::MangaList);
+        }
     }
 
     pub fn push_navigation_stack(
@@ -197,7 +1449,7 @@ impl App {
         active_block: Option<ActiveBlock>,
         hovered_block: Option<ActiveBlock>,
     ) {
-        let mut current_route = self.get_current_route_mut();
+        let current_route = self.get_current_route_mut();
         if let Some(active_block) = active_block {
             current_route.active_block = active_block;
         }
@@ -216,3 +1468,579 @@ impl App {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::schedule::TimeZoneSetting;
+    use tokio::sync::mpsc::{unbounded_channel, UnboundedReceiver};
+
+    fn test_app() -> (App, UnboundedReceiver<(RequestId, IoEvent)>) {
+        let (tx, rx) = unbounded_channel();
+        let app = App::new(tx, AppConfig::default(), "default".to_string());
+        (app, rx)
+    }
+
+    #[test]
+    fn test_handle_io_response() {
+        let (mut app, _io_rx) = test_app();
+
+        app.dispatch(IoEvent::GetUserInfo("@me".to_string()));
+        app.dispatch(IoEvent::GetSearchResults(
+            "cowboy".to_string(),
+            SearchScope::All,
+        ));
+        assert!(app.is_loading());
+
+        app.handle_io_response(0, IoResponse::Done);
+        assert!(app.is_loading());
+
+        app.handle_io_response(1, IoResponse::Error("Http error: 401".to_string()));
+        app.handle_io_response(1, IoResponse::Done);
+        assert!(!app.is_loading());
+        assert_eq!(app.api_error, "Http error: 401");
+        assert_eq!(app.get_current_route().active_block, ActiveBlock::Error);
+    }
+
+    #[test]
+    fn test_dispatch_without_network() {
+        let (mut app, io_rx) = test_app();
+        drop(io_rx);
+
+        app.dispatch(IoEvent::GetUserInfo("@me".to_string()));
+        assert!(!app.is_loading());
+        assert_eq!(app.api_error, "Could not send the request: channel closed");
+        assert_eq!(app.get_current_route().active_block, ActiveBlock::Error);
+    }
+
+    #[test]
+    fn test_superseded_requests() {
+        let (mut app, _io_rx) = test_app();
+
+        app.dispatch(IoEvent::GetSearchResults(
+            "cowboy".to_string(),
+            SearchScope::All,
+        ));
+        app.dispatch(IoEvent::GetUserInfo("@me".to_string()));
+        app.dispatch(IoEvent::GetSearchResults(
+            "cowboy bebop".to_string(),
+            SearchScope::All,
+        ));
+
+        // the first search was cancelled, it no longer counts as loading
+        // and anything it still sends back is ignored
+        app.handle_io_response(1, IoResponse::Done);
+        app.handle_io_response(0, IoResponse::Error("Http error: 500".to_string()));
+        assert!(app.api_error.is_empty());
+        assert!(app.is_loading());
+
+        app.handle_io_response(2, IoResponse::Done);
+        assert!(!app.is_loading());
+    }
+
+    #[test]
+    fn test_list_status_update() {
+        let (mut app, _io_rx) = test_app();
+        let anime: Anime = serde_json::from_value(serde_json::json!({
+            "id": 1,
+            "title": "Cowboy Bebop",
+            "my_list_status": {
+                "status": "watching",
+                "score": 0,
+                "num_episodes_watched": 3,
+                "is_rewatching": false,
+                "updated_at": "2021-04-07T13:00:00+00:00"
+            }
+        }))
+        .unwrap();
+        app.anime_details = Some(anime);
+
+        app.dispatch(IoEvent::DeleteAnimeListStatus(1));
+        app.handle_io_response(0, IoResponse::AnimeListStatus(1, None));
+        assert!(app.anime_details.unwrap().my_list_status.is_none());
+    }
+
+    fn type_in(app: &mut App, text: &str) {
+        app.input = text.chars().collect();
+        app.update_suggestions();
+    }
+
+    #[test]
+    fn test_suggestions_debounce() {
+        let (mut app, mut io_rx) = test_app();
+
+        type_in(&mut app, "be");
+        assert!(app.suggestions.deadline().is_none());
+
+        type_in(&mut app, "beb");
+        let deadline = app.suggestions.deadline().unwrap();
+        app.send_suggestions_query(deadline - Duration::from_millis(1));
+        assert!(io_rx.try_recv().is_err());
+
+        app.send_suggestions_query(deadline);
+        assert!(matches!(
+            io_rx.try_recv(),
+            Ok((_, IoEvent::GetAnimeSearchResults(q))) if q == "beb"
+        ));
+        assert!(matches!(
+            io_rx.try_recv(),
+            Ok((_, IoEvent::GetMangaSearchResults(q))) if q == "beb"
+        ));
+        assert!(app.suggestions.deadline().is_none());
+    }
+
+    fn anime_list() -> Page<Anime> {
+        serde_json::from_value(serde_json::json!({
+            "data": [
+                { "node": { "id": 1, "title": "Shingeki no Kyojin",
+                    "alternative_titles": { "en": "Attack on Titan", "ja": "進撃の巨人" }, "mean": 8.5 } },
+                { "node": { "id": 2, "title": "Cowboy Bebop", "mean": 8.75 } },
+                { "node": { "id": 3, "title": "Bokura no" } },
+            ],
+            "paging": {}
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_list_filter() {
+        let (mut app, _io_rx) = test_app();
+        app.anime_list = Some(anime_list());
+        app.start_filter(EntryView::AnimeList);
+        assert_eq!(app.visible_rows(EntryView::AnimeList), vec![0, 1, 2]);
+
+        app.set_filter_query(EntryView::AnimeList, "bo".to_string());
+        assert_eq!(app.visible_rows(EntryView::AnimeList), vec![2, 1]);
+
+        // the english title matches, and it's the one highlighted
+        app.set_filter_query(EntryView::AnimeList, "titan".to_string());
+        let filter = app.views[&EntryView::AnimeList].filter.as_ref().unwrap();
+        assert_eq!(
+            filter.matches,
+            vec![RowMatch {
+                index: 0,
+                title_index: 1,
+                positions: vec![10, 11, 12, 13, 14],
+            }]
+        );
+        assert_eq!(app.selected_entry(EntryView::AnimeList).unwrap().id(), 1);
+
+        app.set_filter_query(EntryView::AnimeList, "巨人".to_string());
+        assert_eq!(app.visible_rows(EntryView::AnimeList), vec![0]);
+
+        app.clear_filter(EntryView::AnimeList);
+        assert_eq!(app.visible_rows(EntryView::AnimeList).len(), 3);
+    }
+
+    #[test]
+    fn test_list_filter_refresh() {
+        let (mut app, _io_rx) = test_app();
+        app.anime_list = Some(anime_list());
+        app.start_filter(EntryView::AnimeList);
+        app.set_filter_query(EntryView::AnimeList, "bo".to_string());
+        app.views
+            .get_mut(&EntryView::AnimeList)
+            .unwrap()
+            .selected_index = 1;
+
+        // deleting from the list removes the row and keeps the cursor in bounds
+        app.set_anime_list_status(1, None);
+        app.set_anime_list_status(2, None);
+        assert_eq!(app.visible_rows(EntryView::AnimeList), vec![0]);
+        assert_eq!(app.selected_entry(EntryView::AnimeList).unwrap().id(), 3);
+    }
+
+    #[test]
+    fn test_sort() {
+        let (mut app, _io_rx) = test_app();
+        app.anime_list = Some(anime_list());
+        let view = EntryView::AnimeList;
+        let columns = vec![ColumnId::Title, ColumnId::Score];
+        app.app_config
+            .tables
+            .get_mut(&TableId::AnimeList)
+            .unwrap()
+            .columns = columns;
+
+        app.cycle_sort(view);
+        assert_eq!(app.visible_rows(view), vec![2, 1, 0]);
+        app.reverse_sort(view);
+        assert_eq!(app.visible_rows(view), vec![0, 1, 2]);
+
+        // scores start with the best, and unscored rows stay at the end
+        app.cycle_sort(view);
+        assert_eq!(app.visible_rows(view), vec![1, 0, 2]);
+        app.reverse_sort(view);
+        assert_eq!(app.visible_rows(view), vec![0, 1, 2]);
+
+        // a filter keeps the sort
+        app.start_filter(view);
+        app.set_filter_query(view, "bo".to_string());
+        assert_eq!(app.visible_rows(view), vec![1, 2]);
+
+        app.cycle_sort(view);
+        assert_eq!(app.views[&view].sort, None);
+        assert_eq!(app.visible_rows(view), vec![2, 1]);
+    }
+
+    #[test]
+    fn test_cover() {
+        let (mut app, mut io_rx) = test_app();
+        app.cover_protocol = Some(Protocol::HalfBlocks);
+        app.push_navigation_stack(RouteId::AnimeDetails, ActiveBlock::Details);
+        app.dispatch(IoEvent::GetAnime(1));
+        let (id, _) = io_rx.try_recv().unwrap();
+        let anime: Anime = serde_json::from_value(serde_json::json!({
+            "id": 1,
+            "title": "Monster",
+            "main_picture": { "medium": "https://cdn.myanimelist.net/1.jpg" }
+        }))
+        .unwrap();
+        app.handle_io_response(id, IoResponse::AnimeDetails(anime));
+        let id = match io_rx.try_recv() {
+            Ok((id, IoEvent::GetCover(url))) if url == "https://cdn.myanimelist.net/1.jpg" => id,
+            _ => panic!("the cover wasn't requested"),
+        };
+
+        // only the cover of the details shown is kept
+        let cover = |url: &str| Cover {
+            url: url.to_string(),
+            image: Default::default(),
+        };
+        app.handle_io_response(
+            id,
+            IoResponse::Cover(cover("https://cdn.myanimelist.net/2.jpg")),
+        );
+        assert!(app.cover.is_none());
+        app.handle_io_response(
+            id,
+            IoResponse::Cover(cover("https://cdn.myanimelist.net/1.jpg")),
+        );
+        assert!(app.cover.is_some());
+    }
+
+    #[test]
+    fn test_schedule() {
+        let (mut app, _io_rx) = test_app();
+        app.app_config.time_zone = TimeZoneSetting::Named(chrono_tz::Europe::Paris);
+        let show = |id: u64, status: &str| {
+            serde_json::json!({ "node": {
+                "id": id,
+                "title": "Sousou no Frieren",
+                "alternative_titles": { "en": "Frieren" },
+                "start_date": "2023-09-29",
+                "status": "currently_airing",
+                "broadcast": { "day_of_the_week": "saturday", "start_time": "01:30" },
+                "my_list_status": {
+                    "status": status,
+                    "score": 0,
+                    "num_episodes_watched": 3,
+                    "is_rewatching": false,
+                    "updated_at": "2023-10-20T12:00:00+00:00"
+                }
+            } })
+        };
+        app.anime_list = Some(
+            serde_json::from_value(serde_json::json!({
+                "data": [show(1, "watching"), show(2, "plan_to_watch")],
+                "paging": {}
+            }))
+            .unwrap(),
+        );
+
+        // a Friday in Paris, and the one show being watched airs that evening there
+        let now: DateTime<Utc> = "2023-10-20T12:00:00Z".parse().unwrap();
+        assert_eq!(app.schedule_day(now), 4);
+        let week = app.schedule_week(now);
+        let shows: Vec<u64> = week[4].iter().map(|airing| airing.anime.id).collect();
+        assert_eq!(shows, vec![1]);
+        assert_eq!(week.iter().map(Vec::len).sum::<usize>(), 1);
+
+        app.schedule.day = Some(6);
+        assert_eq!(app.schedule_day(now), 6);
+    }
+
+    #[test]
+    fn test_backlog_order() {
+        let (mut app, _io_rx) = test_app();
+        let show = |id: u64, status: &str| {
+            serde_json::json!({ "node": {
+                "id": id,
+                "title": "Monster",
+                "my_list_status": {
+                    "status": status,
+                    "score": 0,
+                    "num_episodes_watched": 0,
+                    "is_rewatching": false,
+                    "updated_at": "2023-10-20T12:00:00+00:00"
+                }
+            } })
+        };
+        app.anime_list = Some(
+            serde_json::from_value(serde_json::json!({
+                "data": [show(1, "plan_to_watch"), show(2, "completed"), show(3, "watching")],
+                "paging": {}
+            }))
+            .unwrap(),
+        );
+        let ids =
+            |app: &App| -> Vec<u64> { app.backlog_queue().iter().map(|anime| anime.id).collect() };
+        assert_eq!(ids(&app), vec![3, 1]);
+
+        // the cursor follows the show it moves
+        app.backlog.selected_index = 1;
+        app.move_backlog_item(true);
+        assert_eq!(ids(&app), vec![1, 3]);
+        assert_eq!(app.backlog.selected_index, 0);
+        app.move_backlog_item(true);
+        assert_eq!(ids(&app), vec![1, 3]);
+        app.move_backlog_item(false);
+        assert_eq!(ids(&app), vec![3, 1]);
+    }
+
+    fn list_entry(id: u64, title: &str, tags: &[&str]) -> serde_json::Value {
+        serde_json::json!({ "node": {
+            "id": id,
+            "title": title,
+            "my_list_status": {
+                "status": "plan_to_watch",
+                "score": 0,
+                "num_episodes_watched": 0,
+                "is_rewatching": false,
+                "tags": tags,
+                "updated_at": "2023-10-20T12:00:00+00:00"
+            }
+        } })
+    }
+
+    #[test]
+    fn test_profile_switched() {
+        let (mut app, _io_rx) = test_app();
+        app.dispatch(IoEvent::GetAnimeList("@me".to_string()));
+        app.backlog.order = vec![1];
+        app.anime_ranking =
+            Some(serde_json::from_value(serde_json::json!({ "data": [], "paging": {} })).unwrap());
+        app.dispatch(IoEvent::SwitchProfile("other".to_string()));
+        app.handle_io_response(
+            1,
+            IoResponse::ProfileSwitched {
+                name: "other".to_string(),
+                app_config: AppConfig::default(),
+                user: serde_json::from_value(serde_json::json!({
+                    "id": 2,
+                    "name": "other",
+                    "picture": "",
+                    "joined_at": "2020-01-01T00:00:00+00:00"
+                }))
+                .unwrap(),
+                searches: Searches::default(),
+            },
+        );
+        assert!(app.anime_ranking.is_none());
+        assert!(app.backlog.order.is_empty());
+
+        // the old account's list coming in late is dropped
+        app.handle_io_response(
+            0,
+            IoResponse::AnimeList(
+                serde_json::from_value(serde_json::json!({
+                    "data": [list_entry(1, "Monster", &[])],
+                    "paging": {}
+                }))
+                .unwrap(),
+            ),
+        );
+        assert!(app.anime_list.is_none());
+        app.handle_io_response(1, IoResponse::Done);
+        assert!(!app.is_loading());
+    }
+
+    #[test]
+    fn test_marks() {
+        let (mut app, _io_rx) = test_app();
+        app.anime_list = Some(
+            serde_json::from_value(serde_json::json!({
+                "data": (1..=5).map(|id| list_entry(id, "Monster", &[])).collect::<Vec<_>>(),
+                "paging": {}
+            }))
+            .unwrap(),
+        );
+        let view = EntryView::AnimeList;
+        app.push_navigation_stack(RouteId::AnimeList, ActiveBlock::Entries);
+        assert_eq!(app.batch_targets(view), vec![1]);
+
+        app.toggle_mark(view);
+        app.views.get_mut(&view).unwrap().selected_index = 1;
+        app.toggle_range(view);
+        app.views.get_mut(&view).unwrap().selected_index = 3;
+        // the range is picked while the cursor moves, and marked with the second `V`
+        assert_eq!(app.batch_targets(view), vec![1, 2, 3, 4]);
+        app.toggle_range(view);
+        assert!(app.views[&view].range_start.is_none());
+        app.toggle_mark(view);
+        assert_eq!(app.batch_targets(view), vec![1, 2, 3]);
+
+        assert!(app.clear_marks(view));
+        assert!(!app.clear_marks(view));
+        assert_eq!(app.batch_targets(view), vec![4]);
+    }
+
+    #[test]
+    fn test_batch() {
+        let (mut app, mut io_rx) = test_app();
+        app.anime_list = Some(
+            serde_json::from_value(serde_json::json!({
+                "data": [list_entry(1, "Monster", &["old"]), list_entry(2, "Cowboy Bebop", &[])],
+                "paging": {}
+            }))
+            .unwrap(),
+        );
+        let view = EntryView::AnimeList;
+        app.push_navigation_stack(RouteId::AnimeList, ActiveBlock::Entries);
+        app.toggle_mark(view);
+        app.views.get_mut(&view).unwrap().selected_index = 1;
+        app.toggle_mark(view);
+        app.open_batch_menu();
+        assert_eq!(app.get_current_route().active_block, ActiveBlock::BatchEdit);
+
+        app.run_batch(BatchAction::AddTags(vec!["new".to_string()]));
+        let (id, changes) = match io_rx.try_recv() {
+            Ok((id, IoEvent::UpdateAnimeListBatch(changes))) => (id, changes),
+            _ => panic!("the batch wasn't sent"),
+        };
+        let tags: Vec<(u64, Option<String>)> = changes
+            .into_iter()
+            .map(|(id, update)| (id, update.unwrap().tags))
+            .collect();
+        assert_eq!(
+            tags,
+            vec![
+                (1, Some("old,new".to_string())),
+                (2, Some("new".to_string()))
+            ]
+        );
+        assert!(app.views[&view].marked.is_empty());
+
+        // a failure doesn't stop the rest, it's listed once all are done
+        app.handle_io_response(id, IoResponse::BatchItem(1, None));
+        app.handle_io_response(id, IoResponse::BatchItem(2, Some("Not found".to_string())));
+        let progress = &app.batch.as_ref().unwrap().progress;
+        assert!(progress.is_finished());
+        assert_eq!(progress.succeeded(), 1);
+        assert_eq!(
+            progress.failures,
+            vec![("Cowboy Bebop".to_string(), "Not found".to_string())]
+        );
+        assert_eq!(app.get_current_route().active_block, ActiveBlock::BatchEdit);
+    }
+
+    #[test]
+    fn test_recall_history() {
+        let (mut app, _io_rx) = test_app();
+        app.searches.push_history("monster");
+        app.searches.push_history("bebop");
+
+        app.recall_history(true);
+        assert_eq!(app.input.iter().collect::<String>(), "bebop");
+        assert_eq!(app.input_idx, 5);
+        app.recall_history(true);
+        app.recall_history(true);
+        assert_eq!(app.input.iter().collect::<String>(), "monster");
+        app.recall_history(false);
+        app.recall_history(false);
+        assert!(app.input.is_empty());
+        assert_eq!(app.history_index, None);
+    }
+
+    #[test]
+    fn test_history_popup() {
+        let (mut app, _io_rx) = test_app();
+        app.searches.push_history("made in abyss");
+        app.searches.push_history("type:movie genre:mecha");
+        app.searches.push_history("monster");
+
+        app.open_history_popup();
+        assert_eq!(app.selected_history_entry().unwrap(), "monster");
+        assert_eq!(app.history_popup.matches.len(), 3);
+
+        app.history_popup.filter = "abys".to_string();
+        app.update_history_popup();
+        assert_eq!(app.history_popup.matches, vec![0]);
+        assert_eq!(app.selected_history_entry().unwrap(), "made in abyss");
+    }
+
+    #[test]
+    fn test_saved_search() {
+        let (mut app, mut io_rx) = test_app();
+        app.set_input("type:movie genre:mecha");
+        app.save_prompt.name = "mecha movies".to_string();
+        app.save_prompt.scope = SearchScope::Anime;
+        app.save_search();
+        app.set_input("");
+
+        app.run_saved_search();
+        assert_eq!(
+            app.input.iter().collect::<String>(),
+            "type:movie genre:mecha"
+        );
+        assert!(matches!(
+            io_rx.try_recv(),
+            Ok((_, IoEvent::GetSearchResults(q, SearchScope::Anime))) if q == "type:movie genre:mecha"
+        ));
+        assert_eq!(app.searches.history, vec!["type:movie genre:mecha"]);
+
+        app.delete_saved_search();
+        assert!(app.searches.saved.is_empty());
+    }
+
+    #[test]
+    fn test_suggestions_ignore_filters() {
+        let (mut app, _io_rx) = test_app();
+
+        type_in(&mut app, "bebop");
+        app.send_suggestions_query(app.suggestions.deadline().unwrap());
+        type_in(&mut app, "bebop score>");
+        type_in(&mut app, "bebop score>8 -type:tv");
+
+        // only the title is searched, so the suggestions stay
+        assert_eq!(app.suggestions.query, "bebop");
+        assert!(app.suggestions.deadline().is_none());
+    }
+
+    #[test]
+    fn test_stale_suggestions() {
+        let (mut app, _io_rx) = test_app();
+        let page: Page<Anime> = serde_json::from_value(serde_json::json!({
+            "data": [{ "node": { "id": 1, "title": "Cowboy Bebop" } }],
+            "paging": {}
+        }))
+        .unwrap();
+
+        type_in(&mut app, "bebop");
+        app.send_suggestions_query(app.suggestions.deadline().unwrap());
+        type_in(&mut app, "beboq");
+
+        // the results for the old query don't show up under the new one
+        app.handle_io_response(0, IoResponse::AnimeSuggestions("bebop".to_string(), page));
+        assert!(app.suggestions.is_empty());
+    }
+
+    #[test]
+    fn test_selected_suggestion() {
+        let mut suggestions = Suggestions {
+            anime: vec![
+                serde_json::from_value(serde_json::json!({ "id": 1, "title": "Monster" })).unwrap(),
+            ],
+            manga: vec![
+                serde_json::from_value(serde_json::json!({ "id": 1, "title": "Monster" })).unwrap(),
+            ],
+            ..Default::default()
+        };
+
+        assert_eq!(suggestions.selected(), None);
+        suggestions.selected_index = Some(0);
+        assert_eq!(suggestions.selected(), Some(Suggestion::Anime(1)));
+        suggestions.selected_index = Some(1);
+        assert_eq!(suggestions.selected(), Some(Suggestion::Manga(1)));
+    }
+}
@@ -0,0 +1,163 @@
+use super::store::{self, StorePaths};
+use super::{load_auth_async, login_async, open_store, LoginMode, OAuth};
+use crate::api::{self, GetUserInformationQuery};
+use crate::cli::AuthCommand;
+use crate::config::{AuthConfig, Profile};
+use eyre::{eyre, WrapErr};
+use std::time::SystemTime;
+
+/// Runs an auth subcommand for `profile`
+pub async fn run(command: AuthCommand, profile: &Profile, mode: LoginMode) -> eyre::Result<()> {
+    match command {
+        AuthCommand::Login => login(profile, mode).await,
+        AuthCommand::Logout => logout(profile),
+        AuthCommand::Status => status(profile).await,
+    }
+}
+
+async fn login(profile: &Profile, mode: LoginMode) -> eyre::Result<()> {
+    let config = AuthConfig::load(profile)?;
+    let store = open_store(&config, profile, true)?;
+    let auth = login_async(&config, store.as_ref(), mode).await?;
+
+    let user = user_name(&auth).await;
+    println!(
+        "Logged in as {} on profile `{}`, the token is in the {}",
+        user,
+        profile.name,
+        store.name()
+    );
+    Ok(())
+}
+
+fn logout(profile: &Profile) -> eyre::Result<()> {
+    ensure_exists(profile)?;
+    let paths = profile.paths();
+    let paths = StorePaths {
+        plaintext: paths.auth_cache_path,
+        encrypted: paths.encrypted_auth_cache_path,
+    };
+    let deleted = store::delete_everywhere(&profile.name, &paths)?;
+
+    if deleted.is_empty() {
+        println!("Profile `{}` is not logged in", profile.name);
+    } else {
+        println!(
+            "Logged out of profile `{}`, removed the token from the {}",
+            profile.name,
+            deleted.join(" and the ")
+        );
+        println!("MyAnimeList has no way to revoke a token, so it stays valid until it expires");
+    }
+    Ok(())
+}
+
+async fn status(profile: &Profile) -> eyre::Result<()> {
+    ensure_exists(profile)?;
+    println!("Profile: {}", profile.name);
+    let config = match AuthConfig::read(profile)? {
+        Some(config) => config,
+        None => {
+            println!("Not set up, run `mal auth login`");
+            return Ok(());
+        }
+    };
+
+    let store = open_store(&config, profile, true)?;
+    println!("Token store: {}", store.name());
+    let auth = match load_auth_async(store.as_ref())
+        .await
+        .wrap_err("The stored token could not be refreshed, run `mal auth login`")?
+    {
+        Some(auth) => auth,
+        None => {
+            println!("Not logged in, run `mal auth login`");
+            return Ok(());
+        }
+    };
+
+    println!("User: {}", user_name(&auth).await);
+    if let Some(token) = auth.token() {
+        let expiry = match (token.expire_time(), token.expires_in_secs()) {
+            (Some(time), Some(secs)) => {
+                format!("{} ({})", format_time(time), format_remaining(secs))
+            }
+            _ => "expired".to_string(),
+        };
+        println!("Expires: {}", expiry);
+
+        let scopes = match token.token.scopes() {
+            Some(scopes) if scopes.is_empty() => "none".to_string(),
+            Some(scopes) => scopes.join(", "),
+            None => "unknown".to_string(),
+        };
+        println!("Scopes: {}", scopes);
+    }
+    Ok(())
+}
+
+/// Fails for a profile that was never set up, e.g. a typo in `--profile`
+fn ensure_exists(profile: &Profile) -> eyre::Result<()> {
+    if profile.exists() {
+        Ok(())
+    } else {
+        Err(eyre!("No such profile `{}`", profile.name))
+    }
+}
+
+/// The name of the logged in user, or why it couldn't be fetched
+async fn user_name(auth: &OAuth) -> String {
+    let query = GetUserInformationQuery { fields: None };
+    match api::get_my_user_information("@me", &query, auth).await {
+        Ok(user) => user.name,
+        Err(e) => format!("unknown user ({})", e),
+    }
+}
+
+fn format_time(time: SystemTime) -> String {
+    time::OffsetDateTime::from(time).format("%F %R UTC")
+}
+
+fn format_remaining(secs: u64) -> String {
+    let (count, unit) = match secs {
+        0..=59 => (secs, "second"),
+        60..=3599 => (secs / 60, "minute"),
+        3600..=86399 => (secs / 3600, "hour"),
+        _ => (secs / 86400, "day"),
+    };
+    let plural = if count == 1 { "" } else { "s" };
+    format!("in {} {}{}", count, unit, plural)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ensure_exists() {
+        let root = tempfile::tempdir().unwrap();
+        let work = Profile::in_dir(root.path(), "work").unwrap();
+        assert!(ensure_exists(&work.default_profile()).is_ok());
+
+        let error = ensure_exists(&work).unwrap_err();
+        assert_eq!(error.to_string(), "No such profile `work`");
+        assert!(!root.path().join("profiles").exists());
+
+        work.ensure_dir().unwrap();
+        assert!(ensure_exists(&work).is_ok());
+    }
+
+    #[test]
+    fn test_format_remaining() {
+        assert_eq!(format_remaining(1), "in 1 second");
+        assert_eq!(format_remaining(150), "in 2 minutes");
+        assert_eq!(format_remaining(3600), "in 1 hour");
+        assert_eq!(format_remaining(2678400), "in 31 days");
+    }
+
+    #[test]
+    fn test_format_time() {
+        let time = SystemTime::UNIX_EPOCH + std::time::Duration::from_secs(1617800400);
+        assert_eq!(format_time(time), "2021-04-07 13:00 UTC");
+    }
+}
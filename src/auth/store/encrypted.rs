@@ -0,0 +1,205 @@
+use super::{atomic_write, CredentialStore, StoreError};
+use crate::auth::OAuth;
+use chacha20poly1305::{
+    aead::{Aead, AeadCore, KeyInit, OsRng},
+    XChaCha20Poly1305, XNonce,
+};
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
+use sha2::Sha256;
+use std::{
+    fs,
+    io::ErrorKind,
+    path::{Path, PathBuf},
+};
+
+/// Environment variable read before prompting for the passphrase
+pub const PASSPHRASE_ENV: &str = "MAL_CLI_PASSPHRASE";
+
+const FORMAT_VERSION: u32 = 1;
+const DEFAULT_ITERATIONS: u32 = 600_000;
+const SALT_LENGTH: usize = 16;
+
+/// Keeps the token in a file encrypted with XChaCha20-Poly1305, using a key
+/// derived from a passphrase with PBKDF2-HMAC-SHA256
+pub struct EncryptedFileStore {
+    path: PathBuf,
+    passphrase: String,
+    iterations: u32,
+}
+
+/// On disk format, binary fields are base64 encoded
+#[derive(Debug, Serialize, Deserialize)]
+struct EncryptedFile {
+    version: u32,
+    iterations: u32,
+    salt: String,
+    nonce: String,
+    ciphertext: String,
+}
+
+impl EncryptedFileStore {
+    pub const NAME: &'static str = "encrypted file";
+
+    pub fn new<P: AsRef<Path>, S: ToString>(path: P, passphrase: S) -> Self {
+        Self {
+            path: path.as_ref().to_path_buf(),
+            passphrase: passphrase.to_string(),
+            iterations: DEFAULT_ITERATIONS,
+        }
+    }
+
+    /// Opens the store with the passphrase from `$MAL_CLI_PASSPHRASE`,
+    /// prompting for it (twice for a new file) if it isn't set
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self, StoreError> {
+        if let Some(store) = Self::from_env(&path) {
+            return Ok(store);
+        }
+
+        let passphrase = rpassword::read_password_from_tty(Some("Token file passphrase: "))?;
+        if !path.as_ref().exists() {
+            let confirmation = rpassword::read_password_from_tty(Some("Repeat passphrase: "))?;
+            if passphrase != confirmation {
+                return Err(StoreError::InvalidFormat(
+                    "The passphrases don't match".to_string(),
+                ));
+            }
+        }
+        Ok(Self::new(path, passphrase))
+    }
+
+    /// Opens the store with the passphrase from `$MAL_CLI_PASSPHRASE` if it is set
+    pub fn from_env<P: AsRef<Path>>(path: P) -> Option<Self> {
+        std::env::var(PASSPHRASE_ENV)
+            .ok()
+            .map(|passphrase| Self::new(path, passphrase))
+    }
+
+    /// Key derivation rounds for new files, existing files keep theirs
+    pub fn with_iterations(mut self, iterations: u32) -> Self {
+        self.iterations = iterations;
+        self
+    }
+
+    fn cipher(&self, salt: &[u8], iterations: u32) -> XChaCha20Poly1305 {
+        let key =
+            pbkdf2::pbkdf2_hmac_array::<Sha256, 32>(self.passphrase.as_bytes(), salt, iterations);
+        XChaCha20Poly1305::new(&key.into())
+    }
+}
+
+impl CredentialStore for EncryptedFileStore {
+    fn name(&self) -> &'static str {
+        Self::NAME
+    }
+
+    fn load(&self) -> Result<Option<OAuth>, StoreError> {
+        let contents = match fs::read_to_string(&self.path) {
+            Ok(contents) => contents,
+            Err(e) if e.kind() == ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(e.into()),
+        };
+        let file: EncryptedFile = serde_json::from_str(&contents)?;
+        if file.version != FORMAT_VERSION {
+            return Err(StoreError::InvalidFormat(format!(
+                "unknown version {}",
+                file.version
+            )));
+        }
+
+        let decode = |field: &str| {
+            base64::decode(field).map_err(|e| StoreError::InvalidFormat(e.to_string()))
+        };
+        let salt = decode(&file.salt)?;
+        let nonce = decode(&file.nonce)?;
+        if nonce.len() != 24 {
+            return Err(StoreError::InvalidFormat("invalid nonce".to_string()));
+        }
+
+        let plaintext = self
+            .cipher(&salt, file.iterations)
+            .decrypt(
+                XNonce::from_slice(&nonce),
+                decode(&file.ciphertext)?.as_ref(),
+            )
+            .map_err(|_| StoreError::DecryptError)?;
+        Ok(Some(serde_json::from_slice(&plaintext)?))
+    }
+
+    fn save(&self, auth: &OAuth) -> Result<(), StoreError> {
+        let mut salt = [0; SALT_LENGTH];
+        rand::thread_rng().fill_bytes(&mut salt);
+        let nonce = XChaCha20Poly1305::generate_nonce(&mut OsRng);
+
+        let ciphertext = self
+            .cipher(&salt, self.iterations)
+            .encrypt(&nonce, serde_json::to_vec(auth)?.as_ref())
+            .map_err(|_| StoreError::InvalidFormat("encryption failed".to_string()))?;
+
+        let file = EncryptedFile {
+            version: FORMAT_VERSION,
+            iterations: self.iterations,
+            salt: base64::encode(salt),
+            nonce: base64::encode(nonce),
+            ciphertext: base64::encode(ciphertext),
+        };
+        Ok(atomic_write(
+            &self.path,
+            serde_json::to_string_pretty(&file)?.as_bytes(),
+        )?)
+    }
+
+    fn delete(&self) -> Result<bool, StoreError> {
+        match fs::remove_file(&self.path) {
+            Ok(()) => Ok(true),
+            Err(e) if e.kind() == ErrorKind::NotFound => Ok(false),
+            Err(e) => Err(e.into()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::auth::store::tests::test_auth;
+
+    #[test]
+    fn test_encrypted_store() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("token.enc");
+        let store = EncryptedFileStore::new(&path, "correct horse").with_iterations(10);
+        assert!(store.load().unwrap().is_none());
+
+        let auth = test_auth();
+        store.save(&auth).unwrap();
+        assert_eq!(store.load().unwrap().unwrap().state, auth.state);
+
+        // nothing readable ends up on disk
+        let contents = fs::read_to_string(&path).unwrap();
+        assert!(!contents.contains(&auth.state));
+        assert!(!contents.contains("client_id"));
+
+        let wrong = EncryptedFileStore::new(&path, "battery staple");
+        assert!(matches!(wrong.load(), Err(StoreError::DecryptError)));
+
+        assert!(store.delete().unwrap());
+        assert!(store.load().unwrap().is_none());
+    }
+
+    #[test]
+    fn test_tampered_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("token.enc");
+        let store = EncryptedFileStore::new(&path, "passphrase").with_iterations(10);
+        store.save(&test_auth()).unwrap();
+
+        let mut file: EncryptedFile =
+            serde_json::from_str(&fs::read_to_string(&path).unwrap()).unwrap();
+        let mut ciphertext = base64::decode(&file.ciphertext).unwrap();
+        ciphertext[0] ^= 1;
+        file.ciphertext = base64::encode(ciphertext);
+        fs::write(&path, serde_json::to_string(&file).unwrap()).unwrap();
+
+        assert!(matches!(store.load(), Err(StoreError::DecryptError)));
+    }
+}
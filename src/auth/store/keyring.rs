@@ -0,0 +1,86 @@
+use super::{CredentialStore, StoreError};
+use crate::auth::OAuth;
+use secret_service::blocking::{Collection, SecretService};
+use secret_service::EncryptionType;
+use std::collections::HashMap;
+
+const SERVICE: &str = "mal-cli";
+
+/// Keeps the token in the desktop keyring (GNOME Keyring, KWallet, ...)
+/// through the Secret Service D-Bus api
+pub struct KeyringStore {
+    account: String,
+}
+
+impl KeyringStore {
+    pub const NAME: &'static str = "keyring";
+
+    /// Fails if no Secret Service is running on the session bus
+    pub fn connect<A: ToString>(account: A) -> Result<Self, StoreError> {
+        let store = Self {
+            account: account.to_string(),
+        };
+        store.with_collection(|_| Ok(()))?;
+        Ok(store)
+    }
+
+    fn attributes(&self) -> HashMap<&str, &str> {
+        let mut attributes = HashMap::new();
+        attributes.insert("service", SERVICE);
+        attributes.insert("account", self.account.as_str());
+        attributes
+    }
+
+    /// The connection can't outlive the call, so every operation opens its own
+    fn with_collection<T, F>(&self, f: F) -> Result<T, StoreError>
+    where
+        F: FnOnce(&Collection) -> Result<T, StoreError>,
+    {
+        let service = SecretService::connect(EncryptionType::Dh)?;
+        let collection = match service.get_default_collection() {
+            Ok(collection) => collection,
+            Err(_) => service.get_any_collection()?,
+        };
+        collection.ensure_unlocked()?;
+        f(&collection)
+    }
+}
+
+impl CredentialStore for KeyringStore {
+    fn name(&self) -> &'static str {
+        Self::NAME
+    }
+
+    fn load(&self) -> Result<Option<OAuth>, StoreError> {
+        self.with_collection(|collection| {
+            match collection.search_items(self.attributes())?.first() {
+                Some(item) => Ok(Some(serde_json::from_slice(&item.get_secret()?)?)),
+                None => Ok(None),
+            }
+        })
+    }
+
+    fn save(&self, auth: &OAuth) -> Result<(), StoreError> {
+        let secret = serde_json::to_vec(auth)?;
+        self.with_collection(|collection| {
+            collection.create_item(
+                &format!("{} ({})", SERVICE, self.account),
+                self.attributes(),
+                &secret,
+                true,
+                "application/json",
+            )?;
+            Ok(())
+        })
+    }
+
+    fn delete(&self) -> Result<bool, StoreError> {
+        self.with_collection(|collection| {
+            let items = collection.search_items(self.attributes())?;
+            for item in &items {
+                item.delete()?;
+            }
+            Ok(!items.is_empty())
+        })
+    }
+}
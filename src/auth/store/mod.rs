@@ -0,0 +1,337 @@
+/// Secret Service keyring backend
+pub mod keyring;
+pub use keyring::KeyringStore;
+
+/// Passphrase encrypted file backend
+pub mod encrypted;
+pub use encrypted::EncryptedFileStore;
+
+/// Plaintext file backend
+pub mod plaintext;
+pub use plaintext::PlaintextFileStore;
+
+use super::OAuth;
+use serde::{Deserialize, Serialize};
+use std::{
+    fs,
+    io::{self, Write},
+    path::{Path, PathBuf},
+};
+
+/// Somewhere to keep the oauth2 state (including the refresh token) between runs
+pub trait CredentialStore: Send + Sync {
+    /// Short name of the backend, e.g. for `auth status`
+    fn name(&self) -> &'static str;
+
+    /// Returns `None` if nothing is stored yet
+    fn load(&self) -> Result<Option<OAuth>, StoreError>;
+
+    fn save(&self, auth: &OAuth) -> Result<(), StoreError>;
+
+    /// Forget the stored credentials, returns `false` if there were none
+    fn delete(&self) -> Result<bool, StoreError>;
+}
+
+/// Which backend to use, set with `token_store` in oauth2.yml
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StoreKind {
+    /// The keyring if a Secret Service is running, the plaintext file otherwise
+    #[default]
+    Auto,
+    Keyring,
+    Encrypted,
+    Plaintext,
+}
+
+#[derive(Debug)]
+pub enum StoreError {
+    /// Represents a failure to read or write the token file
+    IOError(std::io::Error),
+    /// Represents stored credentials that aren't valid json
+    ParseError(serde_json::Error),
+    /// Represents a Secret Service failure
+    KeyringError(secret_service::Error),
+    /// Represents a wrong passphrase or a tampered token file
+    DecryptError,
+    /// Represents a token file in a format we don't know
+    InvalidFormat(String),
+    /// Represents an encrypted file opened where we can't prompt for the passphrase
+    PassphraseRequired,
+}
+
+impl std::error::Error for StoreError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match *self {
+            StoreError::IOError(ref err) => Some(err),
+            StoreError::ParseError(ref err) => Some(err),
+            StoreError::KeyringError(ref err) => Some(err),
+            StoreError::DecryptError => None,
+            StoreError::InvalidFormat(_) => None,
+            StoreError::PassphraseRequired => None,
+        }
+    }
+}
+
+impl std::fmt::Display for StoreError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match *self {
+            StoreError::IOError(ref err) => err.fmt(f),
+            StoreError::ParseError(ref err) => err.fmt(f),
+            StoreError::KeyringError(ref err) => write!(f, "Keyring error: {}", err),
+            StoreError::DecryptError => write!(
+                f,
+                "Could not decrypt the token file (wrong passphrase or corrupted file)"
+            ),
+            StoreError::InvalidFormat(ref err) => write!(f, "Invalid token file: {}", err),
+            StoreError::PassphraseRequired => write!(
+                f,
+                "The token file is encrypted, set ${} to open it here",
+                encrypted::PASSPHRASE_ENV
+            ),
+        }
+    }
+}
+
+impl From<std::io::Error> for StoreError {
+    fn from(err: std::io::Error) -> Self {
+        StoreError::IOError(err)
+    }
+}
+
+impl From<serde_json::Error> for StoreError {
+    fn from(err: serde_json::Error) -> Self {
+        StoreError::ParseError(err)
+    }
+}
+
+impl From<secret_service::Error> for StoreError {
+    fn from(err: secret_service::Error) -> Self {
+        StoreError::KeyringError(err)
+    }
+}
+
+/// Opens the configured store and moves a token cache left by older versions into it
+///
+/// `account` tells apart the keyring entries of different profiles. Unless
+/// `interactive` is set, an encrypted file can only be opened with the passphrase
+/// in the environment.
+pub fn open_store(
+    kind: StoreKind,
+    account: &str,
+    paths: &StorePaths,
+    interactive: bool,
+) -> Result<Box<dyn CredentialStore>, StoreError> {
+    let store: Box<dyn CredentialStore> = match kind {
+        StoreKind::Auto => match KeyringStore::connect(account) {
+            Ok(store) => Box::new(store),
+            Err(_) => Box::new(PlaintextFileStore::new(&paths.plaintext)),
+        },
+        StoreKind::Keyring => Box::new(KeyringStore::connect(account)?),
+        StoreKind::Encrypted if interactive => {
+            Box::new(EncryptedFileStore::open(&paths.encrypted)?)
+        }
+        StoreKind::Encrypted => Box::new(
+            EncryptedFileStore::from_env(&paths.encrypted).ok_or(StoreError::PassphraseRequired)?,
+        ),
+        StoreKind::Plaintext => Box::new(PlaintextFileStore::new(&paths.plaintext)),
+    };
+    migrate_legacy_cache(store.as_ref(), &paths.plaintext)?;
+    Ok(store)
+}
+
+/// Removes the credentials of `account` from every backend, not only the configured
+/// one, since `token_store` may have changed since they were saved.
+/// Returns the names of the backends that held some.
+pub fn delete_everywhere(
+    account: &str,
+    paths: &StorePaths,
+) -> Result<Vec<&'static str>, StoreError> {
+    let mut stores: Vec<Box<dyn CredentialStore>> = vec![
+        Box::new(PlaintextFileStore::new(&paths.plaintext)),
+        // removing the file doesn't need the passphrase
+        Box::new(EncryptedFileStore::new(&paths.encrypted, "")),
+    ];
+    if let Ok(keyring) = KeyringStore::connect(account) {
+        stores.push(Box::new(keyring));
+    }
+
+    let mut deleted = vec![];
+    for store in stores {
+        if store.delete()? {
+            deleted.push(store.name());
+        }
+    }
+    Ok(deleted)
+}
+
+/// Where the file backends keep their tokens
+#[derive(Clone, Debug)]
+pub struct StorePaths {
+    pub plaintext: PathBuf,
+    pub encrypted: PathBuf,
+}
+
+/// Older versions wrote the token cache in plaintext with default permissions
+/// and without truncating, so it may be world readable and have trailing garbage.
+fn migrate_legacy_cache(store: &dyn CredentialStore, legacy_path: &Path) -> Result<(), StoreError> {
+    let contents = match fs::read_to_string(legacy_path) {
+        Ok(contents) => contents,
+        Err(_) => return Ok(()),
+    };
+    let legacy = read_first_json::<OAuth>(&contents);
+
+    if store.name() == PlaintextFileStore::NAME {
+        // same file, rewrite it cleanly if it needs it
+        let is_clean = serde_json::from_str::<OAuth>(&contents).is_ok();
+        match legacy {
+            Some(auth) if !is_clean => store.save(&auth)?,
+            _ => plaintext::restrict_permissions(legacy_path)?,
+        }
+        return Ok(());
+    }
+
+    if let Some(auth) = legacy {
+        if store.load()?.is_none() {
+            store.save(&auth)?;
+        }
+    }
+    fs::remove_file(legacy_path)?;
+    Ok(())
+}
+
+/// Parses the first json value in `s`, ignoring anything after it
+fn read_first_json<T: serde::de::DeserializeOwned>(s: &str) -> Option<T> {
+    serde_json::Deserializer::from_str(s)
+        .into_iter::<T>()
+        .next()
+        .and_then(Result::ok)
+}
+
+/// Writes `contents` to a temporary file next to `path` and renames it over `path`,
+/// so a crash never leaves a half written file behind. The file is only readable by us.
+pub(crate) fn atomic_write(path: &Path, contents: &[u8]) -> io::Result<()> {
+    let dir = path.parent().unwrap_or_else(|| Path::new("."));
+    fs::create_dir_all(dir)?;
+
+    let file_name = path
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    let tmp_path = dir.join(format!(".{}.{}.tmp", file_name, std::process::id()));
+
+    let result = (|| {
+        let mut file = private_file_options().open(&tmp_path)?;
+        file.write_all(contents)?;
+        file.sync_all()?;
+        fs::rename(&tmp_path, path)
+    })();
+    if result.is_err() {
+        let _ = fs::remove_file(&tmp_path);
+    }
+    result
+}
+
+#[cfg(unix)]
+fn private_file_options() -> fs::OpenOptions {
+    use std::os::unix::fs::OpenOptionsExt;
+    let mut options = fs::OpenOptions::new();
+    options.write(true).create(true).truncate(true).mode(0o600);
+    options
+}
+
+#[cfg(not(unix))]
+fn private_file_options() -> fs::OpenOptions {
+    let mut options = fs::OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    options
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+
+    pub fn test_auth() -> OAuth {
+        let mut auth = OAuth::new("mal-cli", "client_id", None, "127.0.0.1:7878");
+        auth.auth_code = Some("code".to_string());
+        auth
+    }
+
+    #[test]
+    fn test_atomic_write() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("token.json");
+
+        atomic_write(&path, b"a much longer first version").unwrap();
+        atomic_write(&path, b"short").unwrap();
+
+        assert_eq!(fs::read_to_string(&path).unwrap(), "short");
+        assert_eq!(fs::read_dir(dir.path()).unwrap().count(), 1);
+    }
+
+    #[test]
+    fn test_migrate_legacy_cache() {
+        let dir = tempfile::tempdir().unwrap();
+        let paths = StorePaths {
+            plaintext: dir.path().join(".mal_token_cache.json"),
+            encrypted: dir.path().join(".mal_token_cache.enc"),
+        };
+        // an old cache that was overwritten by a shorter one without truncating
+        let auth = test_auth();
+        let legacy = format!("{}{}", serde_json::to_string(&auth).unwrap(), "\"}}");
+        fs::write(&paths.plaintext, legacy).unwrap();
+
+        let store = EncryptedFileStore::new(&paths.encrypted, "passphrase").with_iterations(10);
+        migrate_legacy_cache(&store, &paths.plaintext).unwrap();
+
+        assert!(!paths.plaintext.exists());
+        assert_eq!(store.load().unwrap().unwrap().state, auth.state);
+    }
+
+    #[test]
+    fn test_migrate_legacy_plaintext_cache() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(".mal_token_cache.json");
+        let auth = test_auth();
+        let legacy = format!("{}{}", serde_json::to_string(&auth).unwrap(), "garbage");
+        fs::write(&path, legacy).unwrap();
+
+        let store = PlaintextFileStore::new(&path);
+        migrate_legacy_cache(&store, &path).unwrap();
+
+        assert_eq!(store.load().unwrap().unwrap().state, auth.state);
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = fs::metadata(&path).unwrap().permissions().mode();
+            assert_eq!(mode & 0o777, 0o600);
+        }
+    }
+
+    #[test]
+    fn test_delete_everywhere() {
+        let dir = tempfile::tempdir().unwrap();
+        let paths = StorePaths {
+            plaintext: dir.path().join(".mal_token_cache.json"),
+            encrypted: dir.path().join(".mal_token_cache.enc"),
+        };
+        let auth = test_auth();
+        PlaintextFileStore::new(&paths.plaintext)
+            .save(&auth)
+            .unwrap();
+        EncryptedFileStore::new(&paths.encrypted, "passphrase")
+            .with_iterations(10)
+            .save(&auth)
+            .unwrap();
+
+        let deleted = delete_everywhere("mal-cli-test", &paths).unwrap();
+        assert!(deleted.contains(&PlaintextFileStore::NAME));
+        assert!(deleted.contains(&EncryptedFileStore::NAME));
+        assert!(!paths.plaintext.exists());
+        assert!(!paths.encrypted.exists());
+
+        assert!(delete_everywhere("mal-cli-test", &paths)
+            .unwrap()
+            .is_empty());
+    }
+}
@@ -0,0 +1,90 @@
+use super::{atomic_write, CredentialStore, StoreError};
+use crate::auth::OAuth;
+use std::{
+    fs,
+    io::ErrorKind,
+    path::{Path, PathBuf},
+};
+
+/// Keeps the token as json in a file only the owner can read
+///
+/// The fallback for systems without a keyring.
+pub struct PlaintextFileStore {
+    path: PathBuf,
+}
+
+impl PlaintextFileStore {
+    pub const NAME: &'static str = "plaintext file";
+
+    pub fn new<P: AsRef<Path>>(path: P) -> Self {
+        Self {
+            path: path.as_ref().to_path_buf(),
+        }
+    }
+}
+
+impl CredentialStore for PlaintextFileStore {
+    fn name(&self) -> &'static str {
+        Self::NAME
+    }
+
+    fn load(&self) -> Result<Option<OAuth>, StoreError> {
+        match fs::read_to_string(&self.path) {
+            Ok(contents) if contents.trim().is_empty() => Ok(None),
+            Ok(contents) => Ok(Some(serde_json::from_str(&contents)?)),
+            Err(e) if e.kind() == ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    fn save(&self, auth: &OAuth) -> Result<(), StoreError> {
+        Ok(atomic_write(
+            &self.path,
+            serde_json::to_string(auth)?.as_bytes(),
+        )?)
+    }
+
+    fn delete(&self) -> Result<bool, StoreError> {
+        match fs::remove_file(&self.path) {
+            Ok(()) => Ok(true),
+            Err(e) if e.kind() == ErrorKind::NotFound => Ok(false),
+            Err(e) => Err(e.into()),
+        }
+    }
+}
+
+/// Makes `path` readable and writable by its owner only
+#[cfg(unix)]
+pub(crate) fn restrict_permissions(path: &Path) -> Result<(), StoreError> {
+    use std::os::unix::fs::PermissionsExt;
+    Ok(fs::set_permissions(
+        path,
+        fs::Permissions::from_mode(0o600),
+    )?)
+}
+
+#[cfg(not(unix))]
+pub(crate) fn restrict_permissions(_path: &Path) -> Result<(), StoreError> {
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::auth::store::tests::test_auth;
+
+    #[test]
+    fn test_plaintext_store() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = PlaintextFileStore::new(dir.path().join("token.json"));
+        assert!(store.load().unwrap().is_none());
+
+        let auth = test_auth();
+        store.save(&auth).unwrap();
+        assert_eq!(store.load().unwrap().unwrap().state, auth.state);
+
+        assert!(store.delete().unwrap());
+        assert!(!store.delete().unwrap());
+        assert!(store.load().unwrap().is_none());
+    }
+}
@@ -0,0 +1,285 @@
+//! How long the shows being watched or planned will take to get through, at the pace
+//! the user has been watching at.
+
+use crate::api::model::{Anime, AnimeStatistics, DateTimeWrapper, UserWatchStatus};
+use crate::schedule::{to_date, to_utc};
+use chrono::{DateTime, Duration, TimeZone, Utc};
+
+/// How far back the pace is worked out from
+pub const PACE_WINDOW_DAYS: i64 = 28;
+
+/// How many minutes a day the user watches
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Pace {
+    pub minutes_per_day: f64,
+    /// Whether it's from the last `PACE_WINDOW_DAYS` rather than the account's
+    /// whole history
+    pub recent: bool,
+}
+
+/// A show in the backlog and what's left of it
+#[derive(Clone, Debug)]
+pub struct Item<'a> {
+    pub anime: &'a Anime,
+    /// `None` if MAL doesn't know how many episodes or how long they are
+    pub episodes_left: Option<u64>,
+    pub time_left: Option<Duration>,
+    /// When it'd be done, watching the queue in order
+    pub done_at: Option<DateTime<Utc>>,
+}
+
+/// The shows being watched then the ones planned, the ones in `order` first and in
+/// that order. Planned shows go by their priority.
+pub fn queue<'a, I>(anime: I, order: &[u64]) -> Vec<&'a Anime>
+where
+    I: IntoIterator<Item = &'a Anime>,
+{
+    let mut queue: Vec<&Anime> = anime
+        .into_iter()
+        .filter(|anime| rank(anime).is_some())
+        .collect();
+    queue.sort_by_key(|anime| {
+        let position = order.iter().position(|&id| id == anime.id);
+        (position.unwrap_or(order.len()), rank(anime))
+    });
+    queue
+}
+
+/// Where `anime` goes in the default order, `None` if it isn't in the backlog
+fn rank(anime: &Anime) -> Option<(u8, std::cmp::Reverse<u8>)> {
+    let status = anime.my_list_status.as_ref()?;
+    let priority = std::cmp::Reverse(status.priority.unwrap_or(0));
+    match status.status {
+        UserWatchStatus::Watching => Some((0, priority)),
+        UserWatchStatus::PlanToWatch => Some((1, priority)),
+        _ => None,
+    }
+}
+
+/// The time left on each show of `queue`, and when it'd be done at `pace`
+pub fn estimate<'a>(queue: &[&'a Anime], pace: Option<Pace>, now: DateTime<Utc>) -> Vec<Item<'a>> {
+    let mut minutes_before = 0;
+    queue
+        .iter()
+        .map(|&anime| {
+            let episodes_left = episodes_left(anime);
+            let minutes_left = episodes_left
+                .zip(episode_minutes(anime))
+                .map(|(episodes, minutes)| episodes * minutes);
+            let done_at = minutes_left.and_then(|minutes_left| {
+                minutes_before += minutes_left;
+                let pace = pace.filter(|pace| pace.minutes_per_day > 0.0)?;
+                let days = minutes_before as f64 / pace.minutes_per_day;
+                Some(now + Duration::minutes((days * 24.0 * 60.0) as i64))
+            });
+            Item {
+                anime,
+                episodes_left,
+                time_left: minutes_left.map(|minutes| Duration::minutes(minutes as i64)),
+                done_at,
+            }
+        })
+        .collect()
+}
+
+fn episodes_left(anime: &Anime) -> Option<u64> {
+    let episodes = anime.num_episodes.filter(|&episodes| episodes > 0)?;
+    let watched = anime
+        .my_list_status
+        .as_ref()
+        .map_or(0, |status| status.num_episodes_watched);
+    Some(episodes.saturating_sub(watched))
+}
+
+fn episode_minutes(anime: &Anime) -> Option<u64> {
+    anime
+        .average_episode_duration
+        .map(|seconds| seconds / 60)
+        .filter(|&minutes| minutes > 0)
+}
+
+/// The pace over the last `PACE_WINDOW_DAYS`, from the shows being watched that changed
+/// in that time and the ones finished in it. Any other change, like a new score, isn't
+/// watching. What was watched of a show is taken as spread evenly from the day the
+/// user started it to its last change. `None` if nothing was.
+pub fn recent_pace<'a, I>(anime: I, now: DateTime<Utc>) -> Option<Pace>
+where
+    I: IntoIterator<Item = &'a Anime>,
+{
+    let window_start = now - Duration::days(PACE_WINDOW_DAYS);
+    let mut minutes = 0.0;
+    for anime in anime {
+        let status = match &anime.my_list_status {
+            Some(status) => status,
+            None => continue,
+        };
+        let updated_at = match to_utc(&status.updated_at) {
+            Some(updated_at) if updated_at > window_start => updated_at,
+            _ => continue,
+        };
+        let watched_lately = match status.status {
+            UserWatchStatus::Watching => true,
+            UserWatchStatus::Completed => status
+                .finish_date
+                .and_then(to_date)
+                .is_some_and(|finished| finished >= window_start.date_naive()),
+            _ => false,
+        };
+        if !watched_lately {
+            continue;
+        }
+        let watched = match episode_minutes(anime) {
+            Some(episode) => (status.num_episodes_watched * episode) as f64,
+            None => continue,
+        };
+        let started_at = status
+            .start_date
+            .and_then(to_date)
+            .and_then(|date| date.and_hms_opt(0, 0, 0))
+            .map(|start| Utc.from_utc_datetime(&start))
+            .filter(|&started_at| started_at < updated_at);
+        minutes += match started_at {
+            Some(started_at) => {
+                let in_window = updated_at - started_at.max(window_start);
+                let watching = updated_at - started_at;
+                watched * in_window.num_seconds() as f64 / watching.num_seconds() as f64
+            }
+            None => watched,
+        };
+    }
+    if minutes > 0.0 {
+        Some(Pace {
+            minutes_per_day: minutes / PACE_WINDOW_DAYS as f64,
+            recent: true,
+        })
+    } else {
+        None
+    }
+}
+
+/// The pace over the whole account, from MAL's statistics
+pub fn lifetime_pace(
+    statistics: &AnimeStatistics,
+    joined_at: &DateTimeWrapper,
+    now: DateTime<Utc>,
+) -> Option<Pace> {
+    let days = (now - to_utc(joined_at)?).num_days();
+    if days <= 0 || statistics.num_days_watched <= 0.0 {
+        return None;
+    }
+    Some(Pace {
+        minutes_per_day: statistics.num_days_watched * 24.0 * 60.0 / days as f64,
+        recent: false,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn finished(mut anime: Anime, on: &str) -> Anime {
+        anime.my_list_status.as_mut().unwrap().finish_date =
+            serde_json::from_value(serde_json::json!(on)).unwrap();
+        anime
+    }
+
+    fn anime(id: u64, status: &str, watched: u64, updated_at: &str) -> Anime {
+        serde_json::from_value(serde_json::json!({
+            "id": id,
+            "title": format!("Anime {}", id),
+            "num_episodes": 12,
+            "average_episode_duration": 1440,
+            "my_list_status": {
+                "status": status,
+                "score": 0,
+                "num_episodes_watched": watched,
+                "is_rewatching": false,
+                "priority": id % 3,
+                "updated_at": updated_at
+            }
+        }))
+        .unwrap()
+    }
+
+    fn now() -> DateTime<Utc> {
+        "2023-10-29T00:00:00Z".parse().unwrap()
+    }
+
+    #[test]
+    fn test_queue() {
+        let list = [
+            anime(1, "plan_to_watch", 0, "2023-10-01T00:00:00+00:00"),
+            anime(2, "completed", 12, "2023-10-01T00:00:00+00:00"),
+            anime(3, "watching", 4, "2023-10-01T00:00:00+00:00"),
+            anime(5, "plan_to_watch", 0, "2023-10-01T00:00:00+00:00"),
+        ];
+        let ids = |order: &[u64]| -> Vec<u64> {
+            queue(&list, order).iter().map(|anime| anime.id).collect()
+        };
+        // watching, then the planned ones with priority 2 before 1
+        assert_eq!(ids(&[]), vec![3, 5, 1]);
+        assert_eq!(ids(&[1]), vec![1, 3, 5]);
+        assert_eq!(ids(&[1, 5, 3]), vec![1, 5, 3]);
+    }
+
+    #[test]
+    fn test_estimate() {
+        let list = [
+            anime(3, "watching", 2, "2023-10-01T00:00:00+00:00"),
+            anime(1, "plan_to_watch", 0, "2023-10-01T00:00:00+00:00"),
+        ];
+        let queue: Vec<&Anime> = list.iter().collect();
+        // an episode a day
+        let pace = Pace {
+            minutes_per_day: 24.0,
+            recent: true,
+        };
+        let items = estimate(&queue, Some(pace), now());
+        assert_eq!(items[0].episodes_left, Some(10));
+        assert_eq!(items[0].time_left, Some(Duration::minutes(240)));
+        assert_eq!(items[0].done_at, Some(now() + Duration::days(10)));
+        assert_eq!(items[1].done_at, Some(now() + Duration::days(22)));
+
+        assert!(estimate(&queue, None, now())[0].done_at.is_none());
+    }
+
+    #[test]
+    fn test_recent_pace() {
+        // 12 episodes this week, and one show untouched for months
+        let list = [
+            finished(
+                anime(1, "completed", 12, "2023-10-25T00:00:00+00:00"),
+                "2023-10-25",
+            ),
+            anime(2, "watching", 6, "2023-06-01T00:00:00+00:00"),
+        ];
+        let pace = recent_pace(&list, now()).unwrap();
+        assert!((pace.minutes_per_day - 12.0 * 24.0 / 28.0).abs() < 1e-9);
+        assert!(pace.recent);
+        assert!(recent_pace(&list[1..], now()).is_none());
+    }
+
+    #[test]
+    fn test_recent_pace_edits() {
+        // rescoring a show finished long ago, or one that's on hold, isn't watching
+        let list = [
+            finished(
+                anime(1, "completed", 500, "2023-10-25T00:00:00+00:00"),
+                "2021-03-01",
+            ),
+            anime(2, "completed", 12, "2023-10-25T00:00:00+00:00"),
+            anime(3, "on_hold", 6, "2023-10-25T00:00:00+00:00"),
+        ];
+        assert!(recent_pace(&list, now()).is_none());
+    }
+
+    #[test]
+    fn test_recent_pace_spread() {
+        // started 56 days before the last change, half of it falls in the window
+        let mut started: Anime = anime(1, "watching", 10, "2023-10-29T00:00:00+00:00");
+        started.my_list_status.as_mut().unwrap().start_date =
+            serde_json::from_value(serde_json::json!("2023-09-03")).unwrap();
+        let pace = recent_pace(&[started], now()).unwrap();
+        assert!((pace.minutes_per_day - 10.0 * 24.0 / 2.0 / 28.0).abs() < 1e-9);
+    }
+}
@@ -0,0 +1,194 @@
+//! Changes made to many list entries at once. Each entry gets its own request, so some
+//! can fail while the others go through.
+
+use crate::api::{
+    self,
+    model::{UserAnimeListStatus, UserMangaListStatus, UserReadStatus, UserWatchStatus},
+};
+
+/// The statuses an anime can be set to, as MAL names them
+pub const ANIME_STATUSES: [&str; 5] = [
+    "watching",
+    "completed",
+    "on_hold",
+    "dropped",
+    "plan_to_watch",
+];
+
+/// The statuses a manga can be set to, as MAL names them
+pub const MANGA_STATUSES: [&str; 5] =
+    ["reading", "completed", "on_hold", "dropped", "plan_to_read"];
+
+/// MAL's priorities, from 0
+pub const PRIORITIES: [&str; 3] = ["Low", "Medium", "High"];
+
+/// What's done to every marked entry
+#[derive(Clone, Debug, PartialEq)]
+pub enum BatchAction {
+    /// One of `ANIME_STATUSES` or `MANGA_STATUSES`
+    Status(&'static str),
+    Score(u8),
+    AddTags(Vec<String>),
+    RemoveTags(Vec<String>),
+    /// An index into `PRIORITIES`
+    Priority(u8),
+    Delete,
+}
+
+impl BatchAction {
+    /// The update to send for an anime with the `current` list status, `None` to
+    /// delete it
+    pub fn anime_update(
+        &self,
+        current: Option<&UserAnimeListStatus>,
+    ) -> Option<api::UpdateUserAnimeListStatusQuery> {
+        let tags = current.and_then(|status| status.tags.as_ref());
+        let mut update = api::UpdateUserAnimeListStatusQuery::default();
+        match self {
+            BatchAction::Status(status) => update.status = status.parse::<UserWatchStatus>().ok(),
+            BatchAction::Score(score) => update.score = Some(*score),
+            BatchAction::AddTags(_) | BatchAction::RemoveTags(_) => {
+                update.tags = Some(self.tags(tags))
+            }
+            BatchAction::Priority(priority) => update.priority = Some(*priority),
+            BatchAction::Delete => return None,
+        }
+        Some(update)
+    }
+
+    /// The update to send for a manga with the `current` list status, `None` to
+    /// delete it
+    pub fn manga_update(
+        &self,
+        current: Option<&UserMangaListStatus>,
+    ) -> Option<api::UpdateUserMangaStatus> {
+        let tags = current.and_then(|status| status.tags.as_ref());
+        let mut update = api::UpdateUserMangaStatus::default();
+        match self {
+            BatchAction::Status(status) => update.status = status.parse::<UserReadStatus>().ok(),
+            BatchAction::Score(score) => update.score = Some(*score),
+            BatchAction::AddTags(_) | BatchAction::RemoveTags(_) => {
+                update.tags = Some(self.tags(tags))
+            }
+            BatchAction::Priority(priority) => update.priority = Some(*priority),
+            BatchAction::Delete => return None,
+        }
+        Some(update)
+    }
+
+    /// MAL replaces all the tags, so they're the entry's `current` ones with the
+    /// action's added or taken out, comma separated
+    fn tags(&self, current: Option<&Vec<String>>) -> String {
+        let mut tags: Vec<String> = current.cloned().unwrap_or_default();
+        match self {
+            BatchAction::AddTags(added) => {
+                for tag in added {
+                    if !tags.contains(tag) {
+                        tags.push(tag.clone());
+                    }
+                }
+            }
+            BatchAction::RemoveTags(removed) => tags.retain(|tag| !removed.contains(tag)),
+            _ => {}
+        }
+        tags.join(",")
+    }
+}
+
+/// Tags typed in as `a, b,c`
+pub fn parse_tags(input: &str) -> Vec<String> {
+    input
+        .split(',')
+        .map(str::trim)
+        .filter(|tag| !tag.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+/// How far a batch got
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct BatchProgress {
+    pub total: usize,
+    pub done: usize,
+    /// The title of each entry that couldn't be changed, and why
+    pub failures: Vec<(String, String)>,
+}
+
+impl BatchProgress {
+    pub fn new(total: usize) -> Self {
+        Self {
+            total,
+            ..Self::default()
+        }
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.done >= self.total
+    }
+
+    pub fn succeeded(&self) -> usize {
+        self.done - self.failures.len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn status(tags: &[&str]) -> UserAnimeListStatus {
+        serde_json::from_value(serde_json::json!({
+            "status": "plan_to_watch",
+            "score": 0,
+            "num_episodes_watched": 0,
+            "is_rewatching": false,
+            "tags": tags,
+            "updated_at": "2023-10-20T12:00:00+00:00"
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_anime_update() {
+        let current = status(&["backlog", "mecha"]);
+        let update = BatchAction::Status("dropped")
+            .anime_update(Some(&current))
+            .unwrap();
+        assert_eq!(update.status, Some(UserWatchStatus::Dropped));
+        assert!(update.tags.is_none());
+        assert!(BatchAction::Delete.anime_update(Some(&current)).is_none());
+
+        let add = BatchAction::AddTags(parse_tags("mecha, 90s,"));
+        let update = add.anime_update(Some(&current)).unwrap();
+        assert_eq!(update.tags.as_deref(), Some("backlog,mecha,90s"));
+        let remove = BatchAction::RemoveTags(parse_tags("backlog"));
+        let update = remove.anime_update(Some(&current)).unwrap();
+        assert_eq!(update.tags.as_deref(), Some("mecha"));
+        assert_eq!(
+            add.anime_update(None).unwrap().tags.as_deref(),
+            Some("mecha,90s")
+        );
+    }
+
+    #[test]
+    fn test_manga_update() {
+        let update = BatchAction::Status("plan_to_read")
+            .manga_update(None)
+            .unwrap();
+        assert_eq!(update.status, Some(UserReadStatus::PlanToRead));
+        let update = BatchAction::Priority(2).manga_update(None).unwrap();
+        assert_eq!(update.priority, Some(2));
+    }
+
+    #[test]
+    fn test_progress() {
+        let mut progress = BatchProgress::new(2);
+        progress.done = 1;
+        assert!(!progress.is_finished());
+        progress.done = 2;
+        progress
+            .failures
+            .push(("Monster".to_string(), "Not found".to_string()));
+        assert!(progress.is_finished());
+        assert_eq!(progress.succeeded(), 1);
+    }
+}
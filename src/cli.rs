@@ -1,4 +1,5 @@
 use crate::BANNER;
+use std::path::PathBuf;
 use structopt::clap::AppSettings;
 pub use structopt::StructOpt;
 
@@ -7,10 +8,54 @@ pub use structopt::StructOpt;
     name = BANNER,
     global_settings(&[AppSettings::ColoredHelp]),
     about = "\nA Terminal User Interface for myanimelist.net",
-    after_help = "Your Config is stored in $HOME/.config/mal-cli/mal.yml\nYour MAL Client ID is stored in $HOME/.config/mal-cli/oauth2.yml"
+    after_help = "Your Config is stored in $HOME/.config/mal-cli/config.yml\nYour MAL Client ID is stored in $HOME/.config/mal-cli/oauth2.yml\nOther profiles are stored in $HOME/.config/mal-cli/profiles/<name>/"
 )]
 pub struct Opt {
     /// Search for anime or manga
     #[structopt(short, long)]
     search: Option<String>,
+
+    /// Account to use, each profile has its own client id, token and config.yml
+    #[structopt(short, long, global = true, default_value = "default")]
+    pub profile: String,
+
+    /// Log in without opening a browser: print the authorization url and
+    /// paste back the url you get redirected to
+    #[structopt(long, global = true)]
+    pub no_browser: bool,
+
+    #[structopt(subcommand)]
+    pub command: Option<Command>,
+}
+
+#[derive(StructOpt)]
+pub enum Command {
+    /// Manage the login of a profile
+    Auth(AuthCommand),
+    /// The airing schedule of your lists
+    Schedule(ScheduleCommand),
+}
+
+#[derive(StructOpt)]
+pub enum AuthCommand {
+    /// Log in again, replacing the stored token
+    Login,
+    /// Remove the token from every store it may be in
+    Logout,
+    /// Show the logged in user, when the token expires and its scopes
+    Status,
+}
+
+#[derive(StructOpt)]
+pub enum ScheduleCommand {
+    /// Write the broadcasts of the shows you're watching or plan to watch as a calendar
+    Export {
+        /// As an iCalendar (.ics) file, which calendar apps can import or subscribe to.
+        /// It's the only format for now.
+        #[structopt(long)]
+        ics: bool,
+        /// File to write to rather than the standard output
+        #[structopt(short, long, parse(from_os_str))]
+        output: Option<PathBuf>,
+    },
 }
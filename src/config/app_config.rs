@@ -1,5 +1,12 @@
 use super::*;
+use crate::api::model::AlternativeTitles;
+use crate::cover::CoverMode;
 use crate::event::key::Key;
+use crate::schedule::TimeZoneSetting;
+use crate::ui::{ColumnId, TableId};
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::fs;
 use tui::style::Color;
 
 #[derive(Clone)]
@@ -8,6 +15,11 @@ pub struct AppConfig {
     pub theme: Theme,
     pub behavior: BehaviorConfig,
     pub nsfw: bool,
+    pub tables: HashMap<TableId, TableConfig>,
+    pub title_language: TitleLanguage,
+    pub covers: CoverMode,
+    /// Time zone of the airing schedule
+    pub time_zone: TimeZoneSetting,
 }
 
 #[derive(Copy, Clone, Debug)]
@@ -44,6 +56,23 @@ pub struct KeyBindings {
     pub help: Key,
     pub back: Key,
     pub search: Key,
+    /// Filters the list in focus, where it takes over from `search`
+    pub filter: Key,
+    pub switch_profile: Key,
+    pub saved_searches: Key,
+    /// Sorts the table in focus by its next column
+    pub sort: Key,
+    pub reverse_sort: Key,
+    /// Moves the show under the cursor in the backlog queue
+    pub move_up: Key,
+    pub move_down: Key,
+    /// Marks the entry under the cursor of a user list, or a range of them
+    pub mark: Key,
+    pub mark_range: Key,
+    /// Opens what can be done to the marked entries
+    pub batch: Key,
+    /// Deletes the saved search under the cursor
+    pub delete: Key,
 }
 
 #[derive(Clone)]
@@ -51,24 +80,317 @@ pub struct BehaviorConfig {
     pub seek_milliseconds: u32,
     pub tick_rate_milliseconds: u64,
     pub show_loading_indicator: bool,
+    /// Weeks without progress before a show the user is behind on looks dropped
+    pub stalled_after_weeks: u32,
+}
+
+/// Which of an entry's titles to show
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TitleLanguage {
+    /// The main MAL title, romanized
+    #[default]
+    Romaji,
+    English,
+    /// The japanese title
+    Native,
+}
+
+impl TitleLanguage {
+    /// The romaji, english and native titles there are, the preferred one first and the
+    /// others in the order they're used when it's missing
+    pub fn main_titles<'a>(
+        self,
+        title: &'a str,
+        alternative_titles: Option<&'a AlternativeTitles>,
+    ) -> Vec<&'a str> {
+        let en = alternative_titles.and_then(|titles| titles.en.as_deref());
+        let jp = alternative_titles.and_then(|titles| titles.jp.as_deref());
+        let order = match self {
+            TitleLanguage::Romaji => [Some(title), en, jp],
+            TitleLanguage::English => [en, Some(title), jp],
+            TitleLanguage::Native => [jp, Some(title), en],
+        };
+        order
+            .iter()
+            .flatten()
+            .copied()
+            .filter(|title| !title.is_empty())
+            .collect()
+    }
+
+    /// The main titles followed by the synonyms
+    pub fn titles<'a>(
+        self,
+        title: &'a str,
+        alternative_titles: Option<&'a AlternativeTitles>,
+    ) -> Vec<&'a str> {
+        let mut titles = self.main_titles(title, alternative_titles);
+        let synonyms = alternative_titles.and_then(|titles| titles.synonyms.as_ref());
+        titles.extend(
+            synonyms
+                .into_iter()
+                .flatten()
+                .map(String::as_str)
+                .filter(|title| !title.is_empty()),
+        );
+        titles
+    }
+
+    /// The title to show
+    pub fn title<'a>(
+        self,
+        title: &'a str,
+        alternative_titles: Option<&'a AlternativeTitles>,
+    ) -> &'a str {
+        self.main_titles(title, alternative_titles)
+            .first()
+            .copied()
+            .unwrap_or(title)
+    }
+}
+
+/// Which columns a table shows, in order
+#[derive(Clone, Debug, PartialEq)]
+pub struct TableConfig {
+    pub columns: Vec<ColumnId>,
+    /// Widths replacing the columns' defaults
+    pub widths: HashMap<ColumnId, u16>,
+}
+
+impl TableConfig {
+    fn new(columns: Vec<ColumnId>) -> Self {
+        Self {
+            columns,
+            widths: HashMap::new(),
+        }
+    }
+}
+
+/// Settings a profile's `config.yml` can change, anything left out keeps its default
+#[derive(Clone, Debug, Default, PartialEq, Deserialize)]
+#[serde(default)]
+pub struct AppConfigOverrides {
+    pub nsfw: Option<bool>,
+    pub title_language: Option<TitleLanguage>,
+    pub covers: Option<CoverMode>,
+    pub time_zone: Option<TimeZoneSetting>,
+    pub behavior: BehaviorOverrides,
+    pub tables: HashMap<TableId, TableOverrides>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Deserialize)]
+#[serde(default)]
+pub struct BehaviorOverrides {
+    pub seek_milliseconds: Option<u32>,
+    pub tick_rate_milliseconds: Option<u64>,
+    pub show_loading_indicator: Option<bool>,
+    pub stalled_after_weeks: Option<u32>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Deserialize)]
+#[serde(default)]
+pub struct TableOverrides {
+    pub columns: Option<Vec<ColumnId>>,
+    /// Added to the widths set so far
+    pub widths: HashMap<ColumnId, u16>,
 }
 
-// TODO: get app config from file
 impl AppConfig {
-    pub fn load() -> Result<Self, ConfigError> {
-        Ok(Self {
+    /// Defaults, then the default profile's `config.yml`, then `profile`'s
+    pub fn load(profile: &Profile) -> Result<Self, ConfigError> {
+        let mut config = Self::default();
+        let mut layers = vec![profile.default_profile()];
+        if !profile.is_default() {
+            layers.push(profile.clone());
+        }
+        for layer in layers {
+            let path = layer.paths().app_config_file_path;
+            if path.exists() {
+                let overrides = fs::read_to_string(&path)?;
+                // an empty file is a valid (empty) config
+                if !overrides.trim().is_empty() {
+                    config.apply(serde_yaml::from_str(&overrides)?);
+                }
+            }
+        }
+        Ok(config)
+    }
+
+    pub fn apply(&mut self, overrides: AppConfigOverrides) {
+        if let Some(nsfw) = overrides.nsfw {
+            self.nsfw = nsfw;
+        }
+        if let Some(title_language) = overrides.title_language {
+            self.title_language = title_language;
+        }
+        if let Some(covers) = overrides.covers {
+            self.covers = covers;
+        }
+        if let Some(time_zone) = overrides.time_zone {
+            self.time_zone = time_zone;
+        }
+        let behavior = overrides.behavior;
+        if let Some(seek_milliseconds) = behavior.seek_milliseconds {
+            self.behavior.seek_milliseconds = seek_milliseconds;
+        }
+        if let Some(tick_rate_milliseconds) = behavior.tick_rate_milliseconds {
+            self.behavior.tick_rate_milliseconds = tick_rate_milliseconds;
+        }
+        if let Some(show_loading_indicator) = behavior.show_loading_indicator {
+            self.behavior.show_loading_indicator = show_loading_indicator;
+        }
+        if let Some(stalled_after_weeks) = behavior.stalled_after_weeks {
+            self.behavior.stalled_after_weeks = stalled_after_weeks;
+        }
+        for (id, table) in overrides.tables {
+            let config = self
+                .tables
+                .entry(id)
+                .or_insert_with(|| TableConfig::new(vec![ColumnId::Title]));
+            if let Some(columns) = table.columns {
+                config.columns = columns;
+            }
+            config.widths.extend(table.widths);
+        }
+    }
+
+    pub fn table(&self, id: TableId) -> &TableConfig {
+        &self.tables[&id]
+    }
+}
+
+impl Default for AppConfig {
+    fn default() -> Self {
+        Self {
             theme: Theme::default(),
             keys: KeyBindings {
                 help: Key::Char('?'),
                 back: Key::Char('q'),
                 search: Key::Char('/'),
+                filter: Key::Char('/'),
+                switch_profile: Key::Char('P'),
+                saved_searches: Key::Char('S'),
+                sort: Key::Char('s'),
+                reverse_sort: Key::Char('r'),
+                move_up: Key::Char('K'),
+                move_down: Key::Char('J'),
+                mark: Key::Char(' '),
+                mark_range: Key::Char('V'),
+                batch: Key::Char('a'),
+                delete: Key::Char('d'),
             },
             behavior: BehaviorConfig {
                 seek_milliseconds: 1000,
                 tick_rate_milliseconds: 250,
                 show_loading_indicator: true,
+                stalled_after_weeks: 3,
             },
             nsfw: true,
-        })
+            tables: default_tables(),
+            title_language: TitleLanguage::default(),
+            covers: CoverMode::default(),
+            time_zone: TimeZoneSetting::default(),
+        }
+    }
+}
+
+fn default_tables() -> HashMap<TableId, TableConfig> {
+    use ColumnId::*;
+    let mut tables = HashMap::new();
+    tables.insert(
+        TableId::Anime,
+        TableConfig::new(vec![Title, Type, Episodes, Year, Score, Rank]),
+    );
+    tables.insert(
+        TableId::Manga,
+        TableConfig::new(vec![Title, Type, Volumes, Chapters, Score, Rank]),
+    );
+    tables.insert(
+        TableId::User,
+        TableConfig::new(vec![Title, MyStatus, MyScore, Progress]),
+    );
+    tables.insert(
+        TableId::AnimeList,
+        TableConfig::new(vec![Title, Type, MyStatus, MyScore, Progress, Score]),
+    );
+    tables.insert(
+        TableId::MangaList,
+        TableConfig::new(vec![Title, Type, MyStatus, MyScore, Progress, Score]),
+    );
+    tables
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_profile_overrides() {
+        let root = tempfile::tempdir().unwrap();
+        let work = Profile::in_dir(root.path(), "work").unwrap();
+        work.ensure_dir().unwrap();
+        let default_paths = work.default_profile().paths();
+        fs::write(
+            default_paths.app_config_file_path,
+            "nsfw: false\nbehavior:\n  tick_rate_milliseconds: 100\n",
+        )
+        .unwrap();
+        fs::write(
+            work.paths().app_config_file_path,
+            "behavior:\n  show_loading_indicator: false\n",
+        )
+        .unwrap();
+
+        let config = AppConfig::load(&work).unwrap();
+        assert!(!config.nsfw);
+        assert_eq!(config.behavior.tick_rate_milliseconds, 100);
+        assert!(!config.behavior.show_loading_indicator);
+        assert_eq!(config.behavior.seek_milliseconds, 1000);
+
+        let config = AppConfig::load(&work.default_profile()).unwrap();
+        assert!(config.behavior.show_loading_indicator);
+    }
+
+    #[test]
+    fn test_title_language() {
+        let titles: AlternativeTitles = serde_json::from_value(serde_json::json!({
+            "en": "Attack on Titan",
+            "ja": "進撃の巨人",
+            "synonyms": ["AoT"]
+        }))
+        .unwrap();
+        let title = "Shingeki no Kyojin";
+
+        assert_eq!(TitleLanguage::Romaji.title(title, Some(&titles)), title);
+        assert_eq!(
+            TitleLanguage::English.titles(title, Some(&titles)),
+            vec!["Attack on Titan", title, "進撃の巨人", "AoT"]
+        );
+        assert_eq!(
+            TitleLanguage::Native.title(title, Some(&titles)),
+            "進撃の巨人"
+        );
+
+        // falls back to the main title
+        assert_eq!(TitleLanguage::English.title(title, None), title);
+        let config: AppConfigOverrides = serde_yaml::from_str("title_language: native").unwrap();
+        assert_eq!(config.title_language, Some(TitleLanguage::Native));
+    }
+
+    #[test]
+    fn test_table_overrides() {
+        let mut config = AppConfig::default();
+        let overrides = "tables:\n  anime_list:\n    columns: [title, my_score]\n  manga:\n    widths:\n      score: 7\n";
+        config.apply(serde_yaml::from_str(overrides).unwrap());
+
+        let anime_list = config.table(TableId::AnimeList);
+        assert_eq!(anime_list.columns, vec![ColumnId::Title, ColumnId::MyScore]);
+        let manga = config.table(TableId::Manga);
+        assert_eq!(
+            manga.columns,
+            AppConfig::default().table(TableId::Manga).columns
+        );
+        assert_eq!(manga.widths[&ColumnId::Score], 7);
     }
 }
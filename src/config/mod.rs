@@ -4,8 +4,12 @@ pub mod oauth_config;
 // app config
 pub mod app_config;
 
-pub use app_config::AppConfig;
+// profiles
+pub mod profile;
+
+pub use app_config::{AppConfig, TitleLanguage};
 pub use oauth_config::AuthConfig;
+pub use profile::Profile;
 
 use std::path::PathBuf;
 
@@ -14,10 +18,13 @@ const APP_CONFIG_DIR: &str = "mal-cli";
 
 const DEFAULT_PORT: u16 = 7878;
 const DEFAULT_USER_AGENT: &str = "mal-cli";
+const DEFAULT_REDIRECT_TIMEOUT_SECS: u64 = 300;
 const OAUTH_FILE: &str = "oauth2.yml";
 const TOKEN_CACHE_FILE: &str = ".mal_token_cache.json";
+const ENCRYPTED_TOKEN_CACHE_FILE: &str = ".mal_token_cache.enc";
 
-const _CONFIG_FILE: &str = "config.yml";
+const CONFIG_FILE: &str = "config.yml";
+const SEARCHES_FILE: &str = "searches.yml";
 
 #[derive(Debug)]
 pub enum ConfigError {
@@ -27,6 +34,8 @@ pub enum ConfigError {
     ReadError,
     /// Represents a nonexistent path error
     PathError,
+    /// Represents a profile name that can't be used as a directory name
+    InvalidProfile(String),
     /// Represents a serde_yaml parse error
     ParseError(serde_yaml::Error),
     /// Represents all other failures
@@ -39,6 +48,7 @@ impl std::error::Error for ConfigError {
             ConfigError::EmptyConfig => None,
             ConfigError::ReadError => None,
             ConfigError::PathError => None,
+            ConfigError::InvalidProfile(_) => None,
             ConfigError::ParseError(_) => None,
             ConfigError::IOError(_) => None,
         }
@@ -51,6 +61,11 @@ impl std::fmt::Display for ConfigError {
             ConfigError::EmptyConfig => write!(f, "Source contains no data"),
             ConfigError::ReadError => write!(f, "Could not read file"),
             ConfigError::PathError => write!(f, "Path not found"),
+            ConfigError::InvalidProfile(ref name) => write!(
+                f,
+                "Invalid profile name {:?} (use letters, digits, - and _)",
+                name
+            ),
             ConfigError::ParseError(ref err) => err.fmt(f),
             ConfigError::IOError(ref err) => err.fmt(f),
         }
@@ -71,5 +86,8 @@ impl From<serde_yaml::Error> for ConfigError {
 
 pub struct ConfigPaths {
     pub config_file_path: PathBuf,
+    pub app_config_file_path: PathBuf,
     pub auth_cache_path: PathBuf,
+    pub encrypted_auth_cache_path: PathBuf,
+    pub searches_file_path: PathBuf,
 }
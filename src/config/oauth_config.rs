@@ -2,10 +2,11 @@ use serde::{Deserialize, Serialize};
 use std::{
     fs,
     io::{stdin, Write},
-    path::Path,
+    time::Duration,
 };
 
 use super::*;
+use crate::auth::store::StoreKind;
 use crate::BANNER;
 
 #[derive(Default, Clone, Debug, PartialEq, Serialize, Deserialize)]
@@ -13,19 +14,35 @@ pub struct AuthConfig {
     pub client_id: String,
     pub user_agent: Option<String>,
     pub port: Option<u16>,
+    /// Seconds to wait for the browser redirect before giving up
+    pub redirect_timeout: Option<u64>,
+    /// Where to keep the token: `auto`, `keyring`, `encrypted` or `plaintext`
+    pub token_store: Option<StoreKind>,
 }
 
 impl AuthConfig {
-    // TODO: Strip whitespace from user_agent as it can cause code to panic
-    pub fn load() -> Result<Self, ConfigError> {
-        let paths = Self::get_paths()?;
+    /// Reads the profile's config, returns `None` if it hasn't been set up
+    pub fn read(profile: &Profile) -> Result<Option<Self>, ConfigError> {
+        let paths = profile.paths();
         if paths.config_file_path.exists() {
             let config_string = fs::read_to_string(&paths.config_file_path)?;
-            let config_yml: AuthConfig = serde_yaml::from_str(&config_string)?;
+            Ok(Some(serde_yaml::from_str(&config_string)?))
+        } else {
+            Ok(None)
+        }
+    }
 
+    /// Reads the profile's config, asking for it on the terminal if it hasn't been set up
+    // TODO: Strip whitespace from user_agent as it can cause code to panic
+    pub fn load(profile: &Profile) -> Result<Self, ConfigError> {
+        let paths = profile.paths();
+        if let Some(config_yml) = Self::read(profile)? {
             Ok(config_yml)
         } else {
             println!("{}", BANNER);
+            if !profile.is_default() {
+                println!("Setting up profile `{}`", profile.name);
+            }
             println!(
                 "Config will be saved to {}",
                 paths.config_file_path.display()
@@ -43,10 +60,8 @@ impl AuthConfig {
                 "You are now ready to authenticate with myanimelist!",
             ];
 
-            let mut number = 1;
-            for item in instructions.iter() {
-                println!("   {}. {}", number, item);
-                number += 1;
+            for (number, item) in instructions.iter().enumerate() {
+                println!("   {}. {}", number + 1, item);
             }
 
             let mut client_id = String::new();
@@ -71,10 +86,13 @@ impl AuthConfig {
                 client_id,
                 user_agent: Some(user_agent),
                 port: Some(port),
+                redirect_timeout: None,
+                token_store: None,
             };
 
             let content_yml = serde_yaml::to_string(&config_yml)?;
 
+            profile.ensure_dir()?;
             let mut new_config = fs::File::create(&paths.config_file_path)?;
             write!(new_config, "{}", content_yml)?;
 
@@ -90,39 +108,21 @@ impl AuthConfig {
         self.port.unwrap_or(DEFAULT_PORT)
     }
 
+    pub fn get_redirect_timeout(&self) -> Duration {
+        Duration::from_secs(
+            self.redirect_timeout
+                .unwrap_or(DEFAULT_REDIRECT_TIMEOUT_SECS),
+        )
+    }
+
+    pub fn get_token_store(&self) -> StoreKind {
+        self.token_store.unwrap_or_default()
+    }
+
     pub fn get_user_agent(&self) -> String {
         match &self.user_agent {
             Some(s) => s.clone(),
             None => DEFAULT_USER_AGENT.to_string(),
         }
     }
-
-    pub fn get_paths() -> Result<ConfigPaths, ConfigError> {
-        match dirs::home_dir() {
-            Some(home) => {
-                let path = Path::new(&home);
-                let home_config_dir = path.join(CONFIG_DIR);
-                let app_config_dir = home_config_dir.join(APP_CONFIG_DIR);
-
-                if !home_config_dir.exists() {
-                    fs::create_dir(&home_config_dir)?;
-                }
-
-                if !app_config_dir.exists() {
-                    fs::create_dir(&app_config_dir)?;
-                }
-
-                let config_file_path = &app_config_dir.join(OAUTH_FILE);
-                let token_cache_path = &app_config_dir.join(TOKEN_CACHE_FILE);
-
-                let paths = ConfigPaths {
-                    config_file_path: config_file_path.to_path_buf(),
-                    auth_cache_path: token_cache_path.to_path_buf(),
-                };
-
-                Ok(paths)
-            }
-            None => Err(ConfigError::PathError),
-        }
-    }
 }
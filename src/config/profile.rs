@@ -0,0 +1,169 @@
+use super::*;
+use std::{fs, path::Path};
+
+pub const DEFAULT_PROFILE: &str = "default";
+const PROFILES_DIR: &str = "profiles";
+
+/// A named account with its own client id, token and settings
+///
+/// The default profile lives in `~/.config/mal-cli`, the others in
+/// `~/.config/mal-cli/profiles/<name>`. A profile's `config.yml` is applied on
+/// top of the default profile's.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Profile {
+    pub name: String,
+    root: PathBuf,
+}
+
+impl Profile {
+    /// Profile `name` in the mal-cli config directory
+    pub fn new(name: &str) -> Result<Self, ConfigError> {
+        Self::in_dir(app_config_dir()?, name)
+    }
+
+    /// Profile `name` with `root` as the config directory
+    pub fn in_dir<P: AsRef<Path>>(root: P, name: &str) -> Result<Self, ConfigError> {
+        let valid = !name.is_empty()
+            && name
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+        if !valid {
+            return Err(ConfigError::InvalidProfile(name.to_string()));
+        }
+        Ok(Self {
+            name: name.to_string(),
+            root: root.as_ref().to_path_buf(),
+        })
+    }
+
+    pub fn is_default(&self) -> bool {
+        self.name == DEFAULT_PROFILE
+    }
+
+    /// Directory holding the profile's files
+    pub fn dir(&self) -> PathBuf {
+        if self.is_default() {
+            self.root.clone()
+        } else {
+            self.root.join(PROFILES_DIR).join(&self.name)
+        }
+    }
+
+    /// The default profile, whose settings every other profile inherits
+    pub fn default_profile(&self) -> Self {
+        Self {
+            name: DEFAULT_PROFILE.to_string(),
+            root: self.root.clone(),
+        }
+    }
+
+    /// Whether the profile was set up, the default one always is
+    pub fn exists(&self) -> bool {
+        self.is_default() || self.dir().is_dir()
+    }
+
+    /// Creates the profile's directory, before writing the first of its files
+    pub fn ensure_dir(&self) -> Result<(), ConfigError> {
+        Ok(fs::create_dir_all(self.dir())?)
+    }
+
+    /// Paths of the profile's files, which don't have to exist
+    pub fn paths(&self) -> ConfigPaths {
+        let dir = self.dir();
+        ConfigPaths {
+            config_file_path: dir.join(OAUTH_FILE),
+            app_config_file_path: dir.join(CONFIG_FILE),
+            auth_cache_path: dir.join(TOKEN_CACHE_FILE),
+            encrypted_auth_cache_path: dir.join(ENCRYPTED_TOKEN_CACHE_FILE),
+            searches_file_path: dir.join(SEARCHES_FILE),
+        }
+    }
+
+    /// Names of all profiles in the mal-cli config directory, default first
+    pub fn list() -> Result<Vec<String>, ConfigError> {
+        Self::list_in(app_config_dir()?)
+    }
+
+    pub fn list_in<P: AsRef<Path>>(root: P) -> Result<Vec<String>, ConfigError> {
+        let mut names = Vec::new();
+        if let Ok(entries) = fs::read_dir(root.as_ref().join(PROFILES_DIR)) {
+            for entry in entries {
+                let entry = entry?;
+                if entry.file_type()?.is_dir() {
+                    names.push(entry.file_name().to_string_lossy().into_owned());
+                }
+            }
+        }
+        names.retain(|name| name != DEFAULT_PROFILE && Self::in_dir(&root, name).is_ok());
+        names.sort();
+        names.insert(0, DEFAULT_PROFILE.to_string());
+        Ok(names)
+    }
+}
+
+/// `~/.config/mal-cli`, created if it doesn't exist
+fn app_config_dir() -> Result<PathBuf, ConfigError> {
+    match dirs::home_dir() {
+        Some(home) => {
+            let dir = home.join(CONFIG_DIR).join(APP_CONFIG_DIR);
+            fs::create_dir_all(&dir)?;
+            Ok(dir)
+        }
+        None => Err(ConfigError::PathError),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_profile_paths() {
+        let root = tempfile::tempdir().unwrap();
+
+        let default = Profile::in_dir(root.path(), DEFAULT_PROFILE).unwrap();
+        let paths = default.paths();
+        assert_eq!(paths.config_file_path, root.path().join(OAUTH_FILE));
+        assert_eq!(paths.auth_cache_path, root.path().join(TOKEN_CACHE_FILE));
+
+        let work = Profile::in_dir(root.path(), "work").unwrap();
+        let paths = work.paths();
+        let dir = root.path().join("profiles").join("work");
+        // only writing a file creates the directory
+        assert!(!dir.exists() && !work.exists());
+        work.ensure_dir().unwrap();
+        assert!(dir.is_dir() && work.exists());
+        assert_eq!(paths.config_file_path, dir.join(OAUTH_FILE));
+        assert_eq!(paths.app_config_file_path, dir.join(CONFIG_FILE));
+        assert_eq!(work.default_profile(), default);
+    }
+
+    #[test]
+    fn test_invalid_profile_names() {
+        for name in &["", "..", "a/b", "white space", "~"] {
+            assert!(matches!(
+                Profile::in_dir("/tmp", name),
+                Err(ConfigError::InvalidProfile(_))
+            ));
+        }
+    }
+
+    #[test]
+    fn test_list_profiles() {
+        let root = tempfile::tempdir().unwrap();
+        assert_eq!(Profile::list_in(root.path()).unwrap(), vec!["default"]);
+
+        for name in &["work", "test-account"] {
+            Profile::in_dir(root.path(), name)
+                .unwrap()
+                .ensure_dir()
+                .unwrap();
+        }
+        fs::write(root.path().join("profiles").join("stray-file"), "").unwrap();
+
+        assert_eq!(
+            Profile::list_in(root.path()).unwrap(),
+            vec!["default", "test-account", "work"]
+        );
+    }
+}
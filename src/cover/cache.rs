@@ -0,0 +1,62 @@
+use sha2::{Digest, Sha256};
+use std::{
+    env, fs, io,
+    path::{Path, PathBuf},
+};
+
+/// Covers downloaded before, as they came from MAL. Every profile shares them.
+#[derive(Clone, Debug)]
+pub struct CoverCache {
+    dir: PathBuf,
+}
+
+impl CoverCache {
+    pub fn new<P: AsRef<Path>>(dir: P) -> Self {
+        Self {
+            dir: dir.as_ref().to_path_buf(),
+        }
+    }
+
+    /// `mal-cli/covers` in the user's cache directory, or the temp directory if there
+    /// isn't one
+    pub fn in_cache_dir() -> Self {
+        let root = dirs::cache_dir().unwrap_or_else(env::temp_dir);
+        Self::new(root.join("mal-cli").join("covers"))
+    }
+
+    fn path(&self, url: &str) -> PathBuf {
+        let hash = Sha256::digest(url.as_bytes());
+        let name: String = hash.iter().map(|byte| format!("{:02x}", byte)).collect();
+        self.dir.join(name)
+    }
+
+    pub fn get(&self, url: &str) -> Option<Vec<u8>> {
+        fs::read(self.path(url)).ok()
+    }
+
+    pub fn put(&self, url: &str, bytes: &[u8]) -> io::Result<()> {
+        fs::create_dir_all(&self.dir)?;
+        // written aside first, so a cover is never read half written
+        let path = self.path(url);
+        let partial = path.with_extension("part");
+        fs::write(&partial, bytes)?;
+        fs::rename(partial, path)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cover_cache() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = CoverCache::new(dir.path().join("covers"));
+        let url = "https://cdn.myanimelist.net/images/anime/4/19644.jpg";
+
+        assert_eq!(cache.get(url), None);
+        cache.put(url, b"cover").unwrap();
+        assert_eq!(cache.get(url).unwrap(), b"cover");
+        assert_eq!(cache.get("https://cdn.myanimelist.net/other.jpg"), None);
+    }
+}
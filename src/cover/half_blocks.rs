@@ -0,0 +1,84 @@
+use image::{imageops::FilterType, RgbaImage};
+use tui::{buffer::Buffer, layout::Rect, style::Color, widgets::Widget};
+
+/// Upper half block, its foreground is the top pixel and its background the bottom one
+const UPPER_HALF: &str = "▀";
+
+/// A picture drawn with two pixels in every cell, for terminals without a graphics
+/// protocol
+pub struct HalfBlocks<'a> {
+    image: &'a RgbaImage,
+}
+
+impl<'a> HalfBlocks<'a> {
+    pub fn new(image: &'a RgbaImage) -> Self {
+        Self { image }
+    }
+}
+
+impl Widget for HalfBlocks<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        if area.width == 0 || area.height == 0 {
+            return;
+        }
+        let pixels = image::imageops::resize(
+            self.image,
+            u32::from(area.width),
+            u32::from(area.height) * 2,
+            FilterType::Triangle,
+        );
+        for y in 0..area.height {
+            for x in 0..area.width {
+                let top = pixels.get_pixel(u32::from(x), u32::from(y) * 2);
+                let bottom = pixels.get_pixel(u32::from(x), u32::from(y) * 2 + 1);
+                buf.get_mut(area.x + x, area.y + y)
+                    .set_symbol(UPPER_HALF)
+                    .set_fg(color(top))
+                    .set_bg(color(bottom));
+            }
+        }
+    }
+}
+
+/// Transparent pixels show the terminal's background
+fn color(pixel: &image::Rgba<u8>) -> Color {
+    match pixel.0 {
+        [_, _, _, 0] => Color::Reset,
+        [r, g, b, _] => Color::Rgb(r, g, b),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::Rgba;
+    use tui::{backend::TestBackend, Terminal};
+
+    #[test]
+    fn test_half_blocks() {
+        // red over blue on the left, a transparent pixel over green on the right
+        let mut image = RgbaImage::new(2, 2);
+        image.put_pixel(0, 0, Rgba([255, 0, 0, 255]));
+        image.put_pixel(0, 1, Rgba([0, 0, 255, 255]));
+        image.put_pixel(1, 0, Rgba([0, 0, 0, 0]));
+        image.put_pixel(1, 1, Rgba([0, 255, 0, 255]));
+
+        let mut terminal = Terminal::new(TestBackend::new(4, 2)).unwrap();
+        terminal
+            .draw(|f| f.render_widget(HalfBlocks::new(&image), Rect::new(1, 0, 2, 1)))
+            .unwrap();
+
+        let buffer = terminal.backend().buffer();
+        let left = buffer.get(1, 0);
+        assert_eq!(left.symbol, UPPER_HALF);
+        assert_eq!(left.fg, Color::Rgb(255, 0, 0));
+        assert_eq!(left.bg, Color::Rgb(0, 0, 255));
+        let right = buffer.get(2, 0);
+        assert_eq!(right.fg, Color::Reset);
+        assert_eq!(right.bg, Color::Rgb(0, 255, 0));
+
+        // nothing outside the area
+        assert_eq!(buffer.get(0, 0).symbol, " ");
+        assert_eq!(buffer.get(1, 1).symbol, " ");
+    }
+}
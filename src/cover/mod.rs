@@ -0,0 +1,120 @@
+//! Cover art in the terminal. Terminals with a graphics protocol get the picture
+//! itself, the others a picture made of half block characters.
+
+pub mod cache;
+pub mod half_blocks;
+pub mod protocol;
+
+pub use cache::CoverCache;
+pub use half_blocks::HalfBlocks;
+pub use protocol::Protocol;
+
+use image::RgbaImage;
+use serde::Deserialize;
+
+/// How covers are drawn, set with `covers` in `config.yml`
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CoverMode {
+    /// The best protocol the terminal looks like it supports
+    #[default]
+    Auto,
+    Kitty,
+    Iterm2,
+    Sixel,
+    HalfBlocks,
+    Off,
+}
+
+impl CoverMode {
+    /// The protocol to draw with, `None` if covers are off
+    pub fn protocol(self) -> Option<Protocol> {
+        match self {
+            CoverMode::Auto => Some(Protocol::detect()),
+            CoverMode::Kitty => Some(Protocol::Kitty),
+            CoverMode::Iterm2 => Some(Protocol::Iterm2),
+            CoverMode::Sixel => Some(Protocol::Sixel),
+            CoverMode::HalfBlocks => Some(Protocol::HalfBlocks),
+            CoverMode::Off => None,
+        }
+    }
+}
+
+/// A downloaded cover and where it came from
+#[derive(Clone, Debug)]
+pub struct Cover {
+    pub url: String,
+    pub image: RgbaImage,
+}
+
+impl Cover {
+    /// Columns the cover takes when it's `height` rows tall, keeping its proportions.
+    /// Cells are about twice as tall as they're wide.
+    pub fn width(&self, height: u16) -> u16 {
+        let (width, image_height) = self.image.dimensions();
+        if image_height == 0 {
+            return 0;
+        }
+        (u64::from(width) * u64::from(height) * 2 / u64::from(image_height)) as u16
+    }
+}
+
+#[derive(Debug)]
+pub enum CoverError {
+    /// Represents a failed download
+    HttpError(reqwest::Error),
+    /// Represents a picture that couldn't be decoded
+    ImageError(image::ImageError),
+}
+
+impl std::error::Error for CoverError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match *self {
+            CoverError::HttpError(ref err) => Some(err),
+            CoverError::ImageError(ref err) => Some(err),
+        }
+    }
+}
+
+impl std::fmt::Display for CoverError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match *self {
+            CoverError::HttpError(ref err) => write!(f, "Could not download the cover: {}", err),
+            CoverError::ImageError(ref err) => write!(f, "Could not read the cover: {}", err),
+        }
+    }
+}
+
+impl From<reqwest::Error> for CoverError {
+    fn from(err: reqwest::Error) -> Self {
+        CoverError::HttpError(err)
+    }
+}
+
+impl From<image::ImageError> for CoverError {
+    fn from(err: image::ImageError) -> Self {
+        CoverError::ImageError(err)
+    }
+}
+
+/// The cover at `url`, from the disk cache if it was downloaded before
+pub async fn load(cache: &CoverCache, url: &str) -> Result<Cover, CoverError> {
+    let bytes = match cache.get(url) {
+        Some(bytes) => bytes,
+        None => {
+            let bytes = reqwest::get(url)
+                .await?
+                .error_for_status()?
+                .bytes()
+                .await?
+                .to_vec();
+            // not being able to cache it only means downloading it again
+            let _ = cache.put(url, &bytes);
+            bytes
+        }
+    };
+    Ok(Cover {
+        url: url.to_string(),
+        image: image::load_from_memory(&bytes)?.to_rgba8(),
+    })
+}
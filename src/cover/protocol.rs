@@ -0,0 +1,213 @@
+use image::{imageops::FilterType, DynamicImage, ImageOutputFormat, RgbaImage};
+use std::{collections::HashMap, env, fmt::Write, io::Cursor};
+
+/// Bytes of base64 sent in each kitty escape sequence, the most it allows
+const KITTY_CHUNK: usize = 4096;
+/// Assumed size of a cell in pixels, sixel pictures are sized in pixels rather than cells
+const SIXEL_CELL: (u32, u32) = (10, 20);
+
+/// How a picture gets to the terminal
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Protocol {
+    /// The kitty graphics protocol, also spoken by WezTerm and Konsole
+    Kitty,
+    /// iTerm2's inline images
+    Iterm2,
+    Sixel,
+    /// Colored half blocks, which any terminal with true color can show
+    HalfBlocks,
+}
+
+impl Protocol {
+    /// Guesses what the terminal supports from the environment
+    pub fn detect() -> Self {
+        Self::detect_from(|name| env::var(name).ok())
+    }
+
+    fn detect_from<F: Fn(&str) -> Option<String>>(var: F) -> Self {
+        let term = var("TERM").unwrap_or_default();
+        let program = var("TERM_PROGRAM").unwrap_or_default();
+        if var("KITTY_WINDOW_ID").is_some() || term.contains("kitty") {
+            Protocol::Kitty
+        } else if program == "iTerm.app"
+            || program == "WezTerm"
+            || var("LC_TERMINAL").is_some_and(|terminal| terminal == "iTerm2")
+        {
+            Protocol::Iterm2
+        } else if ["foot", "mlterm", "sixel"]
+            .iter()
+            .any(|name| term.contains(name))
+        {
+            Protocol::Sixel
+        } else {
+            Protocol::HalfBlocks
+        }
+    }
+
+    /// The escape sequence drawing `image` in the `columns` by `rows` cells from the
+    /// cursor on, `None` for half blocks which are drawn in the buffer
+    pub fn encode(self, image: &RgbaImage, columns: u16, rows: u16) -> Option<String> {
+        match self {
+            Protocol::Kitty => Some(kitty(image, columns, rows)),
+            Protocol::Iterm2 => Some(iterm2(image, columns, rows)),
+            Protocol::Sixel => Some(sixel(image, columns, rows)),
+            Protocol::HalfBlocks => None,
+        }
+    }
+
+    /// The escape sequence removing what `encode` drew, for pictures drawing over the
+    /// cells rather than in them
+    pub fn clear(self) -> Option<&'static str> {
+        match self {
+            Protocol::Kitty => Some("\x1b_Ga=d,q=2\x1b\\"),
+            _ => None,
+        }
+    }
+}
+
+/// Sends the raw pixels and lets kitty scale them to the cells
+fn kitty(image: &RgbaImage, columns: u16, rows: u16) -> String {
+    let data = base64::encode(image.as_raw());
+    let chunks: Vec<&[u8]> = data.as_bytes().chunks(KITTY_CHUNK).collect();
+    let mut escape = String::new();
+    for (index, chunk) in chunks.iter().enumerate() {
+        let more = (index + 1 < chunks.len()) as u8;
+        // q=2 keeps kitty from answering on stdin, where it would look like key presses
+        let control = if index == 0 {
+            format!(
+                "a=T,f=32,s={},v={},c={},r={},C=1,q=2,m={}",
+                image.width(),
+                image.height(),
+                columns,
+                rows,
+                more
+            )
+        } else {
+            format!("m={}", more)
+        };
+        let chunk = std::str::from_utf8(chunk).unwrap_or_default();
+        let _ = write!(escape, "\x1b_G{};{}\x1b\\", control, chunk);
+    }
+    escape
+}
+
+fn iterm2(image: &RgbaImage, columns: u16, rows: u16) -> String {
+    let mut png = Cursor::new(vec![]);
+    // encoding pixels we decoded can't fail
+    let _ = DynamicImage::ImageRgba8(image.clone()).write_to(&mut png, ImageOutputFormat::Png);
+    let png = png.into_inner();
+    format!(
+        "\x1b]1337;File=inline=1;size={};width={};height={};preserveAspectRatio=1:{}\x07",
+        png.len(),
+        columns,
+        rows,
+        base64::encode(&png)
+    )
+}
+
+/// The picture scaled to the cells and reduced to a 6x6x6 color cube
+fn sixel(image: &RgbaImage, columns: u16, rows: u16) -> String {
+    let width = u32::from(columns) * SIXEL_CELL.0;
+    let height = u32::from(rows) * SIXEL_CELL.1;
+    let pixels = image::imageops::resize(image, width, height, FilterType::Triangle);
+    let level = |value: u8| (u16::from(value) * 5 + 127) / 255;
+    let palette_index =
+        |pixel: &image::Rgba<u8>| level(pixel[0]) * 36 + level(pixel[1]) * 6 + level(pixel[2]);
+
+    let mut escape = format!("\x1bPq\"1;1;{};{}", width, height);
+    for index in 0..216u16 {
+        let percent = |level: u16| level * 100 / 5;
+        let _ = write!(
+            escape,
+            "#{};2;{};{};{}",
+            index,
+            percent(index / 36),
+            percent(index / 6 % 6),
+            percent(index % 6)
+        );
+    }
+
+    // each band is 6 pixels tall, drawn once for every color in it
+    for band in (0..height).step_by(6) {
+        let mut bits: HashMap<u16, Vec<u8>> = HashMap::new();
+        for x in 0..width {
+            for row in 0..6.min(height - band) {
+                let pixel = pixels.get_pixel(x, band + row);
+                if pixel[3] == 0 {
+                    continue;
+                }
+                bits.entry(palette_index(pixel))
+                    .or_insert_with(|| vec![0; width as usize])[x as usize] |= 1 << row;
+            }
+        }
+        let mut colors: Vec<_> = bits.into_iter().collect();
+        colors.sort_by_key(|&(color, _)| color);
+        for (color, bits) in colors {
+            let _ = write!(escape, "#{}", color);
+            run_lengths(&mut escape, &bits);
+            // back to the start of the band for the next color
+            escape.push('$');
+        }
+        escape.push('-');
+    }
+    escape.push_str("\x1b\\");
+    escape
+}
+
+/// Sixel characters for a color's pixels in a band, repeats as `!<count><char>`
+fn run_lengths(escape: &mut String, bits: &[u8]) {
+    let mut index = 0;
+    while index < bits.len() {
+        let count = bits[index..]
+            .iter()
+            .take_while(|&&b| b == bits[index])
+            .count();
+        let c = (63 + bits[index]) as char;
+        if count > 3 {
+            let _ = write!(escape, "!{}{}", count, c);
+        } else {
+            (0..count).for_each(|_| escape.push(c));
+        }
+        index += count;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::Rgba;
+
+    fn detect(vars: &[(&str, &str)]) -> Protocol {
+        let vars: HashMap<&str, &str> = vars.iter().copied().collect();
+        Protocol::detect_from(|name| vars.get(name).map(|value| value.to_string()))
+    }
+
+    #[test]
+    fn test_detect() {
+        assert_eq!(detect(&[("TERM", "xterm-kitty")]), Protocol::Kitty);
+        assert_eq!(
+            detect(&[("TERM", "xterm-256color"), ("TERM_PROGRAM", "iTerm.app")]),
+            Protocol::Iterm2
+        );
+        assert_eq!(detect(&[("TERM", "foot")]), Protocol::Sixel);
+        assert_eq!(detect(&[("TERM", "xterm-256color")]), Protocol::HalfBlocks);
+        assert_eq!(detect(&[]), Protocol::HalfBlocks);
+    }
+
+    #[test]
+    fn test_encode() {
+        let image = RgbaImage::from_pixel(2, 2, Rgba([255, 0, 0, 255]));
+
+        let kitty = Protocol::Kitty.encode(&image, 3, 2).unwrap();
+        assert!(kitty.starts_with("\x1b_Ga=T,f=32,s=2,v=2,c=3,r=2,C=1,q=2,m=0;"));
+        assert!(kitty.ends_with("\x1b\\"));
+
+        let sixel = Protocol::Sixel.encode(&image, 1, 1).unwrap();
+        assert!(sixel.starts_with("\x1bPq\"1;1;10;20"));
+        // all red, in full bands of 6 pixels but the last one which is 2
+        assert!(sixel.contains("#180!10~$-"));
+        assert!(sixel.ends_with("#180!10B$-\x1b\\"));
+
+        assert_eq!(Protocol::HalfBlocks.encode(&image, 1, 1), None);
+    }
+}
@@ -1,6 +1,7 @@
 use crate::event::Key;
 use crossterm::event;
-use std::{sync::mpsc, thread, time::Duration};
+use std::{thread, time::Duration};
+use tokio::sync::mpsc;
 
 #[derive(Debug, Clone, Copy)]
 /// Configuration for event handling.
@@ -25,11 +26,10 @@ pub enum Event<I> {
 }
 
 /// A small event handler that wraps crossterm input and tick events.
-/// Each Event is handled in its own thread and returned to a common `Receiver`
+/// Input is polled on its own thread and returned to a common `Receiver`,
+/// which can be awaited alongside other channels
 pub struct Events {
-    rx: mpsc::Receiver<Event<Key>>,
-    // Needs to be kept in order to prevent disposing of the sender side
-    _tx: mpsc::Sender<Event<Key>>,
+    rx: mpsc::UnboundedReceiver<Event<Key>>,
 }
 
 impl Events {
@@ -42,27 +42,30 @@ impl Events {
 
     /// Constructs a new instance of `Events` from a given config.
     pub fn with_config(config: EventConfig) -> Events {
-        let (tx, rx) = mpsc::channel();
+        let (tx, rx) = mpsc::unbounded_channel();
 
-        let event_tx = tx.clone();
+        // stops once the receiver is dropped
         thread::spawn(move || loop {
             if event::poll(config.tick_rate).unwrap() {
                 if let event::Event::Key(key) = event::read().unwrap() {
                     let key = Key::from(key);
 
-                    event_tx.send(Event::Input(key)).unwrap();
+                    if tx.send(Event::Input(key)).is_err() {
+                        break;
+                    }
                 }
             }
 
-            event_tx.send(Event::Tick).unwrap();
+            if tx.send(Event::Tick).is_err() {
+                break;
+            }
         });
 
-        Events { rx, _tx: tx }
+        Events { rx }
     }
 
-    /// Attempts to read an event.
-    /// This function will block the current thread.
-    pub fn next(&self) -> Result<Event<Key>, mpsc::RecvError> {
-        self.rx.recv()
+    /// Waits for the next event, `None` if the input thread has stopped
+    pub async fn next(&mut self) -> Option<Event<Key>> {
+        self.rx.recv().await
     }
 }
@@ -1,8 +1,7 @@
-use super::common;
-use crate::app::{ActiveBlock, App, RouteId, ANIME_OPTIONS};
+use super::{common, entries};
+use crate::app::{App, ANIME_OPTIONS};
 
 use crate::event::Key;
-use crate::network::IoEvent;
 
 pub fn handler(key: Key, app: &mut App) {
     match key {
@@ -34,7 +33,7 @@ pub fn handler(key: Key, app: &mut App) {
             // Seasonal
             0 => {}
             // Ranking
-            1 => {}
+            1 => entries::open_anime_ranking(app),
             // Suggested
             2 => {}
             // Search
@@ -0,0 +1,34 @@
+use super::{common, details};
+use crate::app::{ActiveBlock, App, RouteId};
+use crate::event::Key;
+use crate::network::IoEvent;
+
+/// Shows the backlog, which is made from the user's anime list
+pub fn open(app: &mut App) {
+    app.dispatch(IoEvent::GetAnimeList("@me".to_string()));
+    app.backlog.selected_index = 0;
+    app.set_current_route_state(Some(ActiveBlock::Empty), None);
+    app.push_navigation_stack(RouteId::Backlog, ActiveBlock::Backlog);
+}
+
+pub fn handler(key: Key, app: &mut App) {
+    let shows: Vec<u64> = app.backlog_queue().iter().map(|anime| anime.id).collect();
+    match key {
+        k if k == app.app_config.keys.move_up => app.move_backlog_item(true),
+        k if k == app.app_config.keys.move_down => app.move_backlog_item(false),
+        k if common::down_event(k) => {
+            app.backlog.selected_index =
+                common::on_down_press(&shows, Some(app.backlog.selected_index));
+        }
+        k if common::up_event(k) => {
+            app.backlog.selected_index =
+                common::on_up_press(&shows, Some(app.backlog.selected_index));
+        }
+        Key::Enter => {
+            if let Some(&anime_id) = shows.get(app.backlog.selected_index) {
+                details::open_anime(app, anime_id);
+            }
+        }
+        _ => {}
+    }
+}
@@ -0,0 +1,111 @@
+use super::common;
+use crate::app::{ActiveBlock, App, BatchStep, BATCH_ACTIONS};
+use crate::batch::{self, BatchAction};
+use crate::event::Key;
+
+/// The step each of `BATCH_ACTIONS` goes to
+const ACTION_STEPS: [BatchStep; BATCH_ACTIONS.len()] = [
+    BatchStep::Status,
+    BatchStep::Score,
+    BatchStep::AddTags,
+    BatchStep::RemoveTags,
+    BatchStep::Priority,
+    BatchStep::ConfirmDelete,
+];
+
+/// The popup for what to do to the marked entries of a user list
+pub fn handler(key: Key, app: &mut App) {
+    match app.batch_menu.step {
+        BatchStep::Action | BatchStep::Status | BatchStep::Priority => pick_handler(key, app),
+        BatchStep::Score | BatchStep::AddTags | BatchStep::RemoveTags => input_handler(key, app),
+        BatchStep::ConfirmDelete => match key {
+            Key::Char('y') => app.run_batch(BatchAction::Delete),
+            Key::Char('n') | Key::Esc => back(app),
+            _ => {}
+        },
+        // the batch goes on with the popup closed, the list's title shows how far
+        BatchStep::Progress => {
+            if matches!(key, Key::Esc | Key::Enter) {
+                close(app);
+            }
+        }
+    }
+}
+
+fn pick_handler(key: Key, app: &mut App) {
+    let options = app.batch_menu.options();
+    let selected_index = app.batch_menu.selected_index;
+    match key {
+        k if common::down_event(k) => {
+            app.batch_menu.selected_index = common::on_down_press(options, Some(selected_index));
+        }
+        k if common::up_event(k) => {
+            app.batch_menu.selected_index = common::on_up_press(options, Some(selected_index));
+        }
+        Key::Enter => match app.batch_menu.step {
+            BatchStep::Action => {
+                app.batch_menu.step = ACTION_STEPS[selected_index];
+                app.batch_menu.selected_index = 0;
+                app.batch_menu.input.clear();
+            }
+            BatchStep::Status => app.run_batch(BatchAction::Status(options[selected_index])),
+            BatchStep::Priority => app.run_batch(BatchAction::Priority(selected_index as u8)),
+            _ => {}
+        },
+        Key::Esc => back(app),
+        _ => {}
+    }
+}
+
+/// Typing a score or tags, Enter does nothing until it's one
+fn input_handler(key: Key, app: &mut App) {
+    match key {
+        Key::Enter => {
+            let input = &app.batch_menu.input;
+            let action = match app.batch_menu.step {
+                BatchStep::Score => input
+                    .trim()
+                    .parse()
+                    .ok()
+                    .filter(|&score| score <= 10)
+                    .map(BatchAction::Score),
+                BatchStep::AddTags => Some(BatchAction::AddTags(batch::parse_tags(input))),
+                BatchStep::RemoveTags => Some(BatchAction::RemoveTags(batch::parse_tags(input))),
+                _ => None,
+            };
+            match action {
+                Some(BatchAction::AddTags(tags)) | Some(BatchAction::RemoveTags(tags))
+                    if tags.is_empty() => {}
+                Some(action) => app.run_batch(action),
+                None => {}
+            }
+        }
+        Key::Esc => back(app),
+        Key::Backspace | Key::Ctrl('h') => {
+            app.batch_menu.input.pop();
+        }
+        Key::Char(c) => app.batch_menu.input.push(c),
+        _ => {}
+    }
+}
+
+/// Goes back to picking an action, or closes the popup if that's where it was
+fn back(app: &mut App) {
+    match app.batch_menu.step {
+        BatchStep::Action => close(app),
+        step => {
+            app.batch_menu.selected_index = ACTION_STEPS
+                .iter()
+                .position(|&action_step| action_step == step)
+                .unwrap_or(0);
+            app.batch_menu.step = BatchStep::Action;
+        }
+    }
+}
+
+fn close(app: &mut App) {
+    // an error has its own route, which is left as is
+    if app.get_current_route().active_block == ActiveBlock::BatchEdit {
+        app.set_current_route_state(Some(ActiveBlock::Entries), None);
+    }
+}
@@ -0,0 +1,35 @@
+use super::{common, details};
+use crate::app::{ActiveBlock, App, RouteId};
+use crate::event::Key;
+use crate::network::IoEvent;
+use chrono::Utc;
+
+/// Shows what the user is behind on, which is worked out from their anime list
+pub fn open(app: &mut App) {
+    app.dispatch(IoEvent::GetAnimeList("@me".to_string()));
+    app.catch_up_index = 0;
+    app.set_current_route_state(Some(ActiveBlock::Empty), None);
+    app.push_navigation_stack(RouteId::CatchUp, ActiveBlock::CatchUp);
+}
+
+pub fn handler(key: Key, app: &mut App) {
+    let shows: Vec<u64> = app
+        .catch_up(Utc::now())
+        .iter()
+        .map(|show| show.anime.id)
+        .collect();
+    match key {
+        k if common::down_event(k) => {
+            app.catch_up_index = common::on_down_press(&shows, Some(app.catch_up_index));
+        }
+        k if common::up_event(k) => {
+            app.catch_up_index = common::on_up_press(&shows, Some(app.catch_up_index));
+        }
+        Key::Enter => {
+            if let Some(&anime_id) = shows.get(app.catch_up_index) {
+                details::open_anime(app, anime_id);
+            }
+        }
+        _ => {}
+    }
+}
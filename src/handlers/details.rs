@@ -0,0 +1,18 @@
+use crate::app::{ActiveBlock, App, RouteId};
+use crate::network::IoEvent;
+
+/// Shows the anime's details, fetching them first
+pub fn open_anime(app: &mut App, anime_id: u64) {
+    app.anime_details = None;
+    app.dispatch(IoEvent::GetAnime(anime_id));
+    app.set_current_route_state(Some(ActiveBlock::Empty), None);
+    app.push_navigation_stack(RouteId::AnimeDetails, ActiveBlock::Details);
+}
+
+/// Shows the manga's details, fetching them first
+pub fn open_manga(app: &mut App, manga_id: u64) {
+    app.manga_details = None;
+    app.dispatch(IoEvent::GetManga(manga_id));
+    app.set_current_route_state(Some(ActiveBlock::Empty), None);
+    app.push_navigation_stack(RouteId::MangaDetails, ActiveBlock::Details);
+}
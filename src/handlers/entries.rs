@@ -0,0 +1,119 @@
+use super::{common, details};
+use crate::api::model::{AnimeRankingType, MangaRankingType};
+use crate::app::{ActiveBlock, App, Entry, EntryView, RouteId, SearchResultBlock};
+use crate::event::Key;
+use crate::network::IoEvent;
+
+pub fn open_anime_ranking(app: &mut App) {
+    app.dispatch(IoEvent::GetAnimeRanking(AnimeRankingType::All));
+    open(app, RouteId::AnimeRanking);
+}
+
+pub fn open_manga_ranking(app: &mut App) {
+    app.dispatch(IoEvent::GetMangaRanking(MangaRankingType::All));
+    open(app, RouteId::MangaRanking);
+}
+
+pub fn open_anime_list(app: &mut App) {
+    app.dispatch(IoEvent::GetAnimeList("@me".to_string()));
+    open(app, RouteId::AnimeList);
+}
+
+pub fn open_manga_list(app: &mut App) {
+    app.dispatch(IoEvent::GetMangaList("@me".to_string()));
+    open(app, RouteId::MangaList);
+}
+
+fn open(app: &mut App, route: RouteId) {
+    app.set_current_route_state(Some(ActiveBlock::Empty), None);
+    app.push_navigation_stack(route, ActiveBlock::Entries);
+}
+
+/// Search results, rankings and user lists
+pub fn handler(key: Key, app: &mut App) {
+    let view = match app.focused_view() {
+        Some(view) => view,
+        None => return,
+    };
+    if app.is_editing_filter() {
+        return filter_handler(key, app, view);
+    }
+
+    let is_search = app.get_current_route().id == RouteId::Search;
+    let is_user_list = matches!(view, EntryView::AnimeList | EntryView::MangaList);
+    match key {
+        k if common::down_event(k) => move_cursor(app, view, true),
+        k if common::up_event(k) => move_cursor(app, view, false),
+        k if is_search && common::left_event(k) => {
+            app.search_results.hovered_block = SearchResultBlock::AnimeSearch;
+        }
+        k if is_search && common::right_event(k) => {
+            app.search_results.hovered_block = SearchResultBlock::MangaSearch;
+        }
+        Key::Enter => open_selected(app, view),
+        k if k == app.app_config.keys.sort => app.cycle_sort(view),
+        k if k == app.app_config.keys.reverse_sort => app.reverse_sort(view),
+        k if is_user_list && k == app.app_config.keys.mark => {
+            app.toggle_mark(view);
+            move_cursor(app, view, true);
+        }
+        k if is_user_list && k == app.app_config.keys.mark_range => app.toggle_range(view),
+        k if is_user_list && k == app.app_config.keys.batch => app.open_batch_menu(),
+        _ => {}
+    }
+}
+
+/// Typing the `/` filter, the rows narrow down with every key
+fn filter_handler(key: Key, app: &mut App, view: EntryView) {
+    let query = app
+        .views
+        .get(&view)
+        .and_then(|state| state.filter.as_ref())
+        .map(|filter| filter.query.clone())
+        .unwrap_or_default();
+    match key {
+        Key::Enter if query.is_empty() => app.clear_filter(view),
+        // keep the filter and go back to moving around
+        Key::Enter => {
+            if let Some(filter) = app.views.get_mut(&view).and_then(|s| s.filter.as_mut()) {
+                filter.editing = false;
+            }
+        }
+        Key::Esc => app.clear_filter(view),
+        Key::Down | Key::Ctrl('n') => move_cursor(app, view, true),
+        Key::Up | Key::Ctrl('p') => move_cursor(app, view, false),
+        Key::Backspace | Key::Ctrl('h') if query.is_empty() => app.clear_filter(view),
+        Key::Backspace | Key::Ctrl('h') => {
+            let mut query = query;
+            query.pop();
+            app.set_filter_query(view, query);
+        }
+        Key::Char(c) => app.set_filter_query(view, format!("{}{}", query, c)),
+        _ => {}
+    }
+}
+
+fn move_cursor(app: &mut App, view: EntryView, down: bool) {
+    let rows = app.visible_rows(view);
+    let state = app.views.entry(view).or_default();
+    let selected_index = Some(state.selected_index);
+    state.selected_index = if down {
+        common::on_down_press(&rows, selected_index)
+    } else {
+        common::on_up_press(&rows, selected_index)
+    };
+}
+
+fn open_selected(app: &mut App, view: EntryView) {
+    match app.selected_entry(view) {
+        Some(Entry::Anime(anime)) => {
+            let id = anime.id;
+            details::open_anime(app, id);
+        }
+        Some(Entry::Manga(manga)) => {
+            let id = manga.id;
+            details::open_manga(app, id);
+        }
+        None => {}
+    }
+}
@@ -0,0 +1,38 @@
+use super::common;
+use crate::app::{ActiveBlock, App};
+use crate::event::Key;
+
+/// The search history popup, typing narrows it down
+pub fn handler(key: Key, app: &mut App) {
+    let matches = &app.history_popup.matches;
+    let selected_index = Some(app.history_popup.selected_index);
+    match key {
+        Key::Down | Key::Ctrl('n') => {
+            app.history_popup.selected_index = common::on_down_press(matches, selected_index);
+        }
+        Key::Up | Key::Ctrl('p') => {
+            app.history_popup.selected_index = common::on_up_press(matches, selected_index);
+        }
+        Key::Enter => {
+            if let Some(query) = app.selected_history_entry().cloned() {
+                app.set_input(&query);
+                app.update_suggestions();
+            }
+            close(app);
+        }
+        Key::Esc | Key::Ctrl('r') => close(app),
+        Key::Backspace | Key::Ctrl('h') => {
+            app.history_popup.filter.pop();
+            app.update_history_popup();
+        }
+        Key::Char(c) => {
+            app.history_popup.filter.push(c);
+            app.update_history_popup();
+        }
+        _ => {}
+    }
+}
+
+fn close(app: &mut App) {
+    app.set_current_route_state(Some(ActiveBlock::Input), Some(ActiveBlock::Input));
+}
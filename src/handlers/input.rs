@@ -1,10 +1,23 @@
-use crate::app::{ActiveBlock, App, RouteId};
+use super::details;
+use crate::app::{ActiveBlock, App, Suggestion};
 use crate::event::Key;
-use crate::network::IoEvent;
+use crate::search::SearchScope;
 use std::convert::TryInto;
 use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
 
 pub fn handler(key: Key, app: &mut App) {
+    let input = app.input.clone();
+    handle_key(key, app);
+    if app.input != input {
+        // editing a recalled search makes it a new one
+        if !matches!(key, Key::Up | Key::Down) {
+            app.history_index = None;
+        }
+        app.update_suggestions();
+    }
+}
+
+fn handle_key(key: Key, app: &mut App) {
     match key {
         // Delete everything after the cursor including selected character
         Key::Ctrl('k') => {
@@ -51,24 +64,57 @@ pub fn handler(key: Key, app: &mut App) {
             app.input_idx = 0;
             app.input_cursor_position = 0;
         }
-        Key::Left | Key::Ctrl('b') => {
-            if !app.input.is_empty() && app.input_idx > 0 {
-                let last_c = app.input[app.input_idx - 1];
-                app.input_idx -= 1;
-                app.input_cursor_position -= compute_character_width(last_c);
-            }
+        Key::Left | Key::Ctrl('b') if !app.input.is_empty() && app.input_idx > 0 => {
+            let last_c = app.input[app.input_idx - 1];
+            app.input_idx -= 1;
+            app.input_cursor_position -= compute_character_width(last_c);
         }
-        Key::Right | Key::Ctrl('f') => {
-            if app.input_idx < app.input.len() {
-                let next_c = app.input[app.input_idx];
-                app.input_idx += 1;
-                app.input_cursor_position += compute_character_width(next_c);
-            }
+        Key::Right | Key::Ctrl('f') if app.input_idx < app.input.len() => {
+            let next_c = app.input[app.input_idx];
+            app.input_idx += 1;
+            app.input_cursor_position += compute_character_width(next_c);
+        }
+        // Go through past searches, starting from an empty search box
+        Key::Up if app.input.is_empty() || app.history_index.is_some() => {
+            app.recall_history(true);
+        }
+        Key::Down if app.history_index.is_some() => {
+            app.recall_history(false);
+        }
+        Key::Down if !app.suggestions.is_empty() => {
+            let next = app.suggestions.selected_index.map_or(0, |index| index + 1);
+            app.suggestions.selected_index = Some(next % app.suggestions.len());
+        }
+        Key::Up if !app.suggestions.is_empty() => {
+            let len = app.suggestions.len();
+            let previous = app
+                .suggestions
+                .selected_index
+                .map_or(len - 1, |index| (index + len - 1) % len);
+            app.suggestions.selected_index = Some(previous);
+        }
+        Key::Ctrl('r') => {
+            app.open_history_popup();
+        }
+        Key::Ctrl('s') if !app.input.is_empty() => {
+            app.save_prompt = Default::default();
+            app.set_current_route_state(Some(ActiveBlock::SaveSearch), None);
         }
         Key::Esc => {
+            app.suggestions.selected_index = None;
             app.set_current_route_state(Some(ActiveBlock::Empty), Some(ActiveBlock::BasicView));
         }
         Key::Enter => {
+            // Jump straight to the highlighted suggestion
+            if let Some(suggestion) = app.suggestions.selected() {
+                app.suggestions.selected_index = None;
+                match suggestion {
+                    Suggestion::Anime(id) => details::open_anime(app, id),
+                    Suggestion::Manga(id) => details::open_manga(app, id),
+                }
+                return;
+            }
+
             let input_str: String = app.input.iter().collect();
 
             // Don't do anything if there is no input
@@ -76,27 +122,22 @@ pub fn handler(key: Key, app: &mut App) {
                 return;
             }
 
-            app.dispatch(IoEvent::GetSearchResults(input_str));
-
-            // On searching for a track, clear the playlist selection
-            app.push_navigation_stack(RouteId::Search, ActiveBlock::SearchResultBlock);
+            app.search(input_str, SearchScope::All);
         }
         Key::Char(c) => {
             app.input.insert(app.input_idx, c);
             app.input_idx += 1;
             app.input_cursor_position += compute_character_width(c);
         }
-        Key::Backspace | Key::Ctrl('h') => {
-            if !app.input.is_empty() && app.input_idx > 0 {
-                let last_c = app.input.remove(app.input_idx - 1);
-                app.input_idx -= 1;
-                app.input_cursor_position -= compute_character_width(last_c);
-            }
+        Key::Backspace | Key::Ctrl('h') if !app.input.is_empty() && app.input_idx > 0 => {
+            let last_c = app.input.remove(app.input_idx - 1);
+            app.input_idx -= 1;
+            app.input_cursor_position -= compute_character_width(last_c);
         }
-        Key::Delete | Key::Ctrl('d') => {
-            if !app.input.is_empty() && app.input_idx < app.input.len() {
-                app.input.remove(app.input_idx);
-            }
+        Key::Delete | Key::Ctrl('d')
+            if !app.input.is_empty() && app.input_idx < app.input.len() =>
+        {
+            app.input.remove(app.input_idx);
         }
         _ => {}
     }
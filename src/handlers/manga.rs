@@ -1,8 +1,7 @@
-use super::common;
-use crate::app::{ActiveBlock, App, RouteId, MANGA_OPTIONS};
+use super::{common, entries};
+use crate::app::{App, MANGA_OPTIONS};
 
 use crate::event::Key;
-use crate::network::IoEvent;
 
 pub fn handler(key: Key, app: &mut App) {
     match key {
@@ -32,7 +31,7 @@ pub fn handler(key: Key, app: &mut App) {
         // like this
         Key::Enter => match app.library.selected_index {
             // Ranking
-            0 => {}
+            0 => entries::open_manga_ranking(app),
             // Search
             1 => {}
             // This is required because Rust can't tell if this pattern in exhaustive
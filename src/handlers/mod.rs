@@ -1,16 +1,27 @@
 mod anime;
+mod backlog;
+mod batch;
+mod catch_up;
 mod common;
+mod details;
+mod entries;
 mod help;
+mod history;
 mod input;
 mod manga;
+mod profile;
+mod saved_searches;
+mod schedule;
 mod user;
 
-use crate::api::model::*;
-use crate::app::{ActiveBlock, App, RouteId, SearchResultBlock};
+use crate::app::{ActiveBlock, App, SearchResultBlock};
 use crate::event::Key;
-use crate::network::IoEvent;
 
+pub use batch::handler as batch_handler;
+pub use entries::handler as entries_handler;
+pub use history::handler as history_handler;
 pub use input::handler as input_handler;
+pub use saved_searches::prompt_handler as save_search_handler;
 
 pub fn handle_app(key: Key, app: &mut App) {
     // First handle any global event and then move to block event
@@ -21,6 +32,21 @@ pub fn handle_app(key: Key, app: &mut App) {
         _ if key == app.app_config.keys.help => {
             app.set_current_route_state(Some(ActiveBlock::Help), None);
         }
+        _ if key == app.app_config.keys.switch_profile => {
+            profile::open(app);
+        }
+        // `/` filters the list in focus rather than searching MAL
+        _ if key == app.app_config.keys.filter && app.focused_view().is_some() => {
+            if let Some(view) = app.focused_view() {
+                app.start_filter(view);
+            }
+        }
+        _ if key == app.app_config.keys.saved_searches => {
+            app.set_current_route_state(
+                Some(ActiveBlock::SavedSearches),
+                Some(ActiveBlock::SavedSearches),
+            );
+        }
         _ if key == app.app_config.keys.search => {
             app.set_current_route_state(Some(ActiveBlock::Input), Some(ActiveBlock::Input));
         }
@@ -35,7 +61,9 @@ fn handle_block_events(key: Key, app: &mut App) {
         ActiveBlock::Input => {
             input::handler(key, app);
         }
-        ActiveBlock::SearchResultBlock => {}
+        ActiveBlock::SearchResultBlock | ActiveBlock::Entries => {
+            entries::handler(key, app);
+        }
         ActiveBlock::Empty => {}
         ActiveBlock::UserStats => {}
         ActiveBlock::Error => {}
@@ -52,10 +80,48 @@ fn handle_block_events(key: Key, app: &mut App) {
         ActiveBlock::User => {
             user::handler(key, app);
         }
+        ActiveBlock::ProfileSwitcher => {
+            profile::handler(key, app);
+        }
+        ActiveBlock::Details => {}
+        ActiveBlock::Schedule => {
+            schedule::handler(key, app);
+        }
+        ActiveBlock::CatchUp => {
+            catch_up::handler(key, app);
+        }
+        ActiveBlock::Backlog => {
+            backlog::handler(key, app);
+        }
+        ActiveBlock::BatchEdit => {
+            batch::handler(key, app);
+        }
+        ActiveBlock::SearchHistory => {
+            history::handler(key, app);
+        }
+        ActiveBlock::SaveSearch => {
+            saved_searches::prompt_handler(key, app);
+        }
+        ActiveBlock::SavedSearches => {
+            saved_searches::handler(key, app);
+        }
     }
 }
 
 fn handle_escape(app: &mut App) {
+    if let Some(view) = app.focused_view() {
+        if app.clear_marks(view) {
+            return;
+        }
+        let filtered = app
+            .views
+            .get(&view)
+            .is_some_and(|state| state.filter.is_some());
+        if filtered {
+            return app.clear_filter(view);
+        }
+    }
+
     match app.get_current_route().active_block {
         ActiveBlock::SearchResultBlock => {
             app.search_results.selected_block = SearchResultBlock::Empty;
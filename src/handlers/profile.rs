@@ -0,0 +1,47 @@
+use super::common;
+use crate::app::{ActiveBlock, App};
+use crate::config::Profile;
+use crate::event::Key;
+use crate::network::IoEvent;
+
+/// Shows the account switcher with the profiles currently on disk
+pub fn open(app: &mut App) {
+    if let Ok(names) = Profile::list() {
+        app.profiles.names = names;
+    }
+    if !app.profiles.names.contains(&app.profiles.active) {
+        app.profiles.names.push(app.profiles.active.clone());
+    }
+    app.profiles.selected_index = app
+        .profiles
+        .names
+        .iter()
+        .position(|name| *name == app.profiles.active)
+        .unwrap_or(0);
+    app.set_current_route_state(
+        Some(ActiveBlock::ProfileSwitcher),
+        Some(ActiveBlock::ProfileSwitcher),
+    );
+}
+
+pub fn handler(key: Key, app: &mut App) {
+    let names = &app.profiles.names;
+    let selected_index = Some(app.profiles.selected_index);
+    match key {
+        k if common::down_event(k) => {
+            app.profiles.selected_index = common::on_down_press(names, selected_index);
+        }
+        k if common::up_event(k) => {
+            app.profiles.selected_index = common::on_up_press(names, selected_index);
+        }
+        Key::Enter => {
+            if let Some(name) = names.get(app.profiles.selected_index).cloned() {
+                if name != app.profiles.active {
+                    app.dispatch(IoEvent::SwitchProfile(name));
+                }
+            }
+            app.set_current_route_state(Some(ActiveBlock::Empty), Some(ActiveBlock::Anime));
+        }
+        _ => {}
+    }
+}
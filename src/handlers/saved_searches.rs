@@ -0,0 +1,45 @@
+use super::common;
+use crate::app::{ActiveBlock, App};
+use crate::event::Key;
+
+/// The saved searches in the sidebar
+pub fn handler(key: Key, app: &mut App) {
+    let saved = &app.searches.saved;
+    let selected_index = Some(app.saved_searches_index);
+    match key {
+        k if common::down_event(k) => {
+            app.saved_searches_index = common::on_down_press(saved, selected_index);
+        }
+        k if common::up_event(k) => {
+            app.saved_searches_index = common::on_up_press(saved, selected_index);
+        }
+        Key::Enter => app.run_saved_search(),
+        k if k == app.app_config.keys.delete || k == Key::Delete => app.delete_saved_search(),
+        _ => {}
+    }
+}
+
+/// Naming the search in the search box before saving it
+pub fn prompt_handler(key: Key, app: &mut App) {
+    match key {
+        Key::Enter => {
+            app.save_search();
+            close_prompt(app);
+        }
+        Key::Esc => close_prompt(app),
+        // cycle through all, anime and manga
+        Key::Tab => app.save_prompt.scope = app.save_prompt.scope.next(),
+        Key::Backspace | Key::Ctrl('h') => {
+            app.save_prompt.name.pop();
+        }
+        Key::Char(c) => app.save_prompt.name.push(c),
+        _ => {}
+    }
+}
+
+fn close_prompt(app: &mut App) {
+    // an error from saving has its own route, which is left as is
+    if app.get_current_route().active_block == ActiveBlock::SaveSearch {
+        app.set_current_route_state(Some(ActiveBlock::Input), Some(ActiveBlock::Input));
+    }
+}
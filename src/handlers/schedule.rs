@@ -0,0 +1,43 @@
+use super::{common, details};
+use crate::app::{ActiveBlock, App, RouteId};
+use crate::event::Key;
+use crate::network::IoEvent;
+use chrono::Utc;
+
+/// Shows the airing schedule, which is made from the user's anime list
+pub fn open(app: &mut App) {
+    app.dispatch(IoEvent::GetAnimeList("@me".to_string()));
+    app.schedule.index = 0;
+    app.set_current_route_state(Some(ActiveBlock::Empty), None);
+    app.push_navigation_stack(RouteId::Schedule, ActiveBlock::Schedule);
+}
+
+pub fn handler(key: Key, app: &mut App) {
+    let now = Utc::now();
+    let day = app.schedule_day(now);
+    let shows: Vec<u64> = app.schedule_week(now)[day]
+        .iter()
+        .map(|airing| airing.anime.id)
+        .collect();
+    match key {
+        k if common::left_event(k) => move_day(app, (day + 6) % 7),
+        k if common::right_event(k) => move_day(app, (day + 1) % 7),
+        k if common::down_event(k) => {
+            app.schedule.index = common::on_down_press(&shows, Some(app.schedule.index));
+        }
+        k if common::up_event(k) => {
+            app.schedule.index = common::on_up_press(&shows, Some(app.schedule.index));
+        }
+        Key::Enter => {
+            if let Some(&anime_id) = shows.get(app.schedule.index) {
+                details::open_anime(app, anime_id);
+            }
+        }
+        _ => {}
+    }
+}
+
+fn move_day(app: &mut App, day: usize) {
+    app.schedule.day = Some(day);
+    app.schedule.index = 0;
+}
@@ -1,8 +1,7 @@
-use super::common;
-use crate::app::{ActiveBlock, App, RouteId, USER_OPTIONS};
+use super::{backlog, catch_up, common, entries, schedule};
+use crate::app::{App, USER_OPTIONS};
 
 use crate::event::Key;
-use crate::network::IoEvent;
 
 pub fn handler(key: Key, app: &mut App) {
     match key {
@@ -33,9 +32,15 @@ pub fn handler(key: Key, app: &mut App) {
             // Stats
             0 => {}
             // AnimeList
-            1 => {}
+            1 => entries::open_anime_list(app),
             // MangaList
-            2 => {}
+            2 => entries::open_manga_list(app),
+            // Schedule
+            3 => schedule::open(app),
+            // CatchUp
+            4 => catch_up::open(app),
+            // Backlog
+            5 => backlog::open(app),
             // This is required because Rust can't tell if this pattern in exhaustive
             _ => {}
         },
@@ -33,7 +33,7 @@ Routing
 pub mod auth;
 
 /// API request functions
-pub mod api;
+pub use rmal::api;
 
 /// UI
 pub mod ui;
@@ -47,6 +47,9 @@ pub mod app;
 /// Network
 pub mod network;
 
+/// Search query syntax
+pub mod search;
+
 /// Events
 pub mod event;
 
@@ -55,3 +58,15 @@ pub mod handlers;
 
 /// Cli
 pub mod cli;
+
+/// Cover art
+pub mod cover;
+
+/// Airing schedule
+pub mod schedule;
+
+/// Watch time estimates
+pub mod backlog;
+
+/// Bulk list changes
+pub mod batch;
@@ -5,23 +5,27 @@ use crossterm::{cursor::MoveTo, ExecutableCommand};
 use eyre::Result;
 
 use tui::backend::CrosstermBackend;
+use tui::layout::Rect;
 use tui::Terminal;
 
-use std::sync::Arc;
 use std::{
     io::{self, Write},
     panic,
 };
-use tokio::sync::Mutex;
+use tokio::sync::mpsc::{unbounded_channel, UnboundedReceiver};
+use tokio::time;
 
 use mal::app::*;
-use mal::auth::OAuth;
-use mal::cli::{Opt, StructOpt};
-use mal::config::{AppConfig, AuthConfig};
+use mal::auth;
+use mal::cli::{Command, Opt, StructOpt};
+use mal::config::{AppConfig, AuthConfig, Profile};
+use mal::cover::Protocol;
 use mal::event;
 use mal::event::key::Key;
 use mal::handlers;
-use mal::network::{IoEvent, Network};
+use mal::network::{IoEvent, IoResponse, Network, RequestId};
+use mal::schedule;
+use mal::search::Searches;
 use mal::ui;
 
 fn setup_terminal() -> Result<()> {
@@ -68,109 +72,198 @@ async fn main() -> Result<()> {
 
     let opt: Opt = Opt::from_args();
 
+    let profile = Profile::new(&opt.profile)?;
+    let login_mode = if opt.no_browser {
+        auth::LoginMode::NoBrowser
+    } else {
+        auth::LoginMode::Browser
+    };
+
+    if let Some(Command::Auth(command)) = opt.command {
+        return auth::commands::run(command, &profile, login_mode).await;
+    }
+
     // Get config
-    let app_config = AppConfig::load()?;
+    let app_config = AppConfig::load(&profile)?;
+    let auth_config = AuthConfig::load(&profile)?;
+    let (oauth, store) = auth::get_auth_async(auth_config, &profile, login_mode).await?;
 
-    let auth_config = AuthConfig::load()?;
-    let oauth = OAuth::get_auth_async(auth_config).await?;
+    if let Some(Command::Schedule(command)) = opt.command {
+        return schedule::commands::run(command, &app_config, &oauth).await;
+    }
 
-    let (sync_io_tx, sync_io_rx) = std::sync::mpsc::channel::<IoEvent>();
+    let (io_tx, io_rx) = unbounded_channel::<(RequestId, IoEvent)>();
+    let (response_tx, response_rx) = unbounded_channel::<(RequestId, IoResponse)>();
 
-    // initialize app state
-    let app = Arc::new(Mutex::new(App::new(sync_io_tx, app_config.clone())));
+    // initialize app state, it's owned by the ui and only changed there
+    let mut app = App::new(io_tx, app_config.clone(), profile.name.clone());
+    app.searches = Searches::load(&profile)?;
+    app.dispatch(IoEvent::GetUserInfo("@me".to_string()));
 
-    let cloned_app = Arc::clone(&app);
-    std::thread::spawn(move || {
-        let mut network = Network::new(oauth, &app);
-        start_network(sync_io_rx, &mut network);
-    });
+    let network = Network::new(profile.name.clone(), oauth, store, &app_config, response_tx);
+    tokio::spawn(network.run(io_rx));
 
     // run ui
-    start_ui(app_config, &cloned_app).await?;
+    start_ui(app_config, app, response_rx).await?;
 
     Ok(())
 }
 
-#[tokio::main]
-async fn start_network<'a>(io_rx: std::sync::mpsc::Receiver<IoEvent>, network: &mut Network) {
-    while let Ok(io_event) = io_rx.recv() {
-        network.handle_network_event(io_event).await;
-    }
-}
-
-async fn start_ui(app_config: AppConfig, app: &Arc<Mutex<App>>) -> Result<()> {
+async fn start_ui(
+    app_config: AppConfig,
+    mut app: App,
+    mut io_rx: UnboundedReceiver<(RequestId, IoResponse)>,
+) -> Result<()> {
     // set up terminal
     let backend = CrosstermBackend::new(io::stdout());
     let mut terminal = Terminal::new(backend)?;
     setup_terminal()?;
 
-    let events = event::Events::new(app_config.behavior.tick_rate_milliseconds);
-
-    let mut is_first_render = true;
+    let mut events = event::Events::new(app_config.behavior.tick_rate_milliseconds);
+    let mut shown_cover = None;
 
     loop {
-        let mut app = app.lock().await;
-
-        let current_route = app.get_current_route();
-        terminal.draw(|mut f| match current_route.active_block {
-            ActiveBlock::Help => {
-                ui::draw_help_menu(&mut f, &app);
+        draw(&mut terminal, &app, &mut shown_cover)?;
+
+        // wakes up to send the suggestions query once typing pauses
+        let suggestions_deadline = app.suggestions.deadline();
+        let suggestions_delay = time::delay_until(time::Instant::from_std(
+            suggestions_deadline.unwrap_or_else(std::time::Instant::now),
+        ));
+
+        // nothing holds the app while waiting, network results are applied as they arrive
+        tokio::select! {
+            event = events.next() => match event {
+                Some(event::Event::Input(key)) => {
+                    if !handle_key(key, &mut app) {
+                        break;
+                    }
+                }
+                Some(event::Event::Tick) => {}
+                None => break,
+            },
+            Some((id, response)) = io_rx.recv() => app.handle_io_response(id, response),
+            _ = suggestions_delay, if suggestions_deadline.is_some() => {
+                app.send_suggestions_query(std::time::Instant::now());
             }
-            ActiveBlock::Error => {
-                ui::draw_error(&mut f, &app);
+        }
+    }
+
+    // clean up terminal
+    cleanup_terminal()?;
+    Ok(())
+}
+
+/// The cover the terminal was last told to draw, and where
+type ShownCover = Option<(String, Rect)>;
+
+fn draw<B: tui::backend::Backend + Write>(
+    terminal: &mut Terminal<B>,
+    app: &App,
+    shown_cover: &mut ShownCover,
+) -> Result<()> {
+    let current_route = app.get_current_route();
+    let cover_area = draw_frame(terminal, app)?;
+
+    // pictures drawn by the terminal aren't in tui's buffers, so they're only sent
+    // again when they change
+    let wanted = match (cover_area, &app.cover) {
+        (Some(area), Some(cover)) => Some((cover.url.clone(), area)),
+        _ => None,
+    };
+    if wanted != *shown_cover {
+        if shown_cover.take().is_some() {
+            match app.cover_protocol.and_then(Protocol::clear) {
+                Some(clear) => write!(terminal.backend_mut(), "{}", clear)?,
+                // the picture replaced the cells, repaint all of them
+                None => {
+                    let size = terminal.size()?;
+                    terminal.resize(size)?;
+                    draw_frame(terminal, app)?;
+                }
             }
-            _ => {
-                ui::draw_main_layout(&mut f, &app);
+        }
+        if let (Some((_, area)), Some(protocol), Some(cover)) =
+            (&wanted, app.cover_protocol, &app.cover)
+        {
+            if let Some(escape) = protocol.encode(&cover.image, area.width, area.height) {
+                terminal.backend_mut().execute(MoveTo(area.x, area.y))?;
+                write!(terminal.backend_mut(), "{}", escape)?;
+                Write::flush(terminal.backend_mut())?;
             }
-        })?;
-
-        if current_route.active_block == ActiveBlock::Input {
-            terminal.show_cursor()?;
-        } else {
-            terminal.hide_cursor()?;
         }
+        *shown_cover = wanted;
+    }
 
-        let cursor_offset = if app.size.height > ui::util::SMALL_TERMINAL_HEIGHT {
-            2
-        } else {
-            1
-        };
+    if current_route.active_block == ActiveBlock::Input {
+        terminal.show_cursor()?;
+    } else {
+        terminal.hide_cursor()?;
+    }
 
-        terminal.backend_mut().execute(MoveTo(
-            cursor_offset + app.input_cursor_position,
-            cursor_offset,
-        ))?;
+    let cursor_offset = if app.size.height > ui::util::SMALL_TERMINAL_HEIGHT {
+        2
+    } else {
+        1
+    };
 
-        match events.next()? {
-            event::Event::Input(key) => {
-                if key == Key::Ctrl('c') {
-                    break;
-                }
+    terminal.backend_mut().execute(MoveTo(
+        cursor_offset + app.input_cursor_position,
+        cursor_offset,
+    ))?;
+    Ok(())
+}
 
-                let current_active_block = app.get_current_route().active_block;
-
-                if current_active_block == ActiveBlock::Input {
-                    handlers::input_handler(key, &mut app);
-                } else if key == app.app_config.keys.back {
-                    if app.get_current_route().active_block != ActiveBlock::Input {
-                        let pop_result = match app.pop_navigation_stack() {
-                            Some(ref x) if x.id == RouteId::Search => app.pop_navigation_stack(),
-                            Some(x) => Some(x),
-                            None => None,
-                        };
-                        if pop_result.is_none() {
-                            break;
-                        }
-                    }
-                } else {
-                    handlers::handle_app(key, &mut app);
-                }
-            }
-            _ => {}
+/// Returns where the cover goes if the terminal draws it
+fn draw_frame<B: tui::backend::Backend>(
+    terminal: &mut Terminal<B>,
+    app: &App,
+) -> Result<Option<Rect>> {
+    let mut cover_area = None;
+    terminal.draw(|f| match app.get_current_route().active_block {
+        ActiveBlock::Help => {
+            ui::draw_help_menu(f, app);
         }
+        ActiveBlock::Error => {
+            ui::draw_error(f, app);
+        }
+        _ => {
+            cover_area = ui::draw_main_layout(f, app);
+        }
+    })?;
+    Ok(cover_area)
+}
+
+/// Handles a key press, returns `false` to quit
+fn handle_key(key: Key, app: &mut App) -> bool {
+    if key == Key::Ctrl('c') {
+        return false;
     }
 
-    // clean up terminal
-    cleanup_terminal()?;
-    Ok(())
+    let current_active_block = app.get_current_route().active_block;
+
+    // blocks being typed in get every key, even the global ones
+    if current_active_block == ActiveBlock::Input {
+        handlers::input_handler(key, app);
+    } else if current_active_block == ActiveBlock::SearchHistory {
+        handlers::history_handler(key, app);
+    } else if current_active_block == ActiveBlock::SaveSearch {
+        handlers::save_search_handler(key, app);
+    } else if current_active_block == ActiveBlock::BatchEdit {
+        handlers::batch_handler(key, app);
+    } else if app.is_editing_filter() {
+        handlers::entries_handler(key, app);
+    } else if key == app.app_config.keys.back {
+        let pop_result = match app.pop_navigation_stack() {
+            Some(ref x) if x.id == RouteId::Search => app.pop_navigation_stack(),
+            Some(x) => Some(x),
+            None => None,
+        };
+        if pop_result.is_none() {
+            return false;
+        }
+    } else {
+        handlers::handle_app(key, app);
+    }
+    true
 }
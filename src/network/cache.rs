@@ -0,0 +1,344 @@
+use crate::api::model::*;
+use std::{
+    any::Any,
+    collections::HashMap,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+/// Number of responses kept before the least recently used ones are dropped
+pub const DEFAULT_CAPACITY: usize = 256;
+
+/// The endpoints whose responses are cached, each stays fresh for its own time
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Endpoint {
+    AnimeDetails,
+    AnimeRanking,
+    AnimeSearch,
+    MangaDetails,
+    MangaRanking,
+    MangaSearch,
+    UserInfo,
+}
+
+impl Endpoint {
+    /// How long a response is served without asking for it again
+    pub fn ttl(self) -> Duration {
+        match self {
+            Endpoint::AnimeDetails | Endpoint::MangaDetails => Duration::from_secs(10 * 60),
+            Endpoint::AnimeRanking | Endpoint::MangaRanking => Duration::from_secs(60 * 60),
+            Endpoint::AnimeSearch | Endpoint::MangaSearch => Duration::from_secs(5 * 60),
+            Endpoint::UserInfo => Duration::from_secs(5 * 60),
+        }
+    }
+}
+
+/// An endpoint and the query (including the path parameters) it was called with, by
+/// the profile it was called for
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct CacheKey {
+    endpoint: Endpoint,
+    query: String,
+    profile: String,
+}
+
+impl CacheKey {
+    pub fn new<Q: ToString>(endpoint: Endpoint, query: Q) -> Self {
+        Self {
+            endpoint,
+            query: query.to_string(),
+            profile: String::new(),
+        }
+    }
+
+    /// The responses carry the account's list statuses, so each profile gets its own
+    pub fn for_profile(mut self, profile: &str) -> Self {
+        self.profile = profile.to_string();
+        self
+    }
+}
+
+/// An anime or manga a cached response contains
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Item {
+    Anime(u64),
+    Manga(u64),
+}
+
+/// A response that can be cached
+pub trait Cacheable: Clone + Send + Sync + 'static {
+    /// The anime and manga in the response, changing the list status of one of them
+    /// invalidates it
+    fn items(&self) -> Vec<Item> {
+        vec![]
+    }
+}
+
+impl Cacheable for Anime {
+    fn items(&self) -> Vec<Item> {
+        vec![Item::Anime(self.id)]
+    }
+}
+
+impl Cacheable for Manga {
+    fn items(&self) -> Vec<Item> {
+        vec![Item::Manga(self.id)]
+    }
+}
+
+impl Cacheable for Page<Anime> {
+    fn items(&self) -> Vec<Item> {
+        self.data
+            .iter()
+            .map(|node| Item::Anime(node.node.id))
+            .collect()
+    }
+}
+
+impl Cacheable for Page<Manga> {
+    fn items(&self) -> Vec<Item> {
+        self.data
+            .iter()
+            .map(|node| Item::Manga(node.node.id))
+            .collect()
+    }
+}
+
+impl Cacheable for Ranking<RankingAnimePair> {
+    fn items(&self) -> Vec<Item> {
+        self.data
+            .iter()
+            .map(|pair| Item::Anime(pair.node.id))
+            .collect()
+    }
+}
+
+impl Cacheable for Ranking<RankingMangaPair> {
+    fn items(&self) -> Vec<Item> {
+        self.data
+            .iter()
+            .map(|pair| Item::Manga(pair.node.id))
+            .collect()
+    }
+}
+
+impl Cacheable for UserInfo {}
+
+/// The outcome of a cache lookup
+#[derive(Debug)]
+pub enum Lookup<T> {
+    /// Younger than the endpoint's ttl
+    Fresh(T),
+    /// Older than the ttl, good to show while it's fetched again
+    Stale(T),
+    Miss,
+}
+
+struct Entry {
+    value: Arc<dyn Any + Send + Sync>,
+    items: Vec<Item>,
+    fetched_at: Instant,
+    last_used: u64,
+}
+
+/// Least recently used cache of api responses
+pub struct ResponseCache {
+    entries: HashMap<CacheKey, Entry>,
+    capacity: usize,
+    /// Incremented on every access, orders the entries by use
+    clock: u64,
+}
+
+impl Default for ResponseCache {
+    fn default() -> Self {
+        Self::new(DEFAULT_CAPACITY)
+    }
+}
+
+impl ResponseCache {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            entries: HashMap::new(),
+            capacity,
+            clock: 0,
+        }
+    }
+
+    pub fn get<T: Cacheable>(&mut self, key: &CacheKey) -> Lookup<T> {
+        self.get_at(key, Instant::now())
+    }
+
+    fn get_at<T: Cacheable>(&mut self, key: &CacheKey, now: Instant) -> Lookup<T> {
+        self.clock += 1;
+        let entry = match self.entries.get_mut(key) {
+            Some(entry) => entry,
+            None => return Lookup::Miss,
+        };
+        let value = match entry.value.downcast_ref::<T>() {
+            Some(value) => value.clone(),
+            None => return Lookup::Miss,
+        };
+
+        entry.last_used = self.clock;
+        if now.duration_since(entry.fetched_at) < key.endpoint.ttl() {
+            Lookup::Fresh(value)
+        } else {
+            Lookup::Stale(value)
+        }
+    }
+
+    pub fn insert<T: Cacheable>(&mut self, key: CacheKey, value: T) {
+        self.insert_at(key, value, Instant::now())
+    }
+
+    fn insert_at<T: Cacheable>(&mut self, key: CacheKey, value: T, now: Instant) {
+        self.clock += 1;
+        if !self.entries.contains_key(&key) && self.entries.len() >= self.capacity {
+            self.evict();
+        }
+        self.entries.insert(
+            key,
+            Entry {
+                items: value.items(),
+                value: Arc::new(value),
+                fetched_at: now,
+                last_used: self.clock,
+            },
+        );
+    }
+
+    /// Drops the least recently used entry
+    fn evict(&mut self) {
+        let oldest = self
+            .entries
+            .iter()
+            .min_by_key(|(_, entry)| entry.last_used)
+            .map(|(key, _)| key.clone());
+        if let Some(key) = oldest {
+            self.entries.remove(&key);
+        }
+    }
+
+    /// Drops every response containing `item`, after its list status changed
+    pub fn invalidate(&mut self, item: Item) {
+        self.entries.retain(|_, entry| !entry.items.contains(&item));
+    }
+
+    pub fn clear(&mut self) {
+        self.entries.clear();
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn anime(id: u64) -> Anime {
+        serde_json::from_value(serde_json::json!({ "id": id, "title": "Cowboy Bebop" })).unwrap()
+    }
+
+    fn details_key(id: u64) -> CacheKey {
+        CacheKey::new(Endpoint::AnimeDetails, id)
+    }
+
+    #[test]
+    fn test_ttl() {
+        let mut cache = ResponseCache::default();
+        let now = Instant::now();
+        cache.insert_at(details_key(1), anime(1), now);
+
+        assert!(matches!(
+            cache.get_at::<Anime>(&details_key(1), now + Duration::from_secs(60)),
+            Lookup::Fresh(_)
+        ));
+        assert!(matches!(
+            cache.get_at::<Anime>(&details_key(1), now + Endpoint::AnimeDetails.ttl()),
+            Lookup::Stale(_)
+        ));
+        assert!(matches!(
+            cache.get_at::<Anime>(&details_key(2), now),
+            Lookup::Miss
+        ));
+        // a value of another type is not returned
+        assert!(matches!(
+            cache.get_at::<Manga>(&details_key(1), now),
+            Lookup::Miss
+        ));
+    }
+
+    #[test]
+    fn test_lru_eviction() {
+        let mut cache = ResponseCache::new(2);
+        cache.insert(details_key(1), anime(1));
+        cache.insert(details_key(2), anime(2));
+
+        // 1 is now used more recently than 2
+        assert!(matches!(
+            cache.get::<Anime>(&details_key(1)),
+            Lookup::Fresh(_)
+        ));
+        cache.insert(details_key(3), anime(3));
+
+        assert_eq!(cache.len(), 2);
+        assert!(matches!(cache.get::<Anime>(&details_key(2)), Lookup::Miss));
+        assert!(matches!(
+            cache.get::<Anime>(&details_key(1)),
+            Lookup::Fresh(_)
+        ));
+        assert!(matches!(
+            cache.get::<Anime>(&details_key(3)),
+            Lookup::Fresh(_)
+        ));
+    }
+
+    #[test]
+    fn test_profiles() {
+        let mut cache = ResponseCache::default();
+        cache.insert(details_key(1).for_profile("work"), anime(1));
+        assert!(matches!(
+            cache.get::<Anime>(&details_key(1).for_profile("home")),
+            Lookup::Miss
+        ));
+        assert!(matches!(
+            cache.get::<Anime>(&details_key(1).for_profile("work")),
+            Lookup::Fresh(_)
+        ));
+    }
+
+    #[test]
+    fn test_invalidate() {
+        let mut cache = ResponseCache::default();
+        let search: Page<Anime> = serde_json::from_value(serde_json::json!({
+            "data": [{ "node": { "id": 1, "title": "Cowboy Bebop" } }],
+            "paging": {}
+        }))
+        .unwrap();
+        let search_key = CacheKey::new(Endpoint::AnimeSearch, "q=bebop");
+        cache.insert(search_key.clone(), search);
+        cache.insert(details_key(1), anime(1));
+        cache.insert(details_key(2), anime(2));
+
+        // manga ids don't clash with anime ids
+        cache.invalidate(Item::Manga(1));
+        assert_eq!(cache.len(), 3);
+
+        cache.invalidate(Item::Anime(1));
+        assert!(matches!(
+            cache.get::<Page<Anime>>(&search_key),
+            Lookup::Miss
+        ));
+        assert!(matches!(cache.get::<Anime>(&details_key(1)), Lookup::Miss));
+        assert!(matches!(
+            cache.get::<Anime>(&details_key(2)),
+            Lookup::Fresh(_)
+        ));
+    }
+}
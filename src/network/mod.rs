@@ -0,0 +1,1050 @@
+/// Cache of api responses
+pub mod cache;
+
+use crate::{
+    api::{self, model::*},
+    auth::{self, store::CredentialStore, OAuth},
+    config::{AppConfig, AuthConfig, Profile},
+    cover::{self, Cover, CoverCache},
+    search::{filter::Filterable, SearchQuery, SearchScope, Searches},
+};
+use cache::{CacheKey, Cacheable, Endpoint, Item, Lookup, ResponseCache};
+use eyre::{eyre, WrapErr};
+use futures::{
+    future::{self, AbortHandle, Either},
+    StreamExt, TryStreamExt,
+};
+use serde::de::DeserializeOwned;
+use std::{
+    collections::HashMap,
+    future::Future,
+    mem::{self, Discriminant},
+    sync::{Arc, Mutex},
+};
+use tokio::sync::{
+    mpsc::{UnboundedReceiver, UnboundedSender},
+    RwLock,
+};
+
+/// Entries requested at a time when filtering search results
+const FILTER_PAGE_SIZE: u64 = 100;
+/// How far into the results a filtered search looks for matches
+const MAX_FILTERED_ENTRIES: usize = 1000;
+/// The most MAL sends of a user list at a time
+pub const USER_LIST_PAGE_SIZE: u64 = 1000;
+/// How many entries of a batch are changed at once
+const BATCH_CONCURRENCY: usize = 4;
+
+/// Tells the responses to dispatched events apart
+pub type RequestId = u64;
+
+#[derive(Debug)]
+pub enum IoEvent {
+    GetSearchResults(String, SearchScope),
+    GetAnimeSearchResults(String),
+    GetMangaSearchResults(String),
+    GetAnime(u64),
+    GetAnimeRanking(AnimeRankingType),
+    GetSeasonalAnime(String),
+    GetSuggestedAnime(String),
+    UpdateAnimeListStatus(u64, api::UpdateUserAnimeListStatusQuery),
+    DeleteAnimeListStatus(u64),
+    GetAnimeList(String),
+    GetManga(u64),
+    GetMangaRanking(MangaRankingType),
+    UpdateMangaListStatus(u64, api::UpdateUserMangaStatus),
+    DeleteMangaListStatus(u64),
+    /// Changes the list status of each anime, deleting it if `None`
+    UpdateAnimeListBatch(Vec<(u64, Option<api::UpdateUserAnimeListStatusQuery>)>),
+    /// Changes the list status of each manga, deleting it if `None`
+    UpdateMangaListBatch(Vec<(u64, Option<api::UpdateUserMangaStatus>)>),
+    GetMangaList(String),
+    GetUserInfo(String),
+    /// Downloads the cover at the url
+    GetCover(String),
+    /// Log in with another profile's stored token
+    SwitchProfile(String),
+}
+
+impl IoEvent {
+    /// Whether only the newest event of this kind matters, so dispatching one
+    /// cancels the one in flight
+    pub fn supersedes_previous(&self) -> bool {
+        matches!(
+            self,
+            IoEvent::GetSearchResults(..)
+                | IoEvent::GetAnimeSearchResults(_)
+                | IoEvent::GetMangaSearchResults(_)
+                | IoEvent::GetCover(_)
+                | IoEvent::SwitchProfile(_)
+        )
+    }
+
+    /// Identifies the kind of event, regardless of its arguments
+    pub fn kind(&self) -> Discriminant<IoEvent> {
+        mem::discriminant(self)
+    }
+}
+
+/// What an `IoEvent` came back with, sent to the ui thread and applied to the `App` there
+pub enum IoResponse {
+    AnimeSearchResults(Page<Anime>),
+    MangaSearchResults(Page<Manga>),
+    /// Top anime matches for the query being typed
+    AnimeSuggestions(String, Page<Anime>),
+    /// Top manga matches for the query being typed
+    MangaSuggestions(String, Page<Manga>),
+    AnimeDetails(Anime),
+    MangaDetails(Manga),
+    AnimeRanking(Ranking<RankingAnimePair>),
+    MangaRanking(Ranking<RankingMangaPair>),
+    /// The anime's list status was updated, or removed if `None`
+    AnimeListStatus(u64, Option<UserAnimeListStatus>),
+    /// The manga's list status was updated, or removed if `None`
+    MangaListStatus(u64, Option<UserMangaListStatus>),
+    /// Every entry of a user's anime list
+    AnimeList(Page<Anime>),
+    /// Every entry of a user's manga list
+    MangaList(Page<Manga>),
+    UserInfo(UserInfo),
+    Cover(Cover),
+    ProfileSwitched {
+        name: String,
+        app_config: AppConfig,
+        user: UserInfo,
+        searches: Searches,
+    },
+    Error(String),
+    /// An entry of a batch was changed, or couldn't be for the reason given
+    BatchItem(u64, Option<String>),
+    /// The event has been handled, whether it succeeded or not
+    Done,
+}
+
+/// State shared by all the requests in flight
+struct Session {
+    /// Name of the profile the token is for
+    profile: String,
+    oauth: OAuth,
+    /// Where the token came from, a refreshed one is saved back to it
+    store: Box<dyn CredentialStore>,
+    nsfw: bool,
+}
+
+#[derive(Clone)]
+pub struct Network {
+    session: Arc<RwLock<Session>>,
+    cache: Arc<Mutex<ResponseCache>>,
+    covers: CoverCache,
+    large_search_limit: u64,
+    small_search_limit: u64,
+    response_tx: UnboundedSender<(RequestId, IoResponse)>,
+}
+
+impl Network {
+    pub fn new(
+        profile: String,
+        oauth: OAuth,
+        store: Box<dyn CredentialStore>,
+        app_config: &AppConfig,
+        response_tx: UnboundedSender<(RequestId, IoResponse)>,
+    ) -> Self {
+        Self {
+            session: Arc::new(RwLock::new(Session {
+                profile,
+                oauth,
+                store,
+                nsfw: app_config.nsfw,
+            })),
+            cache: Arc::new(Mutex::new(ResponseCache::default())),
+            covers: CoverCache::in_cache_dir(),
+            large_search_limit: 20,
+            small_search_limit: 4,
+            response_tx,
+        }
+    }
+
+    /// Runs every event as its own task until the app hangs up, so a slow request
+    /// doesn't hold up the others
+    pub async fn run(self, mut io_rx: UnboundedReceiver<(RequestId, IoEvent)>) {
+        let mut in_flight: HashMap<Discriminant<IoEvent>, AbortHandle> = HashMap::new();
+
+        while let Some((id, io_event)) = io_rx.recv().await {
+            let kind = io_event.kind();
+            let supersedes_previous = io_event.supersedes_previous();
+
+            let network = self.clone();
+            let (task, abort_handle) =
+                future::abortable(async move { network.handle_network_event(id, io_event).await });
+
+            if supersedes_previous {
+                if let Some(previous) = in_flight.insert(kind, abort_handle) {
+                    previous.abort();
+                }
+            }
+            tokio::spawn(task);
+        }
+    }
+
+    pub async fn handle_network_event(&self, id: RequestId, io_event: IoEvent) {
+        match io_event {
+            IoEvent::GetSearchResults(q, scope) => {
+                self.get_search_results(id, q, scope).await;
+            }
+            IoEvent::GetAnimeSearchResults(q) => {
+                self.get_anime_suggestions(id, q).await;
+            }
+            IoEvent::GetMangaSearchResults(q) => {
+                self.get_manga_suggestions(id, q).await;
+            }
+            IoEvent::GetAnime(anime_id) => {
+                self.get_anime(id, anime_id).await;
+            }
+            IoEvent::GetAnimeRanking(ranking_type) => {
+                self.get_anime_ranking(id, ranking_type).await;
+            }
+            // IoEvent::GetSeasonalAnime(String) => {}
+            // IoEvent::GetSuggestedAnime(String) => {}
+            IoEvent::UpdateAnimeListStatus(anime_id, update) => {
+                self.update_anime_list_status(id, anime_id, update).await;
+            }
+            IoEvent::DeleteAnimeListStatus(anime_id) => {
+                self.delete_anime_list_status(id, anime_id).await;
+            }
+            IoEvent::GetAnimeList(user) => {
+                self.get_anime_list(id, user).await;
+            }
+            IoEvent::GetManga(manga_id) => {
+                self.get_manga(id, manga_id).await;
+            }
+            IoEvent::GetMangaRanking(ranking_type) => {
+                self.get_manga_ranking(id, ranking_type).await;
+            }
+            IoEvent::UpdateMangaListStatus(manga_id, update) => {
+                self.update_manga_list_status(id, manga_id, update).await;
+            }
+            IoEvent::DeleteMangaListStatus(manga_id) => {
+                self.delete_manga_list_status(id, manga_id).await;
+            }
+            IoEvent::UpdateAnimeListBatch(changes) => {
+                self.update_anime_list_batch(id, changes).await;
+            }
+            IoEvent::UpdateMangaListBatch(changes) => {
+                self.update_manga_list_batch(id, changes).await;
+            }
+            IoEvent::GetMangaList(user) => {
+                self.get_manga_list(id, user).await;
+            }
+            IoEvent::GetUserInfo(user) => {
+                self.get_user_info(id, user).await;
+            }
+            IoEvent::GetCover(url) => {
+                self.get_cover(id, url).await;
+            }
+            IoEvent::SwitchProfile(name) => {
+                self.switch_profile(id, name).await;
+            }
+            _ => (),
+        }
+
+        self.respond(id, IoResponse::Done);
+    }
+
+    /// Sends a result to the ui, which is gone if the app is quitting
+    fn respond(&self, id: RequestId, response: IoResponse) {
+        let _ = self.response_tx.send((id, response));
+    }
+
+    fn handle_error<E: std::fmt::Display>(&self, id: RequestId, error: E) {
+        self.respond(id, IoResponse::Error(error.to_string()));
+    }
+
+    /// The token to make requests with, refreshed first if it has expired
+    async fn oauth(&self) -> eyre::Result<OAuth> {
+        let mut session = self.session.write().await;
+        if session.oauth.token().is_some_and(|token| token.expired()) {
+            session.oauth.refresh_async().await?;
+            // the refresh token was replaced too, the stored one doesn't work anymore
+            session
+                .store
+                .save(&session.oauth)
+                .wrap_err("The refreshed token could not be saved")?;
+        }
+        Ok(session.oauth.clone())
+    }
+
+    /// Responds with the cached response for `key`, fetching it if it's missing or stale.
+    /// A stale response is shown until the new one comes back.
+    async fn fetch_cached<T, F, R>(&self, id: RequestId, key: CacheKey, fetch: F, into_response: R)
+    where
+        T: Cacheable,
+        F: Future<Output = api::ApiResult<T>>,
+        R: Fn(T) -> IoResponse,
+    {
+        // a request of the profile switched from stores what it gets under that one
+        let key = key.for_profile(&self.session.read().await.profile);
+        let lookup = self.cache.lock().unwrap().get::<T>(&key);
+        let has_stale = match lookup {
+            Lookup::Fresh(value) => return self.respond(id, into_response(value)),
+            Lookup::Stale(value) => {
+                self.respond(id, into_response(value));
+                true
+            }
+            Lookup::Miss => false,
+        };
+
+        match fetch.await {
+            Ok(value) => {
+                self.cache.lock().unwrap().insert(key, value.clone());
+                self.respond(id, into_response(value));
+            }
+            // keep showing the stale response rather than an error
+            Err(_) if has_stale => {}
+            Err(e) => self.handle_error(id, e),
+        }
+    }
+
+    async fn get_user_info(&self, id: RequestId, user: String) {
+        let oauth = match self.oauth().await {
+            Ok(oauth) => oauth,
+            Err(e) => return self.handle_error(id, e),
+        };
+        let key = CacheKey::new(Endpoint::UserInfo, &user);
+        let query = user_info_query();
+        let fetch = api::get_my_user_information(user, &query, &oauth);
+        self.fetch_cached(id, key, fetch, IoResponse::UserInfo)
+            .await;
+    }
+
+    async fn get_cover(&self, id: RequestId, url: String) {
+        // the details are there without it, so a failure isn't worth an error screen
+        if let Ok(cover) = cover::load(&self.covers, &url).await {
+            self.respond(id, IoResponse::Cover(cover));
+        }
+    }
+
+    async fn get_anime_suggestions(&self, id: RequestId, q: String) {
+        let oauth = match self.oauth().await {
+            Ok(oauth) => oauth,
+            Err(e) => return self.handle_error(id, e),
+        };
+        let query = api::GetAnimeListQuery {
+            q: q.clone(),
+            limit: self.small_search_limit,
+            offset: 0,
+            nsfw: self.session.read().await.nsfw,
+            fields: anime_search_fields().to_query(),
+        };
+        let key = CacheKey::new(Endpoint::AnimeSearch, query_string(&query));
+        let fetch = api::get_anime_list(&query, &oauth);
+        self.fetch_cached(id, key, fetch, |results| {
+            IoResponse::AnimeSuggestions(q.clone(), results)
+        })
+        .await;
+    }
+
+    async fn get_manga_suggestions(&self, id: RequestId, q: String) {
+        let oauth = match self.oauth().await {
+            Ok(oauth) => oauth,
+            Err(e) => return self.handle_error(id, e),
+        };
+        let query = api::GetMangaListQuery {
+            q: q.clone(),
+            limit: self.small_search_limit,
+            offset: 0,
+            nsfw: self.session.read().await.nsfw,
+            fields: manga_search_fields().to_query(),
+        };
+        let key = CacheKey::new(Endpoint::MangaSearch, query_string(&query));
+        let fetch = api::get_manga_list(&query, &oauth);
+        self.fetch_cached(id, key, fetch, |results| {
+            IoResponse::MangaSuggestions(q.clone(), results)
+        })
+        .await;
+    }
+
+    async fn get_anime(&self, id: RequestId, anime_id: u64) {
+        let oauth = match self.oauth().await {
+            Ok(oauth) => oauth,
+            Err(e) => return self.handle_error(id, e),
+        };
+        let query = api::GetAnimeDetailQuery {
+            fields: anime_details_fields().to_query(),
+            nsfw: self.session.read().await.nsfw,
+        };
+        let key = CacheKey::new(
+            Endpoint::AnimeDetails,
+            format!("{}?{}", anime_id, query_string(&query)),
+        );
+        let fetch = api::get_anime_details(anime_id, &query, &oauth);
+        self.fetch_cached(id, key, fetch, IoResponse::AnimeDetails)
+            .await;
+    }
+
+    /// Fetches the whole list, so it can be filtered and sorted without asking again.
+    /// It's the user's own data and changes under us, so it isn't cached.
+    async fn get_anime_list(&self, id: RequestId, user: String) {
+        let oauth = match self.oauth().await {
+            Ok(oauth) => oauth,
+            Err(e) => return self.handle_error(id, e),
+        };
+        let query = api::GetUserAnimeListQuery {
+            fields: anime_table_fields()
+                .nested(AnimeField::MyListStatus, anime_list_status_fields())
+                .to_query(),
+            status: None,
+            sort: None,
+            limit: USER_LIST_PAGE_SIZE,
+            offset: 0,
+            nsfw: self.session.read().await.nsfw,
+        };
+        let list = async {
+            let first = api::get_user_anime_list(&user, &query, &oauth).await?;
+            let data = api::paginate(first, None, &oauth).try_collect().await?;
+            api::ApiResult::Ok(single_page(data))
+        };
+        match list.await {
+            Ok(list) => self.respond(id, IoResponse::AnimeList(list)),
+            Err(e) => self.handle_error(id, e),
+        }
+    }
+
+    /// Fetches the whole list, so it can be filtered and sorted without asking again.
+    /// It's the user's own data and changes under us, so it isn't cached.
+    async fn get_manga_list(&self, id: RequestId, user: String) {
+        let oauth = match self.oauth().await {
+            Ok(oauth) => oauth,
+            Err(e) => return self.handle_error(id, e),
+        };
+        let query = api::GetUserMangaListQuery {
+            fields: manga_table_fields()
+                .nested(MangaField::MyListStatus, manga_list_status_fields())
+                .to_query(),
+            status: None,
+            sort: None,
+            limit: USER_LIST_PAGE_SIZE,
+            offset: 0,
+            nsfw: self.session.read().await.nsfw,
+        };
+        let list = async {
+            let first = api::get_user_manga_list(&user, &query, &oauth).await?;
+            let data = api::paginate(first, None, &oauth).try_collect().await?;
+            api::ApiResult::Ok(single_page(data))
+        };
+        match list.await {
+            Ok(list) => self.respond(id, IoResponse::MangaList(list)),
+            Err(e) => self.handle_error(id, e),
+        }
+    }
+
+    async fn get_anime_ranking(&self, id: RequestId, ranking_type: AnimeRankingType) {
+        let oauth = match self.oauth().await {
+            Ok(oauth) => oauth,
+            Err(e) => return self.handle_error(id, e),
+        };
+        let query = api::GetAnimeRankingQuery {
+            ranking_type,
+            limit: self.large_search_limit,
+            offset: 0,
+            nsfw: self.session.read().await.nsfw,
+            fields: anime_table_fields().to_query(),
+        };
+        let key = CacheKey::new(Endpoint::AnimeRanking, query_string(&query));
+        let fetch = api::get_anime_ranking(&query, &oauth);
+        self.fetch_cached(id, key, fetch, IoResponse::AnimeRanking)
+            .await;
+    }
+
+    async fn update_anime_list_status(
+        &self,
+        id: RequestId,
+        anime_id: u64,
+        update: api::UpdateUserAnimeListStatusQuery,
+    ) {
+        let oauth = match self.oauth().await {
+            Ok(oauth) => oauth,
+            Err(e) => return self.handle_error(id, e),
+        };
+        match self
+            .change_anime_list_status(anime_id, Some(&update), &oauth)
+            .await
+        {
+            Ok(status) => self.respond(id, IoResponse::AnimeListStatus(anime_id, status)),
+            Err(e) => self.handle_error(id, e),
+        }
+    }
+
+    async fn delete_anime_list_status(&self, id: RequestId, anime_id: u64) {
+        let oauth = match self.oauth().await {
+            Ok(oauth) => oauth,
+            Err(e) => return self.handle_error(id, e),
+        };
+        match self.change_anime_list_status(anime_id, None, &oauth).await {
+            Ok(status) => self.respond(id, IoResponse::AnimeListStatus(anime_id, status)),
+            Err(e) => self.handle_error(id, e),
+        }
+    }
+
+    /// Changes every anime at once, a few at a time. One failing doesn't stop the
+    /// others, it's reported with the entry instead of as an error.
+    async fn update_anime_list_batch(
+        &self,
+        id: RequestId,
+        changes: Vec<(u64, Option<api::UpdateUserAnimeListStatusQuery>)>,
+    ) {
+        let oauth = match self.oauth().await {
+            Ok(oauth) => oauth,
+            Err(e) => {
+                let error = e.to_string();
+                for (anime_id, _) in changes {
+                    self.respond(id, IoResponse::BatchItem(anime_id, Some(error.clone())));
+                }
+                return;
+            }
+        };
+        let oauth = &oauth;
+        futures::stream::iter(changes)
+            .for_each_concurrent(BATCH_CONCURRENCY, |(anime_id, update)| async move {
+                match self
+                    .change_anime_list_status(anime_id, update.as_ref(), oauth)
+                    .await
+                {
+                    Ok(status) => {
+                        self.respond(id, IoResponse::AnimeListStatus(anime_id, status));
+                        self.respond(id, IoResponse::BatchItem(anime_id, None));
+                    }
+                    Err(e) => {
+                        self.respond(id, IoResponse::BatchItem(anime_id, Some(e.to_string())))
+                    }
+                }
+            })
+            .await;
+    }
+
+    /// Updates the anime's list status, or deletes it if there's no `update`
+    async fn change_anime_list_status(
+        &self,
+        anime_id: u64,
+        update: Option<&api::UpdateUserAnimeListStatusQuery>,
+        oauth: &OAuth,
+    ) -> api::ApiResult<Option<UserAnimeListStatus>> {
+        let status = match update {
+            Some(update) => Some(api::update_anime_list_status(anime_id, update, oauth).await?),
+            None => {
+                api::delete_anime_from_list(anime_id, oauth).await?;
+                None
+            }
+        };
+        self.cache.lock().unwrap().invalidate(Item::Anime(anime_id));
+        Ok(status)
+    }
+
+    async fn get_manga(&self, id: RequestId, manga_id: u64) {
+        let oauth = match self.oauth().await {
+            Ok(oauth) => oauth,
+            Err(e) => return self.handle_error(id, e),
+        };
+        let query = api::GetMangaDetailQuery {
+            fields: manga_details_fields().to_query(),
+            nsfw: self.session.read().await.nsfw,
+        };
+        let key = CacheKey::new(
+            Endpoint::MangaDetails,
+            format!("{}?{}", manga_id, query_string(&query)),
+        );
+        let fetch = api::get_manga_details(manga_id, &query, &oauth);
+        self.fetch_cached(id, key, fetch, IoResponse::MangaDetails)
+            .await;
+    }
+
+    async fn get_manga_ranking(&self, id: RequestId, ranking_type: MangaRankingType) {
+        let oauth = match self.oauth().await {
+            Ok(oauth) => oauth,
+            Err(e) => return self.handle_error(id, e),
+        };
+        let query = api::GetMangaRankingQuery {
+            ranking_type,
+            limit: self.large_search_limit,
+            offset: 0,
+            nsfw: self.session.read().await.nsfw,
+            fields: manga_table_fields().to_query(),
+        };
+        let key = CacheKey::new(Endpoint::MangaRanking, query_string(&query));
+        let fetch = api::get_manga_ranking(&query, &oauth);
+        self.fetch_cached(id, key, fetch, IoResponse::MangaRanking)
+            .await;
+    }
+
+    async fn update_manga_list_status(
+        &self,
+        id: RequestId,
+        manga_id: u64,
+        update: api::UpdateUserMangaStatus,
+    ) {
+        let oauth = match self.oauth().await {
+            Ok(oauth) => oauth,
+            Err(e) => return self.handle_error(id, e),
+        };
+        match self
+            .change_manga_list_status(manga_id, Some(&update), &oauth)
+            .await
+        {
+            Ok(status) => self.respond(id, IoResponse::MangaListStatus(manga_id, status)),
+            Err(e) => self.handle_error(id, e),
+        }
+    }
+
+    async fn delete_manga_list_status(&self, id: RequestId, manga_id: u64) {
+        let oauth = match self.oauth().await {
+            Ok(oauth) => oauth,
+            Err(e) => return self.handle_error(id, e),
+        };
+        match self.change_manga_list_status(manga_id, None, &oauth).await {
+            Ok(status) => self.respond(id, IoResponse::MangaListStatus(manga_id, status)),
+            Err(e) => self.handle_error(id, e),
+        }
+    }
+
+    /// Changes every manga at once, a few at a time. One failing doesn't stop the
+    /// others, it's reported with the entry instead of as an error.
+    async fn update_manga_list_batch(
+        &self,
+        id: RequestId,
+        changes: Vec<(u64, Option<api::UpdateUserMangaStatus>)>,
+    ) {
+        let oauth = match self.oauth().await {
+            Ok(oauth) => oauth,
+            Err(e) => {
+                let error = e.to_string();
+                for (manga_id, _) in changes {
+                    self.respond(id, IoResponse::BatchItem(manga_id, Some(error.clone())));
+                }
+                return;
+            }
+        };
+        let oauth = &oauth;
+        futures::stream::iter(changes)
+            .for_each_concurrent(BATCH_CONCURRENCY, |(manga_id, update)| async move {
+                match self
+                    .change_manga_list_status(manga_id, update.as_ref(), oauth)
+                    .await
+                {
+                    Ok(status) => {
+                        self.respond(id, IoResponse::MangaListStatus(manga_id, status));
+                        self.respond(id, IoResponse::BatchItem(manga_id, None));
+                    }
+                    Err(e) => {
+                        self.respond(id, IoResponse::BatchItem(manga_id, Some(e.to_string())))
+                    }
+                }
+            })
+            .await;
+    }
+
+    /// Updates the manga's list status, or deletes it if there's no `update`
+    async fn change_manga_list_status(
+        &self,
+        manga_id: u64,
+        update: Option<&api::UpdateUserMangaStatus>,
+        oauth: &OAuth,
+    ) -> api::ApiResult<Option<UserMangaListStatus>> {
+        let status = match update {
+            Some(update) => Some(api::update_manga_list_status(manga_id, update, oauth).await?),
+            None => {
+                api::delete_manga_from_list(manga_id, oauth).await?;
+                None
+            }
+        };
+        self.cache.lock().unwrap().invalidate(Item::Manga(manga_id));
+        Ok(status)
+    }
+
+    async fn switch_profile(&self, id: RequestId, name: String) {
+        match load_profile(&name).await {
+            Ok((oauth, store, app_config, user, searches)) => {
+                // the cached list statuses are the other account's
+                self.cache.lock().unwrap().clear();
+                *self.session.write().await = Session {
+                    profile: name.clone(),
+                    oauth,
+                    store,
+                    nsfw: app_config.nsfw,
+                };
+                self.respond(
+                    id,
+                    IoResponse::ProfileSwitched {
+                        name,
+                        app_config,
+                        user,
+                        searches,
+                    },
+                );
+            }
+            Err(e) => self.handle_error(id, e),
+        }
+    }
+
+    /// Searches anime and manga at the same time, or just one of them
+    async fn get_search_results(&self, id: RequestId, q: String, scope: SearchScope) {
+        let search = match SearchQuery::parse(&q) {
+            Ok(search) => search,
+            Err(e) => return self.handle_error(id, e),
+        };
+        let oauth = match self.oauth().await {
+            Ok(oauth) => oauth,
+            Err(e) => return self.handle_error(id, e),
+        };
+        if search.has_filters() {
+            return self
+                .get_filtered_search_results(id, &q, &search, scope, &oauth)
+                .await;
+        }
+        let nsfw = self.session.read().await.nsfw;
+
+        let anime_query = api::GetAnimeListQuery {
+            q: q.clone(),
+            limit: self.large_search_limit,
+            offset: 0,
+            nsfw,
+            fields: anime_table_fields().to_query(),
+        };
+
+        let manga_query = api::GetMangaListQuery {
+            q,
+            limit: self.large_search_limit,
+            offset: 0,
+            nsfw,
+            fields: manga_table_fields().to_query(),
+        };
+
+        let anime = async {
+            if scope.includes_anime() {
+                let key = CacheKey::new(Endpoint::AnimeSearch, query_string(&anime_query));
+                let fetch = api::get_anime_list(&anime_query, &oauth);
+                self.fetch_cached(id, key, fetch, IoResponse::AnimeSearchResults)
+                    .await;
+            }
+        };
+        let manga = async {
+            if scope.includes_manga() {
+                let key = CacheKey::new(Endpoint::MangaSearch, query_string(&manga_query));
+                let fetch = api::get_manga_list(&manga_query, &oauth);
+                self.fetch_cached(id, key, fetch, IoResponse::MangaSearchResults)
+                    .await;
+            }
+        };
+        tokio::join!(anime, manga);
+    }
+
+    /// Searches with filter terms, which MAL can't do so they're applied to the results here
+    async fn get_filtered_search_results(
+        &self,
+        id: RequestId,
+        q: &str,
+        search: &SearchQuery,
+        scope: SearchScope,
+        oauth: &OAuth,
+    ) {
+        let nsfw = self.session.read().await.nsfw;
+        // the filtered results depend on every term, so the whole input is the key
+        let key = format!("{}&nsfw={}", q, nsfw);
+        let anime = async {
+            if scope.includes_anime() {
+                let key = CacheKey::new(Endpoint::AnimeSearch, &key);
+                let fetch = self.filter_anime(search, oauth, nsfw);
+                self.fetch_cached(id, key, fetch, IoResponse::AnimeSearchResults)
+                    .await;
+            }
+        };
+        let manga = async {
+            if scope.includes_manga() {
+                let key = CacheKey::new(Endpoint::MangaSearch, &key);
+                let fetch = self.filter_manga(search, oauth, nsfw);
+                self.fetch_cached(id, key, fetch, IoResponse::MangaSearchResults)
+                    .await;
+            }
+        };
+        tokio::join!(anime, manga);
+    }
+
+    /// Pages through the title search, or the overall ranking if there's no title to
+    /// search for, until enough anime pass the filters
+    async fn filter_anime(
+        &self,
+        search: &SearchQuery,
+        oauth: &OAuth,
+        nsfw: bool,
+    ) -> api::ApiResult<Page<Anime>> {
+        let fields = anime_filter_fields().to_query();
+        let first = match search.title_query() {
+            Some(q) => {
+                let query = api::GetAnimeListQuery {
+                    q: q.to_string(),
+                    limit: FILTER_PAGE_SIZE,
+                    offset: 0,
+                    nsfw,
+                    fields,
+                };
+                Either::Right(api::get_anime_list(&query, oauth).await?)
+            }
+            None => {
+                let query = api::GetAnimeRankingQuery {
+                    ranking_type: AnimeRankingType::All,
+                    limit: FILTER_PAGE_SIZE,
+                    offset: 0,
+                    nsfw,
+                    fields,
+                };
+                Either::Left(api::get_anime_ranking(&query, oauth).await?)
+            }
+        };
+        self.filter_pages(search, first, |pair: RankingAnimePair| pair.node, oauth)
+            .await
+    }
+
+    /// Pages through the title search, or the overall ranking if there's no title to
+    /// search for, until enough manga pass the filters
+    async fn filter_manga(
+        &self,
+        search: &SearchQuery,
+        oauth: &OAuth,
+        nsfw: bool,
+    ) -> api::ApiResult<Page<Manga>> {
+        let fields = manga_filter_fields().to_query();
+        let first = match search.title_query() {
+            Some(q) => {
+                let query = api::GetMangaListQuery {
+                    q: q.to_string(),
+                    limit: FILTER_PAGE_SIZE,
+                    offset: 0,
+                    nsfw,
+                    fields,
+                };
+                Either::Right(api::get_manga_list(&query, oauth).await?)
+            }
+            None => {
+                let query = api::GetMangaRankingQuery {
+                    ranking_type: MangaRankingType::All,
+                    limit: FILTER_PAGE_SIZE,
+                    offset: 0,
+                    nsfw,
+                    fields,
+                };
+                Either::Left(api::get_manga_ranking(&query, oauth).await?)
+            }
+        };
+        self.filter_pages(search, first, |pair: RankingMangaPair| pair.node, oauth)
+            .await
+    }
+
+    /// Pages on from the `first` page of a ranking or a title search until enough
+    /// entries pass the filters, `ranked` takes the entry out of a ranking's pair
+    async fn filter_pages<R, T>(
+        &self,
+        search: &SearchQuery,
+        first: Either<Ranking<R>, Page<T>>,
+        ranked: fn(R) -> T,
+        oauth: &OAuth,
+    ) -> api::ApiResult<Page<T>>
+    where
+        R: DeserializeOwned + Clone + std::fmt::Debug,
+        T: DeserializeOwned + Clone + std::fmt::Debug + Filterable,
+    {
+        let entries = match first {
+            Either::Left(ranking) => Either::Left(
+                api::paginate(ranking, Some(MAX_FILTERED_ENTRIES), oauth)
+                    .map_ok(move |pair| Node { node: ranked(pair) }),
+            ),
+            Either::Right(page) => {
+                Either::Right(api::paginate(page, Some(MAX_FILTERED_ENTRIES), oauth))
+            }
+        };
+
+        let data = entries
+            .try_filter(|entry| future::ready(search.matches(&entry.node)))
+            .take(self.large_search_limit as usize)
+            .try_collect()
+            .await?;
+        Ok(single_page(data))
+    }
+}
+
+/// Entries collected from several pages, which can't be paged through any further
+fn single_page<T: Clone + std::fmt::Debug>(data: Vec<Node<T>>) -> Page<T> {
+    Page {
+        data,
+        paging: Paging {
+            previous: None,
+            next: None,
+        },
+    }
+}
+
+/// Loads a profile's token and settings without prompting, and fetches who it belongs to
+async fn load_profile(
+    name: &str,
+) -> eyre::Result<(
+    OAuth,
+    Box<dyn CredentialStore>,
+    AppConfig,
+    UserInfo,
+    Searches,
+)> {
+    let profile = Profile::new(name)?;
+    let not_logged_in = || {
+        eyre!(
+            "Profile `{}` is not logged in, run `mal --profile {}` first",
+            name,
+            name
+        )
+    };
+
+    let auth_config = AuthConfig::read(&profile)?.ok_or_else(not_logged_in)?;
+    let store = auth::open_store(&auth_config, &profile, false)?;
+    let oauth = auth::load_auth_async(store.as_ref())
+        .await?
+        .ok_or_else(not_logged_in)?;
+    let app_config = AppConfig::load(&profile)?;
+    let searches = Searches::load(&profile)?;
+    let user = api::get_my_user_information("@me", &user_info_query(), &oauth).await?;
+    Ok((oauth, store, app_config, user, searches))
+}
+
+fn user_info_query() -> api::GetUserInformationQuery {
+    api::GetUserInformationQuery {
+        fields: Some(ALL_USER_FIELDS.to_string()),
+    }
+}
+
+/// The query an api call was made with, to tell cached responses apart
+fn query_string<Q: serde::Serialize>(query: &Q) -> String {
+    serde_urlencoded::to_string(query).unwrap_or_default()
+}
+
+/// Fields shown in the anime suggestions
+fn anime_search_fields() -> Fields {
+    Fields::new().fields(&[
+        AnimeField::Id,
+        AnimeField::Title,
+        AnimeField::AlternativeTitles,
+        AnimeField::MediaType,
+        AnimeField::NumEpisodes,
+        AnimeField::StartDate,
+        AnimeField::Mean,
+    ])
+}
+
+/// Fields shown in the manga suggestions
+fn manga_search_fields() -> Fields {
+    Fields::new().fields(&[
+        MangaField::Id,
+        MangaField::Title,
+        MangaField::AlternativeTitles,
+        MangaField::MediaType,
+        MangaField::NumVolumes,
+        MangaField::StartDate,
+        MangaField::Mean,
+    ])
+}
+
+/// Fields the anime details view shows, and the cover
+fn anime_details_fields() -> Fields {
+    anime_search_fields().fields(&[
+        AnimeField::MainPicture,
+        AnimeField::Status,
+        AnimeField::Rank,
+        AnimeField::Popularity,
+        AnimeField::Genres,
+        AnimeField::Synopsis,
+        AnimeField::MyListStatus,
+    ])
+}
+
+/// Fields the manga details view shows, and the cover
+fn manga_details_fields() -> Fields {
+    manga_search_fields().fields(&[
+        MangaField::MainPicture,
+        MangaField::NumChapters,
+        MangaField::Status,
+        MangaField::Rank,
+        MangaField::Popularity,
+        MangaField::Genres,
+        MangaField::Synopsis,
+        MangaField::MyListStatus,
+    ])
+}
+
+/// Fields any of the columns of the anime tables can show, and the airing schedule and
+/// backlog estimates
+fn anime_table_fields() -> Fields {
+    anime_search_fields().fields(&[
+        AnimeField::Status,
+        AnimeField::Broadcast,
+        AnimeField::AverageEpisodeDuration,
+        AnimeField::Rank,
+        AnimeField::Popularity,
+        AnimeField::NumListUsers,
+        AnimeField::MyListStatus,
+    ])
+}
+
+/// Fields any of the columns of the manga tables can show
+fn manga_table_fields() -> Fields {
+    manga_search_fields().fields(&[
+        MangaField::Status,
+        MangaField::NumChapters,
+        MangaField::Rank,
+        MangaField::Popularity,
+        MangaField::NumListUsers,
+        MangaField::MyListStatus,
+    ])
+}
+
+/// The list status of each entry of the user's anime list, with the tags and priority
+/// the batch actions build on
+fn anime_list_status_fields() -> Fields {
+    Fields::new().fields(&[
+        ListStatusField::Status,
+        ListStatusField::Score,
+        ListStatusField::NumEpisodesWatched,
+        ListStatusField::IsRewatching,
+        ListStatusField::StartDate,
+        ListStatusField::FinishDate,
+        ListStatusField::Priority,
+        ListStatusField::Tags,
+        ListStatusField::UpdatedAt,
+    ])
+}
+
+/// The list status of each entry of the user's manga list, with the tags and priority
+/// the batch actions build on
+fn manga_list_status_fields() -> Fields {
+    Fields::new().fields(&[
+        ListStatusField::Status,
+        ListStatusField::Score,
+        ListStatusField::NumVolumesRead,
+        ListStatusField::NumChaptersRead,
+        ListStatusField::IsRereading,
+        ListStatusField::StartDate,
+        ListStatusField::FinishDate,
+        ListStatusField::Priority,
+        ListStatusField::Tags,
+        ListStatusField::UpdatedAt,
+    ])
+}
+
+/// Fields the search filters look at, on top of the ones shown
+fn anime_filter_fields() -> Fields {
+    anime_table_fields().fields(&[
+        AnimeField::Genres,
+        AnimeField::Studios,
+        AnimeField::Rating,
+        AnimeField::Source,
+        AnimeField::StartSeason,
+    ])
+}
+
+/// Fields the search filters look at, on top of the ones shown
+fn manga_filter_fields() -> Fields {
+    manga_table_fields().fields(&[MangaField::Genres])
+}
@@ -0,0 +1,85 @@
+use super::ics;
+use crate::api::{self, model::*, ApiResult, GetUserAnimeListQuery};
+use crate::auth::OAuth;
+use crate::cli::ScheduleCommand;
+use crate::config::AppConfig;
+use crate::network::USER_LIST_PAGE_SIZE;
+use chrono::Utc;
+use eyre::{eyre, WrapErr};
+use futures::TryStreamExt;
+use std::io::Write;
+use std::path::Path;
+
+/// Runs a schedule subcommand for the logged in user
+pub async fn run(
+    command: ScheduleCommand,
+    app_config: &AppConfig,
+    auth: &OAuth,
+) -> eyre::Result<()> {
+    match command {
+        ScheduleCommand::Export { ics: false, .. } => {
+            Err(eyre!("Pick the format to export, only --ics is supported"))
+        }
+        ScheduleCommand::Export { ics: true, output } => {
+            export(app_config, auth, output.as_deref()).await
+        }
+    }
+}
+
+/// Writes the calendar of the watching and plan to watch lists
+async fn export(app_config: &AppConfig, auth: &OAuth, output: Option<&Path>) -> eyre::Result<()> {
+    let mut anime = list(UserStatus::Watching, app_config.nsfw, auth)
+        .await
+        .wrap_err("Could not fetch the watching list")?;
+    anime.extend(
+        list(UserStatus::PlanToWatch, app_config.nsfw, auth)
+            .await
+            .wrap_err("Could not fetch the plan to watch list")?,
+    );
+
+    let calendar = ics::calendar(&anime, app_config.title_language, Utc::now());
+    match output {
+        Some(path) => std::fs::write(path, &calendar)
+            .wrap_err_with(|| format!("Could not write {}", path.display()))?,
+        None => std::io::stdout().write_all(calendar.as_bytes())?,
+    }
+
+    // the calendar may be going to stdout, so this goes to stderr
+    let scheduled = anime
+        .iter()
+        .filter(|anime| ics::is_scheduled(anime))
+        .count();
+    eprintln!(
+        "Exported {} shows, left out {} that finished airing or have no broadcast time, start or end",
+        scheduled,
+        anime.len() - scheduled
+    );
+    Ok(())
+}
+
+/// The whole list of anime with `status`, with what the calendar needs of each
+async fn list(status: UserStatus, nsfw: bool, auth: &OAuth) -> ApiResult<Vec<Anime>> {
+    let query = GetUserAnimeListQuery {
+        fields: Fields::new()
+            .fields(&[
+                AnimeField::Id,
+                AnimeField::Title,
+                AnimeField::AlternativeTitles,
+                AnimeField::StartDate,
+                AnimeField::EndDate,
+                AnimeField::Status,
+                AnimeField::NumEpisodes,
+                AnimeField::Broadcast,
+                AnimeField::AverageEpisodeDuration,
+            ])
+            .to_query(),
+        status: Some(status),
+        sort: None,
+        limit: USER_LIST_PAGE_SIZE,
+        offset: 0,
+        nsfw,
+    };
+    let first = api::get_user_anime_list("@me", &query, auth).await?;
+    let nodes: Vec<Node<Anime>> = api::paginate(first, None, auth).try_collect().await?;
+    Ok(nodes.into_iter().map(|node| node.node).collect())
+}
@@ -0,0 +1,269 @@
+//! The airing schedule as an iCalendar file (RFC 5545), one weekly recurring event a
+//! show. Events stay in Japan's time with a `TZID`, so calendar apps move them to
+//! their own time zone.
+
+use super::{first_episode, to_date, BROADCAST_TIME_ZONE};
+use crate::api::model::{Anime, AnimeStatus};
+use crate::config::TitleLanguage;
+use chrono::{DateTime, NaiveDateTime, TimeZone, Utc};
+
+const PRODUCT_ID: &str = "-//mal-cli//Airing schedule//EN";
+/// How long an event is when MAL doesn't know the episodes' length
+const DEFAULT_EPISODE_MINUTES: u64 = 24;
+/// RFC 5545 wants lines no longer than this many bytes
+const MAX_LINE_LENGTH: usize = 75;
+
+/// The calendar of the shows' broadcasts. Shows that finished airing, or whose
+/// broadcasts can't be placed or would never stop, are left out.
+pub fn calendar<'a, I>(anime: I, language: TitleLanguage, now: DateTime<Utc>) -> String
+where
+    I: IntoIterator<Item = &'a Anime>,
+{
+    let mut lines = vec![
+        "BEGIN:VCALENDAR".to_string(),
+        "VERSION:2.0".to_string(),
+        format!("PRODID:{}", PRODUCT_ID),
+        "CALSCALE:GREGORIAN".to_string(),
+        "X-WR-CALNAME:MAL airing schedule".to_string(),
+    ];
+    lines.extend(time_zone());
+    for anime in anime {
+        lines.extend(event(anime, language, now).into_iter().flatten());
+    }
+    lines.push("END:VCALENDAR".to_string());
+
+    let mut calendar = String::new();
+    for line in lines {
+        calendar.push_str(&fold(&line));
+        calendar.push_str("\r\n");
+    }
+    calendar
+}
+
+/// Whether `anime` gets an event in the calendar
+pub fn is_scheduled(anime: &Anime) -> bool {
+    recurrence(anime).is_some()
+}
+
+/// Japan's time zone, which has had no daylight saving time since 1951
+fn time_zone() -> Vec<String> {
+    [
+        "BEGIN:VTIMEZONE",
+        &format!("TZID:{}", BROADCAST_TIME_ZONE.name()),
+        "BEGIN:STANDARD",
+        "DTSTART:19700101T000000",
+        "TZOFFSETFROM:+0900",
+        "TZOFFSETTO:+0900",
+        "TZNAME:JST",
+        "END:STANDARD",
+        "END:VTIMEZONE",
+    ]
+    .iter()
+    .map(|line| line.to_string())
+    .collect()
+}
+
+/// When the show's weekly event starts and its `RRULE`: from the first episode, as
+/// many times as there are episodes, or until the end date if MAL doesn't know how
+/// many. `None` for a show without a start date or an end, which can't be placed or
+/// would repeat forever.
+fn recurrence(anime: &Anime) -> Option<(DateTime<Utc>, String)> {
+    if anime.status == Some(AnimeStatus::FinishedAiring) {
+        return None;
+    }
+    let start = first_episode(anime)?;
+    // the event starts at the first episode, so it repeats once for every episode
+    let first_number = 1;
+    let count = anime
+        .num_episodes
+        .filter(|&episodes| episodes >= first_number)
+        .map(|episodes| episodes - (first_number - 1));
+
+    let end = match (count, last_day(anime)) {
+        (Some(count), _) => format!("COUNT={}", count),
+        (None, Some(until)) => format!("UNTIL={}", utc(until)),
+        (None, None) => return None,
+    };
+    Some((start, format!("RRULE:FREQ=WEEKLY;{}", end)))
+}
+
+/// A weekly event for the show's broadcasts, see `recurrence`
+fn event(anime: &Anime, language: TitleLanguage, now: DateTime<Utc>) -> Option<Vec<String>> {
+    let (start, rule) = recurrence(anime)?;
+    let minutes = anime
+        .average_episode_duration
+        .map(|seconds| seconds / 60)
+        .filter(|&minutes| minutes > 0)
+        .unwrap_or(DEFAULT_EPISODE_MINUTES);
+    let title = language.title(&anime.title, anime.alternative_titles.as_ref());
+
+    Some(vec![
+        "BEGIN:VEVENT".to_string(),
+        format!("UID:anime-{}@mal-cli", anime.id),
+        format!("DTSTAMP:{}", utc(now)),
+        format!(
+            "DTSTART;TZID={}:{}",
+            BROADCAST_TIME_ZONE.name(),
+            local(start.with_timezone(&BROADCAST_TIME_ZONE).naive_local())
+        ),
+        format!("DURATION:PT{}M", minutes),
+        rule,
+        format!("SUMMARY:{}", escape(title)),
+        format!("URL:https://myanimelist.net/anime/{}", anime.id),
+        "END:VEVENT".to_string(),
+    ])
+}
+
+/// The end of the show's last day in Japan
+fn last_day(anime: &Anime) -> Option<DateTime<Utc>> {
+    let end = to_date(anime.end_date?)?.and_hms_opt(23, 59, 59)?;
+    Some(
+        BROADCAST_TIME_ZONE
+            .from_local_datetime(&end)
+            .single()?
+            .with_timezone(&Utc),
+    )
+}
+
+fn utc(at: DateTime<Utc>) -> String {
+    at.format("%Y%m%dT%H%M%SZ").to_string()
+}
+
+fn local(at: NaiveDateTime) -> String {
+    at.format("%Y%m%dT%H%M%S").to_string()
+}
+
+/// `text` as a TEXT value, with the characters that mean something in one escaped
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '\\' | ';' | ',' => {
+                escaped.push('\\');
+                escaped.push(c);
+            }
+            '\n' => escaped.push_str("\\n"),
+            '\r' => {}
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// Splits `line` into lines of at most 75 bytes, each one after the first starting
+/// with a space, without cutting a character in two
+fn fold(line: &str) -> String {
+    let mut folded = String::with_capacity(line.len());
+    let mut length = 0;
+    for c in line.chars() {
+        if length + c.len_utf8() > MAX_LINE_LENGTH {
+            folded.push_str("\r\n ");
+            length = 1;
+        }
+        folded.push(c);
+        length += c.len_utf8();
+    }
+    folded
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn anime(value: serde_json::Value) -> Anime {
+        let mut anime = serde_json::json!({
+            "id": 52991,
+            "title": "Sousou no Frieren",
+            "alternative_titles": { "en": "Frieren: Beyond Journey's End" },
+            "start_date": "2023-09-29",
+            "status": "currently_airing",
+            "broadcast": { "day_of_the_week": "friday", "start_time": "23:00" },
+            "average_episode_duration": 1470
+        });
+        anime
+            .as_object_mut()
+            .unwrap()
+            .extend(value.as_object().unwrap().clone());
+        serde_json::from_value(anime).unwrap()
+    }
+
+    fn now() -> DateTime<Utc> {
+        "2023-10-20T12:00:00Z".parse().unwrap()
+    }
+
+    #[test]
+    fn test_calendar() {
+        let shows = [anime(serde_json::json!({ "num_episodes": 28 }))];
+        let calendar = calendar(&shows, TitleLanguage::English, now());
+        let lines: Vec<&str> = calendar.split("\r\n").collect();
+        assert_eq!(lines.first(), Some(&"BEGIN:VCALENDAR"));
+        assert_eq!(lines[lines.len() - 2], "END:VCALENDAR");
+        assert_eq!(lines.last(), Some(&""));
+
+        for line in [
+            "TZID:Asia/Tokyo",
+            "UID:anime-52991@mal-cli",
+            "DTSTAMP:20231020T120000Z",
+            "DTSTART;TZID=Asia/Tokyo:20230929T230000",
+            "DURATION:PT24M",
+            "RRULE:FREQ=WEEKLY;COUNT=28",
+            "SUMMARY:Frieren: Beyond Journey's End",
+        ] {
+            assert!(lines.contains(&line), "no {:?} in {}", line, calendar);
+        }
+    }
+
+    #[test]
+    fn test_recurrence() {
+        let rule = |anime: &Anime| {
+            event(anime, TitleLanguage::Romaji, now())
+                .unwrap()
+                .into_iter()
+                .find(|line| line.starts_with("RRULE"))
+                .unwrap()
+        };
+
+        // the end of the 22nd of March in Japan
+        let ended = anime(serde_json::json!({ "end_date": "2024-03-22" }));
+        assert_eq!(rule(&ended), "RRULE:FREQ=WEEKLY;UNTIL=20240322T145959Z");
+        // the episode count wins over the end date
+        let counted = anime(serde_json::json!({ "num_episodes": 12, "end_date": "2024-03-22" }));
+        assert_eq!(rule(&counted), "RRULE:FREQ=WEEKLY;COUNT=12");
+
+        // no end to the recurrence, or no telling which episode airs when
+        for show in [
+            anime(serde_json::json!({ "num_episodes": 0 })),
+            anime(serde_json::json!({ "num_episodes": null })),
+            anime(serde_json::json!({ "num_episodes": 12, "start_date": null })),
+        ] {
+            assert!(event(&show, TitleLanguage::Romaji, now()).is_none());
+            assert!(!is_scheduled(&show));
+        }
+
+        let finished = anime(serde_json::json!({ "status": "finished_airing" }));
+        assert!(event(&finished, TitleLanguage::Romaji, now()).is_none());
+        let no_broadcast = anime(serde_json::json!({ "broadcast": null }));
+        assert!(event(&no_broadcast, TitleLanguage::Romaji, now()).is_none());
+    }
+
+    #[test]
+    fn test_escape() {
+        assert_eq!(
+            escape("Re:Zero, Season 2; Part 1\\2"),
+            "Re:Zero\\, Season 2\\; Part 1\\\\2"
+        );
+        assert_eq!(escape("a\r\nb"), "a\\nb");
+    }
+
+    #[test]
+    fn test_fold() {
+        let line = format!("SUMMARY:{}", "葬送のフリーレン".repeat(4));
+        let folded = fold(&line);
+        let lines: Vec<&str> = folded.split("\r\n").collect();
+        assert_eq!(lines.len(), 2);
+        assert!(lines.iter().all(|line| line.len() <= MAX_LINE_LENGTH));
+        assert!(lines[1].starts_with(' '));
+        assert_eq!(lines.concat().replacen(' ', "", 1), line);
+        assert_eq!(fold("VERSION:2.0"), "VERSION:2.0");
+    }
+}
@@ -0,0 +1,425 @@
+//! When the shows being watched air. MAL gives each show a weekly broadcast slot in
+//! Japan's time, which is moved to the user's time zone here.
+
+pub mod commands;
+pub mod ics;
+
+use crate::api::model::{Anime, AnimeStatus, DateTimeWrapper, DateWrapper, UserWatchStatus};
+use chrono::{
+    DateTime, Datelike, Duration, Local, NaiveDate, NaiveDateTime, NaiveTime, TimeZone, Utc,
+    Weekday,
+};
+use chrono_tz::Tz;
+use serde::{Deserialize, Deserializer};
+
+/// MAL's broadcast times are Japan's
+pub const BROADCAST_TIME_ZONE: Tz = chrono_tz::Asia::Tokyo;
+
+pub const WEEKDAYS: [Weekday; 7] = [
+    Weekday::Mon,
+    Weekday::Tue,
+    Weekday::Wed,
+    Weekday::Thu,
+    Weekday::Fri,
+    Weekday::Sat,
+    Weekday::Sun,
+];
+
+/// Which time zone the schedule is shown in, set with `time_zone` in `config.yml`:
+/// `local`, `profile` for the MAL account's, or a name like `Europe/Paris`
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum TimeZoneSetting {
+    #[default]
+    Local,
+    Profile,
+    Named(Tz),
+}
+
+impl TimeZoneSetting {
+    /// The zone to show times in, the local one if the profile's isn't known
+    pub fn zone(self, profile_time_zone: Option<&str>) -> Zone {
+        match self {
+            TimeZoneSetting::Local => Zone::Local,
+            TimeZoneSetting::Profile => profile_time_zone
+                .and_then(|name| name.parse().ok())
+                .map_or(Zone::Local, Zone::Named),
+            TimeZoneSetting::Named(tz) => Zone::Named(tz),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for TimeZoneSetting {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        use serde::de::Error;
+        let name = String::deserialize(deserializer)?;
+        match name.as_str() {
+            "local" => Ok(TimeZoneSetting::Local),
+            "profile" => Ok(TimeZoneSetting::Profile),
+            _ => name
+                .parse()
+                .map(TimeZoneSetting::Named)
+                .map_err(|_| D::Error::custom(format!("unknown time zone {:?}", name))),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Zone {
+    Local,
+    Named(Tz),
+}
+
+impl Zone {
+    /// The date and time it is in the zone at `at`
+    pub fn localize(self, at: DateTime<Utc>) -> NaiveDateTime {
+        match self {
+            Zone::Local => at.with_timezone(&Local).naive_local(),
+            Zone::Named(tz) => at.with_timezone(&tz).naive_local(),
+        }
+    }
+}
+
+/// When a show airs every week, in Japan
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Slot {
+    pub weekday: Weekday,
+    pub time: NaiveTime,
+}
+
+impl Slot {
+    /// The show's slot, `None` if MAL doesn't know the day or time
+    pub fn of(anime: &Anime) -> Option<Self> {
+        let broadcast = anime.broadcast.as_ref()?;
+        let start_time = broadcast.start_time.as_ref()?.time;
+        Some(Self {
+            weekday: broadcast.day_of_the_week.parse().ok()?,
+            time: NaiveTime::from_hms_opt(
+                u32::from(start_time.hour()),
+                u32::from(start_time.minute()),
+                0,
+            )?,
+        })
+    }
+
+    /// The first time the slot comes on `date` or after it, `date` being in Japan
+    pub fn on_or_after(self, date: NaiveDate) -> DateTime<Utc> {
+        let days = (7 + self.weekday.num_days_from_monday() as i64
+            - date.weekday().num_days_from_monday() as i64)
+            % 7;
+        let at = (date + Duration::days(days)).and_time(self.time);
+        // Japan has no daylight saving time, so every time there is unique
+        BROADCAST_TIME_ZONE
+            .from_local_datetime(&at)
+            .unwrap()
+            .with_timezone(&Utc)
+    }
+}
+
+/// An episode and when it airs
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Episode {
+    /// `None` if the show's start date isn't known
+    pub number: Option<u64>,
+    pub airs_at: DateTime<Utc>,
+}
+
+/// When the first episode aired or airs: the start date, or the broadcast day after it
+pub fn first_episode(anime: &Anime) -> Option<DateTime<Utc>> {
+    let slot = Slot::of(anime)?;
+    Some(slot.on_or_after(to_date(anime.start_date?)?))
+}
+
+/// The next episode to air after `now`, one a week from the first one. `None` once the
+/// last one aired.
+pub fn next_episode(anime: &Anime, now: DateTime<Utc>) -> Option<Episode> {
+    let slot = Slot::of(anime)?;
+    let first = match first_episode(anime) {
+        Some(first) => first,
+        None => {
+            let today = now.with_timezone(&BROADCAST_TIME_ZONE).date_naive();
+            let mut airs_at = slot.on_or_after(today);
+            if airs_at <= now {
+                airs_at += Duration::weeks(1);
+            }
+            return Some(Episode {
+                number: None,
+                airs_at,
+            });
+        }
+    };
+
+    let number = if now < first {
+        1
+    } else {
+        (now - first).num_weeks() as u64 + 2
+    };
+    if anime
+        .num_episodes
+        .is_some_and(|episodes| episodes > 0 && number > episodes)
+    {
+        return None;
+    }
+    Some(Episode {
+        number: Some(number),
+        airs_at: first + Duration::weeks(number as i64 - 1),
+    })
+}
+
+/// A show in the weekly calendar
+#[derive(Clone, Debug)]
+pub struct Airing<'a> {
+    pub anime: &'a Anime,
+    pub next: Episode,
+    /// When the next episode airs in the calendar's time zone
+    pub local: NaiveDateTime,
+}
+
+/// The airing shows being watched, by the day of the week the next episode airs in
+/// `zone`, Monday first and the earliest first in each day
+pub fn week<'a, I>(anime: I, now: DateTime<Utc>, zone: Zone) -> [Vec<Airing<'a>>; 7]
+where
+    I: IntoIterator<Item = &'a Anime>,
+{
+    let mut days: [Vec<Airing>; 7] = Default::default();
+    for anime in anime.into_iter().filter(|anime| is_watching_airing(anime)) {
+        if let Some(next) = next_episode(anime, now) {
+            let local = zone.localize(next.airs_at);
+            let day = local.weekday().num_days_from_monday() as usize;
+            days[day].push(Airing { anime, next, local });
+        }
+    }
+    for day in &mut days {
+        day.sort_by_key(|airing| airing.local.time());
+    }
+    days
+}
+
+/// Whether `anime` is airing and on the user's watching list
+fn is_watching_airing(anime: &Anime) -> bool {
+    let watching = anime
+        .my_list_status
+        .as_ref()
+        .is_some_and(|status| status.status == UserWatchStatus::Watching);
+    watching && anime.status == Some(AnimeStatus::CurrentlyAiring)
+}
+
+/// How many episodes aired by `now`, one a week from the first one. `None` if when the
+/// first one aired isn't known.
+pub fn aired_episodes(anime: &Anime, now: DateTime<Utc>) -> Option<u64> {
+    let first = first_episode(anime)?;
+    if now < first {
+        return Some(0);
+    }
+    let aired = (now - first).num_weeks() as u64 + 1;
+    Some(match anime.num_episodes {
+        Some(episodes) if episodes > 0 => aired.min(episodes),
+        _ => aired,
+    })
+}
+
+/// A show being watched with episodes out that the user hasn't seen
+#[derive(Clone, Debug)]
+pub struct CatchUp<'a> {
+    pub anime: &'a Anime,
+    pub watched: u64,
+    pub aired: u64,
+    /// How long ago the user's list entry last changed
+    pub idle: Duration,
+    /// Whether it's been long enough for the show to look dropped
+    pub stalled: bool,
+}
+
+impl CatchUp<'_> {
+    pub fn behind(&self) -> u64 {
+        self.aired - self.watched
+    }
+}
+
+/// The airing shows being watched that the user is behind on, the furthest behind
+/// first. Those with no progress for `stalled_after` are flagged.
+pub fn catch_up<'a, I>(anime: I, now: DateTime<Utc>, stalled_after: Duration) -> Vec<CatchUp<'a>>
+where
+    I: IntoIterator<Item = &'a Anime>,
+{
+    let mut shows: Vec<CatchUp> = anime
+        .into_iter()
+        .filter(|anime| is_watching_airing(anime))
+        .filter_map(|anime| {
+            let status = anime.my_list_status.as_ref()?;
+            let aired = aired_episodes(anime, now)?;
+            if aired <= status.num_episodes_watched {
+                return None;
+            }
+            let idle = now - to_utc(&status.updated_at)?;
+            Some(CatchUp {
+                anime,
+                watched: status.num_episodes_watched,
+                aired,
+                idle,
+                stalled: idle >= stalled_after,
+            })
+        })
+        .collect();
+    shows.sort_by_key(|show| std::cmp::Reverse(show.behind()));
+    shows
+}
+
+/// A date MAL sent, `None` if it's only a year or a month
+pub fn to_date(date: DateWrapper) -> Option<NaiveDate> {
+    NaiveDate::from_ymd_opt(date.year, u32::from(date.month?), u32::from(date.day?))
+}
+
+/// A time MAL sent, which it does in UTC
+pub fn to_utc(at: &DateTimeWrapper) -> Option<DateTime<Utc>> {
+    let timestamp = at.datetime.assume_utc().timestamp();
+    Utc.timestamp_opt(timestamp, 0).single()
+}
+
+/// How long until something, like `2d 5h`, `3h 12m` or `12m`
+pub fn countdown(left: Duration) -> String {
+    let minutes = left.num_minutes().max(0);
+    let (days, hours, minutes) = (minutes / (24 * 60), minutes / 60 % 24, minutes % 60);
+    if days > 0 {
+        format!("{}d {}h", days, hours)
+    } else if hours > 0 {
+        format!("{}h {}m", hours, minutes)
+    } else {
+        format!("{}m", minutes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn anime(start_date: Option<&str>, episodes: u64) -> Anime {
+        serde_json::from_value(serde_json::json!({
+            "id": 1,
+            "title": "Frieren",
+            "start_date": start_date,
+            "num_episodes": episodes,
+            "status": "currently_airing",
+            "broadcast": { "day_of_the_week": "saturday", "start_time": "01:30" },
+            "my_list_status": {
+                "status": "watching",
+                "score": 0,
+                "num_episodes_watched": 3,
+                "is_rewatching": false,
+                "updated_at": "2023-10-20T12:00:00+00:00"
+            }
+        }))
+        .unwrap()
+    }
+
+    fn utc(at: &str) -> DateTime<Utc> {
+        at.parse().unwrap()
+    }
+
+    #[test]
+    fn test_next_episode() {
+        // Friday the 29th, and the first episode is the Saturday after at 01:30 in Japan
+        let anime = anime(Some("2023-09-29"), 12);
+        let first = utc("2023-09-29T16:30:00Z");
+        assert_eq!(first_episode(&anime), Some(first));
+
+        let next = next_episode(&anime, utc("2023-09-01T00:00:00Z")).unwrap();
+        assert_eq!(next.number, Some(1));
+        let next = next_episode(&anime, first).unwrap();
+        assert_eq!(
+            (next.number, next.airs_at),
+            (Some(2), first + Duration::weeks(1))
+        );
+        let next = next_episode(&anime, utc("2023-10-20T12:00:00Z")).unwrap();
+        assert_eq!(
+            (next.number, next.airs_at),
+            (Some(4), utc("2023-10-20T16:30:00Z"))
+        );
+
+        // over once the last one aired
+        assert!(next_episode(&anime, first + Duration::weeks(11)).is_none());
+    }
+
+    #[test]
+    fn test_next_episode_without_start_date() {
+        let anime = anime(None, 0);
+        let next = next_episode(&anime, utc("2023-10-20T17:00:00Z")).unwrap();
+        assert_eq!(next.number, None);
+        assert_eq!(next.airs_at, utc("2023-10-27T16:30:00Z"));
+    }
+
+    #[test]
+    fn test_week() {
+        let shows = [anime(Some("2023-09-29"), 28)];
+        let now = utc("2023-10-20T12:00:00Z");
+
+        // Saturday 01:30 in Japan is Friday evening in Paris
+        let paris = Zone::Named(chrono_tz::Europe::Paris);
+        let days = week(&shows, now, paris);
+        assert_eq!(days[4].len(), 1);
+        assert_eq!(
+            days[4][0].local.time(),
+            NaiveTime::from_hms_opt(18, 30, 0).unwrap()
+        );
+
+        let tokyo = Zone::Named(BROADCAST_TIME_ZONE);
+        assert_eq!(week(&shows, now, tokyo)[5].len(), 1);
+    }
+
+    #[test]
+    fn test_time_zone_setting() {
+        let setting: TimeZoneSetting = serde_yaml::from_str("Europe/Paris").unwrap();
+        assert_eq!(setting, TimeZoneSetting::Named(chrono_tz::Europe::Paris));
+        assert!(serde_yaml::from_str::<TimeZoneSetting>("Mars/Olympus").is_err());
+
+        let profile: TimeZoneSetting = serde_yaml::from_str("profile").unwrap();
+        assert_eq!(
+            profile.zone(Some("America/New_York")),
+            Zone::Named(chrono_tz::America::New_York)
+        );
+        assert_eq!(profile.zone(None), Zone::Local);
+    }
+
+    #[test]
+    fn test_catch_up() {
+        let now = utc("2023-10-20T17:00:00Z");
+        // 4 episodes aired, 3 were watched on the 20th
+        let shows = [anime(Some("2023-09-29"), 28), anime(Some("2023-10-13"), 12)];
+        assert_eq!(aired_episodes(&shows[0], now), Some(4));
+        assert_eq!(aired_episodes(&shows[1], now), Some(2));
+        assert_eq!(
+            aired_episodes(&shows[0], utc("2023-09-01T00:00:00Z")),
+            Some(0)
+        );
+        assert_eq!(
+            aired_episodes(&shows[0], utc("2024-09-01T00:00:00Z")),
+            Some(28)
+        );
+
+        let behind = catch_up(&shows, now, Duration::weeks(3));
+        let behind: Vec<(u64, bool)> = behind
+            .iter()
+            .map(|show| (show.behind(), show.stalled))
+            .collect();
+        assert_eq!(behind, vec![(1, false)]);
+
+        // no progress for a month
+        let later = utc("2023-11-20T17:00:00Z");
+        let behind = catch_up(&shows, later, Duration::weeks(3));
+        let behind: Vec<(u64, bool)> = behind
+            .iter()
+            .map(|show| (show.behind(), show.stalled))
+            .collect();
+        assert_eq!(behind, vec![(5, true), (3, true)]);
+    }
+
+    #[test]
+    fn test_countdown() {
+        assert_eq!(countdown(Duration::minutes(12)), "12m");
+        assert_eq!(countdown(Duration::minutes(3 * 60 + 12)), "3h 12m");
+        assert_eq!(countdown(Duration::hours(53)), "2d 5h");
+        assert_eq!(countdown(Duration::minutes(-5)), "0m");
+    }
+}
@@ -0,0 +1,521 @@
+//! The search box takes free text mixed with filter terms, e.g.
+//! `gundam type:movie year:2019..2021 score>8 genre:mecha -genre:ecchi`.
+//!
+//! The free text is sent to MAL, which only does title search, and the terms are
+//! checked against the results here.
+
+use super::MIN_QUERY_LENGTH;
+use crate::api::model::*;
+use std::fmt;
+
+/// A search box query split into the free text and filter terms
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct SearchQuery {
+    /// What's sent to the api as `q`
+    pub text: String,
+    pub terms: Vec<Term>,
+}
+
+/// A filter term, `-` in front negates it
+#[derive(Clone, Debug, PartialEq)]
+pub struct Term {
+    pub negated: bool,
+    pub condition: Condition,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Condition {
+    /// `field:value`
+    Text(TextField, String),
+    /// `field:a..b`, `field>a`, `field<=b` and so on
+    Number(NumberField, Bounds),
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum TextField {
+    Type,
+    Status,
+    Genre,
+    Studio,
+    Rating,
+    Source,
+    Season,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum NumberField {
+    Year,
+    Score,
+    Episodes,
+    Volumes,
+    Chapters,
+    Rank,
+    Popularity,
+    Members,
+}
+
+/// An inclusive or exclusive range, either end can be open
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Bounds {
+    pub min: Option<(f64, bool)>,
+    pub max: Option<(f64, bool)>,
+}
+
+impl Bounds {
+    pub fn contains(&self, value: f64) -> bool {
+        let above = match self.min {
+            Some((min, true)) => value >= min,
+            Some((min, false)) => value > min,
+            None => true,
+        };
+        let below = match self.max {
+            Some((max, true)) => value <= max,
+            Some((max, false)) => value < max,
+            None => true,
+        };
+        above && below
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub enum FilterError {
+    /// Represents a term like `score>high`
+    InvalidNumber(String),
+    /// Represents a term like `year:2021..2019` or `year:..`
+    InvalidRange(String),
+    /// Represents a term like `genre:`
+    MissingValue(String),
+}
+
+impl std::error::Error for FilterError {}
+
+impl fmt::Display for FilterError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            FilterError::InvalidNumber(ref term) => write!(f, "`{}` needs a number", term),
+            FilterError::InvalidRange(ref term) => write!(f, "`{}` is not a valid range", term),
+            FilterError::MissingValue(ref term) => write!(f, "`{}` needs a value", term),
+        }
+    }
+}
+
+impl TextField {
+    fn from_name(name: &str) -> Option<Self> {
+        Some(match name {
+            "type" => TextField::Type,
+            "status" => TextField::Status,
+            "genre" => TextField::Genre,
+            "studio" => TextField::Studio,
+            "rating" => TextField::Rating,
+            "source" => TextField::Source,
+            "season" => TextField::Season,
+            _ => return None,
+        })
+    }
+}
+
+impl NumberField {
+    fn from_name(name: &str) -> Option<Self> {
+        Some(match name {
+            "year" => NumberField::Year,
+            "score" => NumberField::Score,
+            "episodes" | "eps" => NumberField::Episodes,
+            "volumes" => NumberField::Volumes,
+            "chapters" => NumberField::Chapters,
+            "rank" => NumberField::Rank,
+            "popularity" => NumberField::Popularity,
+            "members" => NumberField::Members,
+            _ => return None,
+        })
+    }
+}
+
+impl SearchQuery {
+    pub fn parse(input: &str) -> Result<Self, FilterError> {
+        let mut text = vec![];
+        let mut terms = vec![];
+        for word in split_words(input) {
+            match parse_term(&word)? {
+                Some(term) => terms.push(term),
+                None => text.push(word),
+            }
+        }
+        Ok(Self {
+            text: text.join(" "),
+            terms,
+        })
+    }
+
+    /// The free text to search titles for, `None` if there's none or it's too short
+    /// for MAL to search
+    pub fn title_query(&self) -> Option<&str> {
+        if self.text.chars().count() >= MIN_QUERY_LENGTH {
+            Some(&self.text)
+        } else {
+            None
+        }
+    }
+
+    pub fn has_filters(&self) -> bool {
+        !self.terms.is_empty()
+    }
+
+    pub fn matches<T: Filterable>(&self, entry: &T) -> bool {
+        self.terms.iter().all(|term| term.matches(entry))
+    }
+}
+
+/// Splits on whitespace, keeping "quoted phrases" together (without the quotes)
+fn split_words(input: &str) -> Vec<String> {
+    let mut words = vec![];
+    let mut word = String::new();
+    let mut quoted = false;
+    for c in input.chars() {
+        match c {
+            '"' => quoted = !quoted,
+            c if c.is_whitespace() && !quoted => {
+                if !word.is_empty() {
+                    words.push(std::mem::take(&mut word));
+                }
+            }
+            c => word.push(c),
+        }
+    }
+    if !word.is_empty() {
+        words.push(word);
+    }
+    words
+}
+
+/// Returns `None` for free text, including `name:value` with a name we don't know,
+/// so titles like `Re:Zero` still work
+fn parse_term(word: &str) -> Result<Option<Term>, FilterError> {
+    let (negated, body) = match word.strip_prefix('-') {
+        Some(body) if !body.is_empty() => (true, body),
+        _ => (false, word),
+    };
+
+    let split_at = match body.find([':', '<', '>']) {
+        Some(index) => index,
+        None => return Ok(None),
+    };
+    let name = body[..split_at].to_lowercase();
+    let rest = &body[split_at..];
+
+    let condition = if let Some(field) = TextField::from_name(&name) {
+        match rest.strip_prefix(':') {
+            Some("") => return Err(FilterError::MissingValue(word.to_string())),
+            Some(value) => Condition::Text(field, value.to_string()),
+            None => return Ok(None),
+        }
+    } else if let Some(field) = NumberField::from_name(&name) {
+        Condition::Number(field, parse_bounds(word, rest)?)
+    } else {
+        return Ok(None);
+    };
+    Ok(Some(Term { negated, condition }))
+}
+
+/// Parses `:a`, `:a..b`, `:a..`, `:..b`, `>a`, `>=a`, `<b` and `<=b`
+fn parse_bounds(word: &str, rest: &str) -> Result<Bounds, FilterError> {
+    let number = |s: &str| {
+        s.parse::<f64>()
+            .map_err(|_| FilterError::InvalidNumber(word.to_string()))
+    };
+
+    let bounds = if let Some(value) = rest.strip_prefix(">=") {
+        Bounds {
+            min: Some((number(value)?, true)),
+            max: None,
+        }
+    } else if let Some(value) = rest.strip_prefix('>') {
+        Bounds {
+            min: Some((number(value)?, false)),
+            max: None,
+        }
+    } else if let Some(value) = rest.strip_prefix("<=") {
+        Bounds {
+            min: None,
+            max: Some((number(value)?, true)),
+        }
+    } else if let Some(value) = rest.strip_prefix('<') {
+        Bounds {
+            min: None,
+            max: Some((number(value)?, false)),
+        }
+    } else {
+        let value = rest.trim_start_matches(':');
+        if value.is_empty() {
+            return Err(FilterError::MissingValue(word.to_string()));
+        }
+        match value.split_once("..") {
+            Some(("", "")) => return Err(FilterError::InvalidRange(word.to_string())),
+            Some((min, max)) => {
+                let min = if min.is_empty() {
+                    None
+                } else {
+                    Some((number(min)?, true))
+                };
+                let max = if max.is_empty() {
+                    None
+                } else {
+                    Some((number(max)?, true))
+                };
+                if let (Some((min, _)), Some((max, _))) = (min, max) {
+                    if min > max {
+                        return Err(FilterError::InvalidRange(word.to_string()));
+                    }
+                }
+                Bounds { min, max }
+            }
+            None => {
+                let value = number(value)?;
+                Bounds {
+                    min: Some((value, true)),
+                    max: Some((value, true)),
+                }
+            }
+        }
+    };
+    Ok(bounds)
+}
+
+impl Term {
+    pub fn matches<T: Filterable>(&self, entry: &T) -> bool {
+        let matches = match &self.condition {
+            Condition::Text(field, value) => {
+                let value = normalize(value);
+                entry
+                    .text(*field)
+                    .iter()
+                    .any(|candidate| text_matches(*field, &normalize(candidate), &value))
+            }
+            Condition::Number(field, bounds) => {
+                entry.number(*field).is_some_and(|n| bounds.contains(n))
+            }
+        };
+        matches != self.negated
+    }
+}
+
+/// Lowercase without separators, so `slice_of_life` matches "Slice of Life"
+fn normalize(s: &str) -> String {
+    s.chars()
+        .filter(|c| !matches!(c, ' ' | '_' | '-'))
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
+fn text_matches(field: TextField, candidate: &str, value: &str) -> bool {
+    match field {
+        // studios are often written shorter, `studio:ghibli` for "Studio Ghibli"
+        TextField::Studio => candidate.contains(value),
+        // `status:airing` rather than `status:currently_airing`
+        TextField::Status => {
+            candidate == value
+                || match value {
+                    "airing" => candidate == "currentlyairing",
+                    "publishing" => candidate == "currentlypublishing",
+                    "finished" => candidate.starts_with("finished"),
+                    "upcoming" => candidate.starts_with("notyet"),
+                    _ => false,
+                }
+        }
+        _ => candidate == value,
+    }
+}
+
+/// The fields the filters look at
+pub trait Filterable {
+    /// All the values of a text field, e.g. every genre
+    fn text(&self, field: TextField) -> Vec<String>;
+
+    fn number(&self, field: NumberField) -> Option<f64>;
+}
+
+fn name<T>(value: &T) -> String
+where
+    for<'a> &'a T: Into<&'static str>,
+{
+    let name: &'static str = value.into();
+    name.to_string()
+}
+
+fn genre_names(genres: &Option<Vec<Genre>>) -> Vec<String> {
+    genres
+        .iter()
+        .flatten()
+        .map(|genre| genre.name.clone())
+        .collect()
+}
+
+impl Filterable for Anime {
+    fn text(&self, field: TextField) -> Vec<String> {
+        match field {
+            TextField::Type => self.media_type.iter().map(name).collect(),
+            TextField::Status => self.status.iter().map(name).collect(),
+            TextField::Genre => genre_names(&self.genres),
+            TextField::Studio => self
+                .studios
+                .iter()
+                .flatten()
+                .map(|studio| studio.name.clone())
+                .collect(),
+            TextField::Rating => self.rating.iter().map(name).collect(),
+            TextField::Source => self.source.iter().map(name).collect(),
+            TextField::Season => self
+                .start_season
+                .iter()
+                .map(|season| name(&season.season))
+                .collect(),
+        }
+    }
+
+    fn number(&self, field: NumberField) -> Option<f64> {
+        match field {
+            NumberField::Year => self
+                .start_date
+                .map(|date| date.year as f64)
+                .or_else(|| self.start_season.as_ref().map(|season| season.year as f64)),
+            NumberField::Score => self.mean,
+            NumberField::Episodes => self.num_episodes.filter(|&n| n > 0).map(|n| n as f64),
+            NumberField::Rank => self.rank.map(|n| n as f64),
+            NumberField::Popularity => self.popularity.map(|n| n as f64),
+            NumberField::Members => self.num_list_users.map(|n| n as f64),
+            NumberField::Volumes | NumberField::Chapters => None,
+        }
+    }
+}
+
+impl Filterable for Manga {
+    fn text(&self, field: TextField) -> Vec<String> {
+        match field {
+            TextField::Type => self.media_type.iter().map(name).collect(),
+            TextField::Status => self.status.iter().map(name).collect(),
+            TextField::Genre => genre_names(&self.genres),
+            TextField::Studio | TextField::Rating | TextField::Source | TextField::Season => {
+                vec![]
+            }
+        }
+    }
+
+    fn number(&self, field: NumberField) -> Option<f64> {
+        match field {
+            NumberField::Year => self.start_date.map(|date| date.year as f64),
+            NumberField::Score => self.mean,
+            NumberField::Volumes => self.num_volumes.filter(|&n| n > 0).map(|n| n as f64),
+            NumberField::Chapters => self.num_chapters.filter(|&n| n > 0).map(|n| n as f64),
+            NumberField::Rank => self.rank.map(|n| n as f64),
+            NumberField::Popularity => self.popularity.map(|n| n as f64),
+            NumberField::Members => self.num_list_users.map(|n| n as f64),
+            NumberField::Episodes => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn anime(json: serde_json::Value) -> Anime {
+        serde_json::from_value(json).unwrap()
+    }
+
+    #[test]
+    fn test_parse() {
+        let query = SearchQuery::parse(
+            "gundam type:movie year:2019..2021 score>8 genre:mecha status:airing -genre:ecchi",
+        )
+        .unwrap();
+        assert_eq!(query.text, "gundam");
+        assert_eq!(query.terms.len(), 6);
+        assert_eq!(
+            query.terms[1].condition,
+            Condition::Number(
+                NumberField::Year,
+                Bounds {
+                    min: Some((2019.0, true)),
+                    max: Some((2021.0, true)),
+                }
+            )
+        );
+        assert_eq!(
+            query.terms[2].condition,
+            Condition::Number(
+                NumberField::Score,
+                Bounds {
+                    min: Some((8.0, false)),
+                    max: None,
+                }
+            )
+        );
+        assert!(query.terms[5].negated);
+    }
+
+    #[test]
+    fn test_parse_free_text() {
+        // unknown names and comparisons on text fields stay in the title search
+        let query = SearchQuery::parse("Re:Zero -kara studio:\"kyoto animation\"").unwrap();
+        assert_eq!(query.text, "Re:Zero -kara");
+        assert_eq!(
+            query.terms[0].condition,
+            Condition::Text(TextField::Studio, "kyoto animation".to_string())
+        );
+        assert!(!SearchQuery::parse("cowboy bebop").unwrap().has_filters());
+    }
+
+    #[test]
+    fn test_title_query() {
+        // too short for MAL, the filters go through the ranking instead
+        assert_eq!(
+            SearchQuery::parse("re type:tv").unwrap().title_query(),
+            None
+        );
+        assert_eq!(SearchQuery::parse("type:tv").unwrap().title_query(), None);
+        assert_eq!(
+            SearchQuery::parse("rezero type:tv").unwrap().title_query(),
+            Some("rezero")
+        );
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert_eq!(
+            SearchQuery::parse("score>high"),
+            Err(FilterError::InvalidNumber("score>high".to_string()))
+        );
+        assert_eq!(
+            SearchQuery::parse("year:2021..2019"),
+            Err(FilterError::InvalidRange("year:2021..2019".to_string()))
+        );
+        assert_eq!(
+            SearchQuery::parse("genre:"),
+            Err(FilterError::MissingValue("genre:".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_matches() {
+        let movie = anime(serde_json::json!({
+            "id": 1,
+            "title": "Made in Abyss: Dawn of the Deep Soul",
+            "media_type": "movie",
+            "status": "finished_airing",
+            "start_date": "2020-01-17",
+            "mean": 8.7,
+            "genres": [{ "id": 1, "name": "Slice of Life" }, { "id": 2, "name": "Adventure" }],
+            "studios": [{ "id": 1, "name": "Kinema Citrus" }]
+        }));
+        let matches = |query: &str| SearchQuery::parse(query).unwrap().matches(&movie);
+
+        assert!(matches("type:movie year:2019..2021 score>8"));
+        assert!(matches("genre:slice_of_life studio:kinema status:finished"));
+        assert!(matches("-genre:ecchi score>=8.7 year:2020"));
+        assert!(!matches("type:tv"));
+        assert!(!matches("-genre:adventure"));
+        assert!(!matches("status:airing"));
+        // the movie's episode count isn't known, so it isn't in any range
+        assert!(!matches("episodes<3"));
+        assert!(matches("-episodes<3"));
+    }
+}
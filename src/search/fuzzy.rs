@@ -0,0 +1,141 @@
+//! Ranks strings by how well they match a typed pattern, fzf style: the pattern's
+//! characters have to appear in order, and matches that are closer together or at the
+//! start of words score higher.
+
+const MATCH: i64 = 16;
+const CONSECUTIVE_BONUS: i64 = 24;
+const WORD_START_BONUS: i64 = 16;
+const GAP_PENALTY: i64 = 1;
+
+/// Where and how well a pattern matched
+#[derive(Clone, Debug, PartialEq)]
+pub struct Match {
+    pub score: i64,
+    /// Indices of the matched chars in the candidate, to highlight them
+    pub positions: Vec<usize>,
+}
+
+/// How well `candidate` matches `pattern`, `None` if it doesn't. Case is ignored
+/// and spaces in the pattern are skipped.
+pub fn score(pattern: &str, candidate: &str) -> Option<i64> {
+    find(pattern, candidate).map(|found| found.score)
+}
+
+/// Finds the best way `pattern` matches `candidate`, `None` if it doesn't
+pub fn find(pattern: &str, candidate: &str) -> Option<Match> {
+    let pattern: Vec<char> = pattern
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .map(lowercase)
+        .collect();
+    // one char for each char, so the positions line up with the original
+    let candidate: Vec<char> = candidate.chars().map(lowercase).collect();
+    if pattern.is_empty() {
+        return Some(Match {
+            score: 0,
+            positions: vec![],
+        });
+    }
+
+    // best[i][j] is the best score with pattern[..=i] matched and pattern[i] at
+    // candidate[j], from[i][j] where pattern[i - 1] was matched for it
+    let mut best: Vec<Vec<Option<i64>>> = vec![vec![None; candidate.len()]; pattern.len()];
+    let mut from: Vec<Vec<usize>> = vec![vec![0; candidate.len()]; pattern.len()];
+    for (i, wanted) in pattern.iter().enumerate() {
+        for (j, c) in candidate.iter().enumerate() {
+            if c != wanted {
+                continue;
+            }
+            let mut score = MATCH;
+            if j == 0 || !candidate[j - 1].is_alphanumeric() {
+                score += WORD_START_BONUS;
+            }
+            let before = if i == 0 {
+                Some((-GAP_PENALTY * j as i64, 0))
+            } else {
+                (0..j)
+                    .filter_map(|k| {
+                        best[i - 1][k].map(|previous| {
+                            let previous = if k + 1 == j {
+                                previous + CONSECUTIVE_BONUS
+                            } else {
+                                previous - GAP_PENALTY * (j - k - 1) as i64
+                            };
+                            (previous, k)
+                        })
+                    })
+                    .max_by_key(|&(previous, _)| previous)
+            };
+            if let Some((before, k)) = before {
+                best[i][j] = Some(before + score);
+                from[i][j] = k;
+            }
+        }
+    }
+
+    let last = pattern.len() - 1;
+    let (mut j, score) = best[last]
+        .iter()
+        .enumerate()
+        .filter_map(|(j, score)| score.map(|score| (j, score)))
+        .max_by_key(|&(_, score)| score)?;
+    let mut positions = vec![j; pattern.len()];
+    for i in (1..pattern.len()).rev() {
+        j = from[i][j];
+        positions[i - 1] = j;
+    }
+    Some(Match { score, positions })
+}
+
+fn lowercase(c: char) -> char {
+    c.to_lowercase().next().unwrap_or(c)
+}
+
+/// Indices of the `candidates` matching `pattern`, best match first. Equal scores keep
+/// their order, and an empty pattern matches everything.
+pub fn rank<S: AsRef<str>>(pattern: &str, candidates: &[S]) -> Vec<usize> {
+    let mut matches: Vec<(usize, i64)> = candidates
+        .iter()
+        .enumerate()
+        .filter_map(|(index, candidate)| {
+            score(pattern, candidate.as_ref()).map(|score| (index, score))
+        })
+        .collect();
+    matches.sort_by_key(|&(_, score)| std::cmp::Reverse(score));
+    matches.into_iter().map(|(index, _)| index).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_score() {
+        assert!(score("bbp", "Cowboy Bebop").is_some());
+        assert!(score("COWBOY", "cowboy bebop").is_some());
+        assert!(score("pob", "Cowboy Bebop").is_none());
+        assert_eq!(score("", "anything"), Some(0));
+
+        // together and at word starts beats scattered
+        assert!(score("bebop", "Cowboy Bebop") > score("bebop", "be a big opening"));
+        assert!(score("fma", "Fullmetal Alchemist") > score("fma", "Information"));
+    }
+
+    #[test]
+    fn test_find() {
+        let found = find("bebop", "Cowboy Bebop").unwrap();
+        assert_eq!(found.positions, vec![7, 8, 9, 10, 11]);
+        let found = find("fm a", "Fullmetal Alchemist").unwrap();
+        assert_eq!(found.positions, vec![0, 4, 10]);
+        assert_eq!(find("", "Monster").unwrap().positions, Vec::<usize>::new());
+        assert!(find("zz", "Monster").is_none());
+    }
+
+    #[test]
+    fn test_rank() {
+        let history = ["type:movie genre:mecha", "monster", "made in abyss"];
+        assert_eq!(rank("m", &history), vec![1, 2, 0]);
+        assert_eq!(rank("abyss", &history), vec![2]);
+        assert_eq!(rank("", &history), vec![0, 1, 2]);
+    }
+}
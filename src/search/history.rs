@@ -0,0 +1,168 @@
+use crate::auth::store::atomic_write;
+use crate::config::{ConfigError, Profile};
+use serde::{Deserialize, Serialize};
+use std::{fs, path::PathBuf};
+
+/// How many past searches are remembered
+pub const MAX_HISTORY: usize = 200;
+
+/// Whether a search looks for anime, manga or both
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SearchScope {
+    #[default]
+    All,
+    Anime,
+    Manga,
+}
+
+impl SearchScope {
+    pub fn includes_anime(self) -> bool {
+        self != SearchScope::Manga
+    }
+
+    pub fn includes_manga(self) -> bool {
+        self != SearchScope::Anime
+    }
+
+    /// The scope after this one, to cycle through them
+    pub fn next(self) -> Self {
+        match self {
+            SearchScope::All => SearchScope::Anime,
+            SearchScope::Anime => SearchScope::Manga,
+            SearchScope::Manga => SearchScope::All,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            SearchScope::All => "All",
+            SearchScope::Anime => "Anime",
+            SearchScope::Manga => "Manga",
+        }
+    }
+}
+
+/// A search kept under a name to run again later
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct SavedSearch {
+    pub name: String,
+    /// Free text and filter terms, as typed in the search box
+    pub query: String,
+    #[serde(default)]
+    pub scope: SearchScope,
+}
+
+/// Past and saved searches of a profile, kept in its `searches.yml`
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Searches {
+    /// Oldest first
+    pub history: Vec<String>,
+    pub saved: Vec<SavedSearch>,
+    /// Where changes are written, nowhere if `None`
+    #[serde(skip)]
+    path: Option<PathBuf>,
+}
+
+impl Searches {
+    /// The searches of `profile`, empty if it has none yet
+    pub fn load(profile: &Profile) -> Result<Self, ConfigError> {
+        Self::load_from(profile.paths().searches_file_path)
+    }
+
+    pub fn load_from(path: PathBuf) -> Result<Self, ConfigError> {
+        let mut searches = if path.exists() {
+            let contents = fs::read_to_string(&path)?;
+            if contents.trim().is_empty() {
+                Self::default()
+            } else {
+                serde_yaml::from_str(&contents)?
+            }
+        } else {
+            Self::default()
+        };
+        searches.path = Some(path);
+        Ok(searches)
+    }
+
+    pub fn save(&self) -> Result<(), ConfigError> {
+        if let Some(path) = &self.path {
+            atomic_write(path, serde_yaml::to_string(self)?.as_bytes())?;
+        }
+        Ok(())
+    }
+
+    /// Remembers `query` as the newest search, moving it up if it was searched before
+    pub fn push_history(&mut self, query: &str) {
+        let query = query.trim();
+        if query.is_empty() {
+            return;
+        }
+        self.history.retain(|past| past != query);
+        self.history.push(query.to_string());
+        if self.history.len() > MAX_HISTORY {
+            let excess = self.history.len() - MAX_HISTORY;
+            self.history.drain(..excess);
+        }
+    }
+
+    /// Saves a search, replacing the one with the same name
+    pub fn save_search(&mut self, search: SavedSearch) {
+        match self
+            .saved
+            .iter_mut()
+            .find(|saved| saved.name == search.name)
+        {
+            Some(saved) => *saved = search,
+            None => self.saved.push(search),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_push_history() {
+        let mut searches = Searches::default();
+        searches.push_history("monster");
+        searches.push_history("  ");
+        searches.push_history("bebop");
+        searches.push_history(" monster ");
+        assert_eq!(searches.history, vec!["bebop", "monster"]);
+
+        for i in 0..MAX_HISTORY {
+            searches.push_history(&i.to_string());
+        }
+        assert_eq!(searches.history.len(), MAX_HISTORY);
+        assert_eq!(searches.history[0], "0");
+    }
+
+    #[test]
+    fn test_save_and_load() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("searches.yml");
+
+        let mut searches = Searches::load_from(path.clone()).unwrap();
+        assert_eq!(searches, Searches::load_from(path.clone()).unwrap());
+        searches.push_history("gundam");
+        searches.save_search(SavedSearch {
+            name: "mecha movies".to_string(),
+            query: "type:movie genre:mecha".to_string(),
+            scope: SearchScope::Anime,
+        });
+        searches.save_search(SavedSearch {
+            name: "mecha movies".to_string(),
+            query: "type:movie genre:mecha year>2015".to_string(),
+            scope: SearchScope::Anime,
+        });
+        searches.save().unwrap();
+
+        let loaded = Searches::load_from(path).unwrap();
+        assert_eq!(loaded, searches);
+        assert_eq!(loaded.saved.len(), 1);
+        assert_eq!(loaded.saved[0].query, "type:movie genre:mecha year>2015");
+    }
+}
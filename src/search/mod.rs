@@ -0,0 +1,12 @@
+/// Filter syntax for the search box
+pub mod filter;
+/// Fuzzy matching for the search popups
+pub mod fuzzy;
+/// Search history and saved searches
+pub mod history;
+
+pub use filter::SearchQuery;
+
+/// MAL rejects searches shorter than this
+pub const MIN_QUERY_LENGTH: usize = 3;
+pub use history::{SavedSearch, SearchScope, Searches};
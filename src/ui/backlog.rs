@@ -0,0 +1,100 @@
+use super::util::get_color;
+use crate::app::{ActiveBlock, App};
+use crate::backlog::{self, Item};
+use crate::schedule::{countdown, Zone};
+use chrono::{Duration, Utc};
+use tui::{
+    backend::Backend,
+    layout::{Constraint, Rect},
+    style::{Modifier, Style},
+    text::Span,
+    widgets::{Block, Borders, Row, Table, TableState},
+    Frame,
+};
+
+const HEADER: [&str; 4] = ["#", "Title", "Left", "Done by"];
+
+/// The shows being watched and planned in the order they'll be watched, with how
+/// long they'll take and when they'd be done
+pub fn draw_backlog<B>(f: &mut Frame<B>, app: &App, layout_chunk: Rect)
+where
+    B: Backend,
+{
+    let theme = app.app_config.theme;
+    let now = Utc::now();
+    let pace = app.backlog_pace(now);
+    let queue = app.backlog_queue();
+    let items = backlog::estimate(&queue, pace, now);
+    let zone = app.schedule_zone();
+
+    let rows: Vec<Vec<String>> = items
+        .iter()
+        .enumerate()
+        .map(|(index, item)| row(app, zone, index, item))
+        .collect();
+    let styled_rows = rows
+        .iter()
+        .map(|row| Row::StyledData(row.iter(), Style::default().fg(theme.text)));
+
+    let total = items
+        .iter()
+        .filter_map(|item| item.time_left)
+        .fold(Duration::zero(), |total, left| total + left);
+    let unknown = items.iter().filter(|item| item.time_left.is_none()).count();
+    let mut title = format!("Backlog: {} left", countdown(total));
+    if unknown > 0 {
+        title.push_str(&format!(" and {} unknown", unknown));
+    }
+    match pace {
+        Some(pace) => title.push_str(&format!(
+            ", at {} a day {}",
+            countdown(Duration::minutes(pace.minutes_per_day.round() as i64)),
+            if pace.recent { "lately" } else { "on average" }
+        )),
+        None => title.push_str(", nothing watched to go by"),
+    }
+
+    let active = app.get_current_route().active_block == ActiveBlock::Backlog;
+    let table = Table::new(HEADER.iter(), styled_rows)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(Span::styled(title, get_color((active, false), theme)))
+                .border_style(get_color((active, false), theme)),
+        )
+        .header_style(Style::default().fg(theme.hint).add_modifier(Modifier::BOLD))
+        .highlight_style(
+            Style::default()
+                .fg(theme.selected)
+                .add_modifier(Modifier::BOLD),
+        )
+        .highlight_symbol("> ")
+        .widths(&[
+            Constraint::Length(4),
+            Constraint::Percentage(50),
+            Constraint::Length(16),
+            Constraint::Length(12),
+        ]);
+
+    let mut state = TableState::default();
+    if !items.is_empty() {
+        state.select(Some(app.backlog.selected_index.min(items.len() - 1)));
+    }
+    f.render_stateful_widget(table, layout_chunk, &mut state);
+}
+
+fn row(app: &App, zone: Zone, index: usize, item: &Item<'_>) -> Vec<String> {
+    let title = app
+        .app_config
+        .title_language
+        .title(&item.anime.title, item.anime.alternative_titles.as_ref());
+    let left = match (item.episodes_left, item.time_left) {
+        (Some(episodes), Some(time)) => format!("{} ep, {}", episodes, countdown(time)),
+        (Some(episodes), None) => format!("{} ep", episodes),
+        _ => "?".to_string(),
+    };
+    let done_by = item.done_at.map_or("-".to_string(), |done_at| {
+        zone.localize(done_at).format("%Y-%m-%d").to_string()
+    });
+    vec![(index + 1).to_string(), title.to_string(), left, done_by]
+}
@@ -0,0 +1,175 @@
+use crate::app::{App, BatchStep};
+use crate::batch::BatchProgress;
+use tui::{
+    backend::Backend,
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Modifier, Style},
+    text::{Span, Spans},
+    widgets::{Block, Borders, Clear, Gauge, List, ListItem, ListState, Paragraph},
+    Frame,
+};
+
+/// What to do to the marked entries, over the list they're in
+pub fn draw_batch_popup<B>(f: &mut Frame<B>, app: &App, layout_chunk: Rect)
+where
+    B: Backend,
+{
+    let menu = &app.batch_menu;
+    let view = match menu.view {
+        Some(view) => view,
+        None => return,
+    };
+    if menu.step == BatchStep::Progress {
+        if let Some(batch) = &app.batch {
+            draw_progress(f, app, &batch.progress, layout_chunk);
+        }
+        return;
+    }
+
+    let theme = app.app_config.theme;
+    let text = Style::default().fg(theme.text);
+    let hint = Style::default().fg(theme.hint);
+    let count = app.batch_targets(view).len();
+    let entries = if count == 1 { "entry" } else { "entries" };
+    let title = match menu.step {
+        BatchStep::Action => format!("{} {}", count, entries),
+        BatchStep::Status => format!("Set the status of {} {}", count, entries),
+        BatchStep::Score => format!("Set the score of {} {}", count, entries),
+        BatchStep::AddTags => format!("Add tags to {} {}", count, entries),
+        BatchStep::RemoveTags => format!("Remove tags from {} {}", count, entries),
+        BatchStep::Priority => format!("Set the priority of {} {}", count, entries),
+        BatchStep::ConfirmDelete | BatchStep::Progress => format!("Delete {} {}", count, entries),
+    };
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .title(Span::styled(title, Style::default().fg(theme.selected)))
+        .border_style(Style::default().fg(theme.selected));
+
+    let options = menu.options();
+    if !options.is_empty() {
+        let area = popup_area(layout_chunk, options.len() as u16 + 2);
+        let items: Vec<ListItem> = options
+            .iter()
+            .map(|option| ListItem::new(Span::from(option.replace('_', " "))).style(text))
+            .collect();
+        let list = List::new(items)
+            .block(block)
+            .highlight_style(
+                Style::default()
+                    .fg(theme.selected)
+                    .add_modifier(Modifier::BOLD),
+            )
+            .highlight_symbol("> ");
+        let mut state = ListState::default();
+        state.select(Some(menu.selected_index));
+        f.render_widget(Clear, area);
+        f.render_stateful_widget(list, area, &mut state);
+        return;
+    }
+
+    let lines = match menu.step {
+        BatchStep::ConfirmDelete => vec![Spans::from(vec![
+            Span::styled(
+                format!("Remove {} {} from your list? ", count, entries),
+                Style::default().fg(theme.error_text),
+            ),
+            Span::styled("(y/n)", hint),
+        ])],
+        step => {
+            let (label, help) = match step {
+                BatchStep::Score => ("Score: ", "  (0 to 10, 0 clears it)"),
+                _ => ("Tags:  ", "  (comma separated)"),
+            };
+            vec![Spans::from(vec![
+                Span::styled(label, hint),
+                Span::styled(menu.input.as_str(), text),
+                Span::styled("_", Style::default().fg(theme.selected)),
+                Span::styled(help, hint),
+            ])]
+        }
+    };
+    let area = popup_area(layout_chunk, lines.len() as u16 + 2);
+    f.render_widget(Clear, area);
+    f.render_widget(Paragraph::new(lines).block(block), area);
+}
+
+/// How many entries are done, and the ones that failed with why
+fn draw_progress<B>(f: &mut Frame<B>, app: &App, progress: &BatchProgress, layout_chunk: Rect)
+where
+    B: Backend,
+{
+    let theme = app.app_config.theme;
+    let failures: Vec<ListItem> = progress
+        .failures
+        .iter()
+        .map(|(title, error)| {
+            ListItem::new(Spans::from(vec![
+                Span::styled(title.as_str(), Style::default().fg(theme.text)),
+                Span::styled(": ", Style::default().fg(theme.hint)),
+                Span::styled(error.as_str(), Style::default().fg(theme.error_text)),
+            ]))
+        })
+        .collect();
+    let height = if failures.is_empty() {
+        3
+    } else {
+        3 + failures.len().min(10) as u16 + 2
+    };
+    let area = popup_area(layout_chunk, height);
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Length(3), Constraint::Min(0)].as_ref())
+        .split(area);
+
+    let title = if progress.is_finished() {
+        format!(
+            "Done, {} of {} changed (<Esc> to close)",
+            progress.succeeded(),
+            progress.total
+        )
+    } else {
+        "Changing (<Esc> to hide)".to_string()
+    };
+    let ratio = if progress.total == 0 {
+        1.0
+    } else {
+        progress.done as f64 / progress.total as f64
+    };
+    let gauge = Gauge::default()
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(Span::styled(title, Style::default().fg(theme.selected)))
+                .border_style(Style::default().fg(theme.selected)),
+        )
+        .gauge_style(Style::default().fg(theme.active))
+        .ratio(ratio.min(1.0))
+        .label(format!("{}/{}", progress.done, progress.total));
+
+    f.render_widget(Clear, area);
+    f.render_widget(gauge, chunks[0]);
+    if !failures.is_empty() {
+        let failed = List::new(failures).block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(Span::styled(
+                    format!("{} failed", progress.failures.len()),
+                    Style::default().fg(theme.error_text),
+                ))
+                .border_style(Style::default().fg(theme.error_border)),
+        );
+        f.render_widget(failed, chunks[1]);
+    }
+}
+
+/// Centered in `layout_chunk`, 60% of its width
+fn popup_area(layout_chunk: Rect, height: u16) -> Rect {
+    let width = layout_chunk.width * 3 / 5;
+    let height = height.min(layout_chunk.height);
+    Rect::new(
+        layout_chunk.x + (layout_chunk.width - width) / 2,
+        layout_chunk.y + (layout_chunk.height - height) / 2,
+        width,
+        height,
+    )
+}
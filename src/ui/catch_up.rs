@@ -0,0 +1,86 @@
+use super::util::get_color;
+use crate::app::{ActiveBlock, App};
+use crate::schedule::CatchUp;
+use chrono::Utc;
+use tui::{
+    backend::Backend,
+    layout::{Constraint, Rect},
+    style::{Modifier, Style},
+    text::Span,
+    widgets::{Block, Borders, Row, Table, TableState},
+    Frame,
+};
+
+const HEADER: [&str; 4] = ["Title", "Progress", "Behind", "Last watched"];
+
+/// The shows the user is behind on, the furthest behind first
+pub fn draw_catch_up<B>(f: &mut Frame<B>, app: &App, layout_chunk: Rect)
+where
+    B: Backend,
+{
+    let theme = app.app_config.theme;
+    let shows = app.catch_up(Utc::now());
+    let rows: Vec<Vec<String>> = shows.iter().map(|show| row(app, show)).collect();
+    let styled_rows = rows.iter().zip(&shows).map(|(row, show)| {
+        // shows that look dropped stand out
+        let color = if show.stalled {
+            theme.error_text
+        } else {
+            theme.text
+        };
+        Row::StyledData(row.iter(), Style::default().fg(color))
+    });
+
+    let active = app.get_current_route().active_block == ActiveBlock::CatchUp;
+    let title = format!(
+        "Catch up ({} shows, {} episodes)",
+        shows.len(),
+        shows.iter().map(CatchUp::behind).sum::<u64>()
+    );
+    let table = Table::new(HEADER.iter(), styled_rows)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(Span::styled(title, get_color((active, false), theme)))
+                .border_style(get_color((active, false), theme)),
+        )
+        .header_style(Style::default().fg(theme.hint).add_modifier(Modifier::BOLD))
+        .highlight_style(Style::default().add_modifier(Modifier::BOLD))
+        .highlight_symbol("> ")
+        .widths(&[
+            Constraint::Percentage(50),
+            Constraint::Length(10),
+            Constraint::Length(8),
+            Constraint::Length(24),
+        ]);
+
+    let mut state = TableState::default();
+    if !shows.is_empty() {
+        state.select(Some(app.catch_up_index.min(shows.len() - 1)));
+    }
+    f.render_stateful_widget(table, layout_chunk, &mut state);
+}
+
+fn row(app: &App, show: &CatchUp<'_>) -> Vec<String> {
+    let title = app
+        .app_config
+        .title_language
+        .title(&show.anime.title, show.anime.alternative_titles.as_ref());
+    let weeks = show.idle.num_weeks();
+    let mut last_watched = match (show.idle.num_days(), weeks) {
+        (0, _) => "today".to_string(),
+        (1, _) => "yesterday".to_string(),
+        (days, 0) => format!("{} days ago", days),
+        (_, 1) => "a week ago".to_string(),
+        (_, weeks) => format!("{} weeks ago", weeks),
+    };
+    if show.stalled {
+        last_watched.push_str(", dropped?");
+    }
+    vec![
+        title.to_string(),
+        format!("{}/{}", show.watched, show.aired),
+        show.behind().to_string(),
+        last_watched,
+    ]
+}
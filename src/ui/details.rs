@@ -0,0 +1,290 @@
+use crate::api::model::*;
+use crate::app::App;
+use crate::cover::{HalfBlocks, Protocol};
+use tui::{
+    backend::Backend,
+    layout::Rect,
+    style::{Modifier, Style},
+    text::{Span, Spans},
+    widgets::{Block, Borders, Paragraph, Wrap},
+    Frame,
+};
+
+/// Returns where the cover goes if the terminal draws it, see `draw_with_cover`
+/// Rows the cover takes at most
+const MAX_COVER_HEIGHT: u16 = 16;
+
+pub fn draw_anime_details<B>(f: &mut Frame<B>, app: &App, layout_chunk: Rect) -> Option<Rect>
+where
+    B: Backend,
+{
+    let anime = match &app.anime_details {
+        Some(anime) => anime,
+        None => {
+            draw_loading(f, app, layout_chunk);
+            return None;
+        }
+    };
+
+    let mut facts = vec![];
+    if let Some(media_type) = &anime.media_type {
+        facts.push(name(media_type.clone()).to_uppercase());
+    }
+    if let Some(episodes) = anime.num_episodes.filter(|&n| n > 0) {
+        facts.push(format!("{} episodes", episodes));
+    }
+    if let Some(status) = &anime.status {
+        facts.push(name(status.clone()).replace('_', " "));
+    }
+
+    let my_list = match &anime.my_list_status {
+        Some(status) => format!(
+            "{}, {}/{} episodes{}",
+            name(status.status.clone()).replace('_', " "),
+            status.num_episodes_watched,
+            anime
+                .num_episodes
+                .map_or("?".to_string(), |n| n.to_string()),
+            score(status.score),
+        ),
+        None => "Not in your list".to_string(),
+    };
+
+    let lines = details_lines(
+        app,
+        Details {
+            titles: app
+                .app_config
+                .title_language
+                .main_titles(&anime.title, anime.alternative_titles.as_ref()),
+            facts,
+            mean: anime.mean,
+            rank: anime.rank,
+            popularity: anime.popularity,
+            start_date: anime.start_date,
+            genres: anime.genres.as_deref(),
+            my_list,
+            synopsis: anime.synopsis.as_deref(),
+        },
+    );
+    draw_with_cover(f, app, layout_chunk, "Anime", lines)
+}
+
+/// Returns where the cover goes if the terminal draws it, see `draw_with_cover`
+pub fn draw_manga_details<B>(f: &mut Frame<B>, app: &App, layout_chunk: Rect) -> Option<Rect>
+where
+    B: Backend,
+{
+    let manga = match &app.manga_details {
+        Some(manga) => manga,
+        None => {
+            draw_loading(f, app, layout_chunk);
+            return None;
+        }
+    };
+
+    let mut facts = vec![];
+    if let Some(media_type) = &manga.media_type {
+        facts.push(name(media_type.clone()).replace('_', " "));
+    }
+    if let Some(volumes) = manga.num_volumes.filter(|&n| n > 0) {
+        facts.push(format!("{} volumes", volumes));
+    }
+    if let Some(chapters) = manga.num_chapters.filter(|&n| n > 0) {
+        facts.push(format!("{} chapters", chapters));
+    }
+    if let Some(status) = &manga.status {
+        facts.push(name(status.clone()).replace('_', " "));
+    }
+
+    let my_list = match &manga.my_list_status {
+        Some(status) => format!(
+            "{}, {}/{} chapters{}",
+            name(status.status.clone()).replace('_', " "),
+            status.num_chapters_read,
+            manga
+                .num_chapters
+                .map_or("?".to_string(), |n| n.to_string()),
+            score(status.score),
+        ),
+        None => "Not in your list".to_string(),
+    };
+
+    let lines = details_lines(
+        app,
+        Details {
+            titles: app
+                .app_config
+                .title_language
+                .main_titles(&manga.title, manga.alternative_titles.as_ref()),
+            facts,
+            mean: manga.mean,
+            rank: manga.rank,
+            popularity: manga.popularity,
+            start_date: manga.start_date,
+            genres: manga.genres.as_deref(),
+            my_list,
+            synopsis: manga.synopsis.as_deref(),
+        },
+    );
+    draw_with_cover(f, app, layout_chunk, "Manga", lines)
+}
+
+/// What the anime and manga views have in common
+struct Details<'a> {
+    /// The preferred title first, the others under it
+    titles: Vec<&'a str>,
+    facts: Vec<String>,
+    mean: Option<f64>,
+    rank: Option<u64>,
+    popularity: Option<u64>,
+    start_date: Option<DateWrapper>,
+    genres: Option<&'a [Genre]>,
+    my_list: String,
+    synopsis: Option<&'a str>,
+}
+
+fn details_lines<'a>(app: &App, details: Details<'a>) -> Vec<Spans<'a>> {
+    let theme = app.app_config.theme;
+    let hint = Style::default().fg(theme.hint);
+    let mut titles = details.titles.into_iter();
+    let mut lines = vec![Spans::from(Span::styled(
+        titles.next().unwrap_or_default(),
+        Style::default()
+            .fg(theme.selected)
+            .add_modifier(Modifier::BOLD),
+    ))];
+    lines.extend(titles.map(|title| Spans::from(Span::styled(title, hint))));
+    lines.push(Spans::from(""));
+
+    if !details.facts.is_empty() {
+        lines.push(Spans::from(details.facts.join(" · ")));
+    }
+
+    let mut stats = vec![];
+    if let Some(mean) = details.mean {
+        stats.push(format!("Score {:.2}", mean));
+    }
+    if let Some(rank) = details.rank {
+        stats.push(format!("Ranked #{}", rank));
+    }
+    if let Some(popularity) = details.popularity {
+        stats.push(format!("Popularity #{}", popularity));
+    }
+    if !stats.is_empty() {
+        lines.push(Spans::from(stats.join(" · ")));
+    }
+
+    if let Some(date) = details.start_date {
+        lines.push(Spans::from(format!("Started {}", date.pretty())));
+    }
+    if let Some(genres) = details.genres.filter(|genres| !genres.is_empty()) {
+        let genres: Vec<&str> = genres.iter().map(|genre| genre.name.as_str()).collect();
+        lines.push(Spans::from(format!("Genres: {}", genres.join(", "))));
+    }
+    lines.push(Spans::from(vec![
+        Span::styled("My list: ", hint),
+        Span::raw(details.my_list),
+    ]));
+
+    if let Some(synopsis) = details.synopsis {
+        lines.push(Spans::from(""));
+        lines.extend(synopsis.lines().map(Spans::from));
+    }
+    lines
+}
+
+fn draw_paragraph<B>(
+    f: &mut Frame<B>,
+    app: &App,
+    layout_chunk: Rect,
+    title: &str,
+    lines: Vec<Spans>,
+) where
+    B: Backend,
+{
+    let paragraph = paragraph(app, lines).block(details_block(app, title));
+    f.render_widget(paragraph, layout_chunk);
+}
+
+/// The details with the cover on their left. Half blocks are drawn in the buffer, the
+/// other protocols draw over it so the area they get is returned and left blank.
+fn draw_with_cover<B>(
+    f: &mut Frame<B>,
+    app: &App,
+    layout_chunk: Rect,
+    title: &str,
+    lines: Vec<Spans>,
+) -> Option<Rect>
+where
+    B: Backend,
+{
+    let (protocol, cover) = match (app.cover_protocol, &app.cover) {
+        (Some(protocol), Some(cover)) if app.cover_url() == Some(cover.url.as_str()) => {
+            (protocol, cover)
+        }
+        _ => {
+            draw_paragraph(f, app, layout_chunk, title, lines);
+            return None;
+        }
+    };
+
+    let block = details_block(app, title);
+    let inner = block.inner(layout_chunk);
+    f.render_widget(block, layout_chunk);
+
+    let height = inner.height.min(MAX_COVER_HEIGHT);
+    let width = cover.width(height).min(inner.width / 2);
+    let cover_area = Rect::new(inner.x, inner.y, width, height);
+    let text_area = Rect::new(
+        inner.x + width + 1,
+        inner.y,
+        inner.width.saturating_sub(width + 1),
+        inner.height,
+    );
+    f.render_widget(paragraph(app, lines), text_area);
+
+    if protocol == Protocol::HalfBlocks {
+        f.render_widget(HalfBlocks::new(&cover.image), cover_area);
+        None
+    } else {
+        Some(cover_area)
+    }
+}
+
+fn details_block<'a>(app: &App, title: &'a str) -> Block<'a> {
+    let theme = app.app_config.theme;
+    Block::default()
+        .borders(Borders::ALL)
+        .title(Span::styled(title, Style::default().fg(theme.selected)))
+        .border_style(Style::default().fg(theme.selected))
+}
+
+fn paragraph<'a>(app: &App, lines: Vec<Spans<'a>>) -> Paragraph<'a> {
+    Paragraph::new(lines)
+        .style(Style::default().fg(app.app_config.theme.text))
+        .wrap(Wrap { trim: true })
+}
+
+fn draw_loading<B>(f: &mut Frame<B>, app: &App, layout_chunk: Rect)
+where
+    B: Backend,
+{
+    let lines = vec![Spans::from(Span::styled(
+        "Loading...",
+        Style::default().fg(app.app_config.theme.hint),
+    ))];
+    draw_paragraph(f, app, layout_chunk, "Details", lines);
+}
+
+fn name<T: Into<&'static str>>(value: T) -> &'static str {
+    value.into()
+}
+
+fn score(score: u8) -> String {
+    if score == 0 {
+        String::new()
+    } else {
+        format!(", scored {}", score)
+    }
+}
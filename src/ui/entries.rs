@@ -0,0 +1,258 @@
+use super::table::{ColumnId, DataTable, TableHeader, TableItem};
+use super::util::get_color;
+use crate::app::{ActiveBlock, App, Entry, EntryView, RouteId, RowMatch, SearchResultBlock, Value};
+use crate::config::TitleLanguage;
+use std::collections::HashMap;
+use tui::{
+    backend::Backend,
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Modifier, Style},
+    text::{Span, Spans},
+    widgets::{Block, Borders},
+    Frame,
+};
+
+/// Anime results on the left, manga on the right
+pub fn draw_search_results<B>(f: &mut Frame<B>, app: &App, layout_chunk: Rect)
+where
+    B: Backend,
+{
+    let chunks = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Percentage(50), Constraint::Percentage(50)].as_ref())
+        .split(layout_chunk);
+
+    let active = app.get_current_route().active_block == ActiveBlock::SearchResultBlock;
+    let hovered = &app.search_results.hovered_block;
+    draw_entries(
+        f,
+        app,
+        chunks[0],
+        EntryView::AnimeSearch,
+        "Anime",
+        (active && *hovered == SearchResultBlock::AnimeSearch, false),
+    );
+    draw_entries(
+        f,
+        app,
+        chunks[1],
+        EntryView::MangaSearch,
+        "Manga",
+        (active && *hovered == SearchResultBlock::MangaSearch, false),
+    );
+}
+
+/// The ranking or user list the route is for
+pub fn draw_route_entries<B>(f: &mut Frame<B>, app: &App, layout_chunk: Rect)
+where
+    B: Backend,
+{
+    let (view, title) = match app.get_current_route().id {
+        RouteId::AnimeRanking => (EntryView::AnimeRanking, "Anime Ranking"),
+        RouteId::MangaRanking => (EntryView::MangaRanking, "Manga Ranking"),
+        RouteId::AnimeList => (EntryView::AnimeList, "Anime List"),
+        RouteId::MangaList => (EntryView::MangaList, "Manga List"),
+        _ => return,
+    };
+    let active = app.get_current_route().active_block == ActiveBlock::Entries;
+    draw_entries(f, app, layout_chunk, view, title, (active, false));
+}
+
+/// A table of the view's rows, narrowed down to the ones matching the `/` filter if
+/// there is one
+pub fn draw_entries<B>(
+    f: &mut Frame<B>,
+    app: &App,
+    layout_chunk: Rect,
+    view: EntryView,
+    title: &str,
+    highlight_state: (bool, bool),
+) where
+    B: Backend,
+{
+    let theme = app.app_config.theme;
+    let text = Style::default().fg(theme.text);
+    let hint = Style::default().fg(theme.hint);
+    let matched = Style::default()
+        .fg(theme.selected)
+        .add_modifier(Modifier::BOLD);
+
+    let language = app.app_config.title_language;
+    let entries = app.entries(view);
+    let state = app.views.get(&view);
+    let filter = state.and_then(|state| state.filter.as_ref());
+    let row_matches: HashMap<usize, &RowMatch> = filter
+        .iter()
+        .flat_map(|filter| filter.matches.iter().map(|row| (row.index, row)))
+        .collect();
+
+    let header = TableHeader::new(view.table_id(), app.app_config.table(view.table_id()));
+    let items: Vec<TableItem> = app
+        .visible_rows(view)
+        .into_iter()
+        .map(|index| {
+            let entry = entries[index];
+            let format = header
+                .columns()
+                .map(|column| match (column, row_matches.get(&index)) {
+                    (ColumnId::Title, Some(row)) => {
+                        Spans::from(title_spans(entry, row, language, (text, hint, matched)))
+                    }
+                    _ => Spans::from(Span::styled(cell(entry, column, language), text)),
+                })
+                .collect();
+            TableItem {
+                id: entry.id(),
+                format,
+            }
+        })
+        .collect();
+
+    let title = match filter {
+        Some(filter) => format!(
+            "{} /{}{} ({}/{})",
+            title,
+            filter.query,
+            if filter.editing { "_" } else { "" },
+            filter.matches.len(),
+            entries.len()
+        ),
+        None => title.to_string(),
+    };
+
+    let mut title = title;
+    let marked = app.marked(view);
+    if !marked.is_empty() {
+        title.push_str(&format!(" [{} marked]", marked.len()));
+    }
+    match &app.batch {
+        Some(batch) if batch.view == view && !batch.progress.is_finished() => {
+            title.push_str(&format!(
+                " [changing {}/{}]",
+                batch.progress.done, batch.progress.total
+            ));
+        }
+        _ => {}
+    }
+
+    let selected = if items.is_empty() {
+        None
+    } else {
+        Some(state.map_or(0, |state| state.selected_index))
+    };
+    let table = DataTable::new(header, items)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(Span::styled(title, get_color(highlight_state, theme)))
+                .border_style(get_color(highlight_state, theme)),
+        )
+        .header_style(hint.add_modifier(Modifier::BOLD))
+        .sort(state.and_then(|state| state.sort))
+        .select(selected)
+        .marked(marked, Style::default().fg(theme.hovered));
+    f.render_widget(table, layout_chunk);
+}
+
+/// The main title with what the filter matched highlighted, or followed by the other
+/// title that matched
+fn title_spans(
+    entry: Entry<'_>,
+    row: &RowMatch,
+    language: TitleLanguage,
+    (text, hint, matched): (Style, Style, Style),
+) -> Vec<Span<'static>> {
+    let titles = entry.titles(language);
+    if row.title_index == 0 {
+        return highlighted(titles[0], &row.positions, text, matched);
+    }
+    let mut spans = vec![Span::styled(titles[0].to_string(), text)];
+    spans.push(Span::styled("  ", hint));
+    spans.extend(highlighted(
+        titles[row.title_index],
+        &row.positions,
+        hint,
+        matched,
+    ));
+    spans
+}
+
+/// What `entry` shows in `column`
+fn cell(entry: Entry<'_>, column: ColumnId, language: TitleLanguage) -> String {
+    let value = match entry.value(column, language) {
+        Some(value) => value,
+        None if column == ColumnId::Progress => return String::new(),
+        None => return "-".to_string(),
+    };
+    match (column, value) {
+        (ColumnId::Type, Value::Text(text)) if matches!(entry, Entry::Anime(_)) => {
+            text.to_uppercase()
+        }
+        (ColumnId::Title, Value::Text(text)) => text.to_string(),
+        (_, Value::Text(text)) => text.replace('_', " "),
+        (ColumnId::Score, Value::Number(n)) => format!("{:.2}", n),
+        (ColumnId::Rank | ColumnId::Popularity, Value::Number(n)) => format!("#{}", n),
+        (ColumnId::Progress, Value::Number(n)) => {
+            let total = match entry {
+                Entry::Anime(_) => entry.value(ColumnId::Episodes, language),
+                Entry::Manga(_) => entry.value(ColumnId::Chapters, language),
+            };
+            match total {
+                Some(Value::Number(total)) => format!("{}/{}", n, total),
+                _ => format!("{}/?", n),
+            }
+        }
+        (_, Value::Number(n)) => n.to_string(),
+    }
+}
+
+/// `text` with the chars at `positions` in the `matched` style
+fn highlighted(
+    text: &str,
+    positions: &[usize],
+    style: Style,
+    matched: Style,
+) -> Vec<Span<'static>> {
+    let mut spans = vec![];
+    let mut run = String::new();
+    let mut run_matched = false;
+    for (index, c) in text.chars().enumerate() {
+        let is_matched = positions.contains(&index);
+        if is_matched != run_matched && !run.is_empty() {
+            let style = if run_matched { matched } else { style };
+            spans.push(Span::styled(std::mem::take(&mut run), style));
+        }
+        run_matched = is_matched;
+        run.push(c);
+    }
+    if !run.is_empty() {
+        spans.push(Span::styled(run, if run_matched { matched } else { style }));
+    }
+    spans
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tui::style::Color;
+
+    #[test]
+    fn test_highlighted() {
+        let style = Style::default();
+        let matched = Style::default().fg(Color::Yellow);
+        let spans = highlighted("Cowboy Bebop", &[0, 7, 8], style, matched);
+        let runs: Vec<(&str, Style)> = spans
+            .iter()
+            .map(|span| (span.content.as_ref(), span.style))
+            .collect();
+        assert_eq!(
+            runs,
+            vec![
+                ("C", matched),
+                ("owboy ", style),
+                ("Be", matched),
+                ("bop", style),
+            ]
+        );
+    }
+}
@@ -1,4 +1,41 @@
 pub fn get_help() -> Vec<Vec<&'static str>> {
     // TODO: Help docs
-    vec![vec!["Down", "j", "Pagination"]]
+    vec![
+        vec!["Down", "j", "Pagination"],
+        vec!["Switch account", "P", "General"],
+        vec!["Select a suggestion", "<Up>/<Down>", "Search input"],
+        vec!["Open the selected suggestion", "<Enter>", "Search input"],
+        vec!["Previous/next search", "<Up>/<Down>", "Empty search input"],
+        vec!["Search the history", "<Ctrl+r>", "Search input"],
+        vec!["Save the search", "<Ctrl+s>", "Search input"],
+        vec!["Go to the saved searches", "S", "General"],
+        vec!["Filter the rows", "/", "Lists"],
+        vec!["Keep the filter", "<Enter>", "Typing a filter"],
+        vec!["Clear the filter", "<Esc>", "Lists"],
+        vec!["Anime/manga results", "h/l", "Search results"],
+        vec!["Sort by the next column", "s", "Lists"],
+        vec!["Reverse the sort", "r", "Lists"],
+        vec!["Mark/unmark the entry", "<Space>", "My lists"],
+        vec!["Start/mark a range to the cursor", "V", "My lists"],
+        vec!["Change the marked entries", "a", "My lists"],
+        vec!["Unmark everything", "<Esc>", "My lists"],
+        vec!["Previous/next day", "h/l", "Schedule"],
+        vec!["Open the show's details", "<Enter>", "Schedule"],
+        vec!["Open the show's details", "<Enter>", "Catch up"],
+        vec!["Move the show up/down the queue", "K/J", "Backlog"],
+        vec!["Open the show's details", "<Enter>", "Backlog"],
+        vec!["Run the saved search", "<Enter>", "Saved searches"],
+        vec!["Delete the saved search", "d", "Saved searches"],
+        vec![
+            "Filter by a field",
+            "type:movie genre:mecha",
+            "Search input",
+        ],
+        vec![
+            "Filter by a number range",
+            "year:2019..2021 score>8",
+            "Search input",
+        ],
+        vec!["Leave out matches", "-genre:ecchi", "Search input"],
+    ]
 }
@@ -1,65 +1,25 @@
+pub mod backlog;
+pub mod batch;
+pub mod catch_up;
+pub mod details;
+pub mod entries;
 pub mod help;
+pub mod schedule;
+pub mod table;
 pub mod util;
-use crate::api::model::*;
 use crate::app::*;
+use crate::search::SearchScope;
 use tui::{
     backend::Backend,
     layout::{Constraint, Direction, Layout, Rect},
-    style::{Color, Modifier, Style},
-    symbols,
+    style::{Modifier, Style},
     text::{Span, Spans},
-    widgets::canvas::{Canvas, Line, Map, MapResolution, Rectangle},
-    widgets::{
-        Axis, BarChart, Block, Borders, Chart, Dataset, Gauge, List, ListItem, ListState,
-        Paragraph, Row, Sparkline, Table, Tabs, Wrap,
-    },
+    widgets::{Block, Borders, Clear, List, ListItem, ListState, Paragraph, Row, Table, Wrap},
     Frame,
 };
 use util::get_color;
 
-pub enum TableId {
-    Anime,
-    Manga,
-    User,
-    AnimeList,
-    MangaList,
-}
-
-#[derive(PartialEq)]
-pub enum ColumnId {
-    None,
-    Anime,
-    Manga,
-}
-
-impl Default for ColumnId {
-    fn default() -> Self {
-        ColumnId::None
-    }
-}
-
-pub struct TableHeader<'a> {
-    id: TableId,
-    items: Vec<TableHeaderItem<'a>>,
-}
-
-impl TableHeader<'_> {
-    pub fn get_index(&self, id: ColumnId) -> Option<usize> {
-        self.items.iter().position(|item| item.id == id)
-    }
-}
-
-#[derive(Default)]
-pub struct TableHeaderItem<'a> {
-    id: ColumnId,
-    text: &'a str,
-    width: u16,
-}
-
-pub struct TableItem {
-    id: String,
-    format: Vec<String>,
-}
+pub use table::{ColumnId, DataTable, Sort, TableHeader, TableId, TableItem};
 
 pub fn draw_help_menu<B>(f: &mut Frame<B>, app: &App)
 where
@@ -143,7 +103,8 @@ where
     f.render_widget(error_paragraph, chunks[0]);
 }
 
-pub fn draw_main_layout<B>(f: &mut Frame<B>, app: &App)
+/// Returns where the cover goes if the terminal draws it rather than the buffer
+pub fn draw_main_layout<B>(f: &mut Frame<B>, app: &App) -> Option<Rect>
 where
     B: Backend,
 {
@@ -158,7 +119,159 @@ where
     draw_input_and_help_box(f, app, parent_layout[0]);
 
     // Draw dashboard
-    draw_routes(f, app, parent_layout[1]);
+    let cover_area = draw_routes(f, app, parent_layout[1]);
+
+    // Suggestions and the search popups drop down over the dashboard, and the cover
+    // would be drawn over them
+    match app.get_current_route().active_block {
+        ActiveBlock::Input if !app.suggestions.is_empty() => {
+            draw_suggestions(f, app, parent_layout[0], parent_layout[1]);
+            None
+        }
+        ActiveBlock::SearchHistory => {
+            draw_history_popup(f, app, parent_layout[0], parent_layout[1]);
+            None
+        }
+        ActiveBlock::SaveSearch => {
+            draw_save_prompt(f, app, parent_layout[0], parent_layout[1]);
+            None
+        }
+        _ => cover_area,
+    }
+}
+
+/// Area right under the search box and as wide as it, which takes 90% of the row
+fn drop_down_area(input_chunk: Rect, below: Rect, height: u16) -> Rect {
+    Rect::new(
+        input_chunk.x,
+        below.y,
+        input_chunk.width * 9 / 10,
+        height.min(below.height),
+    )
+}
+
+/// Past searches matching what's typed, best match first
+pub fn draw_history_popup<B>(f: &mut Frame<B>, app: &App, input_chunk: Rect, below: Rect)
+where
+    B: Backend,
+{
+    let theme = app.app_config.theme;
+    let items: Vec<ListItem> = app
+        .history_popup
+        .matches
+        .iter()
+        .map(|&index| {
+            ListItem::new(app.searches.history[index].as_str())
+                .style(Style::default().fg(theme.text))
+        })
+        .collect();
+
+    // always tall enough to show a few, so it doesn't jump around while typing
+    let area = drop_down_area(input_chunk, below, items.len().max(5) as u16 + 2);
+
+    let mut state = ListState::default();
+    state.select(Some(app.history_popup.selected_index));
+    let list = List::new(items)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(Span::styled(
+                    format!("History: {}", app.history_popup.filter),
+                    Style::default().fg(theme.selected),
+                ))
+                .border_style(Style::default().fg(theme.selected)),
+        )
+        .highlight_style(
+            Style::default()
+                .fg(theme.selected)
+                .add_modifier(Modifier::BOLD),
+        )
+        .highlight_symbol("> ");
+
+    f.render_widget(Clear, area);
+    f.render_stateful_widget(list, area, &mut state);
+}
+
+/// Asks for a name for the search in the search box
+pub fn draw_save_prompt<B>(f: &mut Frame<B>, app: &App, input_chunk: Rect, below: Rect)
+where
+    B: Backend,
+{
+    let theme = app.app_config.theme;
+    let hint = Style::default().fg(theme.hint);
+    let text = Style::default().fg(theme.text);
+    let lines = vec![
+        Spans::from(vec![
+            Span::styled("Name:  ", hint),
+            Span::styled(app.save_prompt.name.as_str(), text),
+            Span::styled("_", Style::default().fg(theme.selected)),
+        ]),
+        Spans::from(vec![
+            Span::styled("Scope: ", hint),
+            Span::styled(app.save_prompt.scope.name(), text),
+            Span::styled("  (<Tab> to change)", hint),
+        ]),
+    ];
+
+    let area = drop_down_area(input_chunk, below, lines.len() as u16 + 2);
+    let prompt = Paragraph::new(lines).block(
+        Block::default()
+            .borders(Borders::ALL)
+            .title(Span::styled(
+                "Save search (<Enter> to save, <Esc> to cancel)",
+                Style::default().fg(theme.selected),
+            ))
+            .border_style(Style::default().fg(theme.selected)),
+    );
+
+    f.render_widget(Clear, area);
+    f.render_widget(prompt, area);
+}
+
+/// Lists the top matches for what's being typed right under the search box
+pub fn draw_suggestions<B>(f: &mut Frame<B>, app: &App, input_chunk: Rect, below: Rect)
+where
+    B: Backend,
+{
+    let theme = app.app_config.theme;
+    let language = app.app_config.title_language;
+    let anime = app.suggestions.anime.iter().map(|anime| {
+        let title = language.title(&anime.title, anime.alternative_titles.as_ref());
+        ("Anime", title)
+    });
+    let manga = app.suggestions.manga.iter().map(|manga| {
+        let title = language.title(&manga.title, manga.alternative_titles.as_ref());
+        ("Manga", title)
+    });
+    let items: Vec<ListItem> = anime
+        .chain(manga)
+        .map(|(kind, title)| {
+            ListItem::new(Spans::from(vec![
+                Span::styled(format!("{} ", kind), Style::default().fg(theme.hint)),
+                Span::styled(title, Style::default().fg(theme.text)),
+            ]))
+        })
+        .collect();
+
+    let area = drop_down_area(input_chunk, below, items.len() as u16 + 2);
+
+    let mut state = ListState::default();
+    state.select(app.suggestions.selected_index);
+    let list = List::new(items)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(theme.selected)),
+        )
+        .highlight_style(
+            Style::default()
+                .fg(theme.selected)
+                .add_modifier(Modifier::BOLD),
+        )
+        .highlight_symbol("> ");
+
+    f.render_widget(Clear, area);
+    f.render_stateful_widget(list, area, &mut state);
 }
 
 pub fn draw_input_and_help_box<B>(f: &mut Frame<B>, app: &App, layout_chunk: Rect)
@@ -190,7 +303,7 @@ where
     );
     f.render_widget(input, chunks[0]);
 
-    let show_loading = app.is_loading && app.app_config.behavior.show_loading_indicator;
+    let show_loading = app.is_loading() && app.app_config.behavior.show_loading_indicator;
     let help_block_text = if show_loading {
         (app.app_config.theme.hint, "Loading...")
     } else {
@@ -209,7 +322,7 @@ where
     f.render_widget(help, chunks[1]);
 }
 
-pub fn draw_routes<B>(f: &mut Frame<B>, app: &App, layout_chunk: Rect)
+pub fn draw_routes<B>(f: &mut Frame<B>, app: &App, layout_chunk: Rect) -> Option<Rect>
 where
     B: Backend,
 {
@@ -221,6 +334,27 @@ where
     draw_user_block(f, app, chunks[0]);
 
     let current_route = app.get_current_route();
+    if current_route.active_block == ActiveBlock::ProfileSwitcher {
+        draw_profile_switcher(f, app, chunks[1]);
+        return None;
+    }
+
+    match current_route.id {
+        RouteId::AnimeDetails => return details::draw_anime_details(f, app, chunks[1]),
+        RouteId::MangaDetails => return details::draw_manga_details(f, app, chunks[1]),
+        RouteId::Search => entries::draw_search_results(f, app, chunks[1]),
+        RouteId::AnimeRanking | RouteId::MangaRanking | RouteId::AnimeList | RouteId::MangaList => {
+            entries::draw_route_entries(f, app, chunks[1])
+        }
+        RouteId::Schedule => schedule::draw_schedule(f, app, chunks[1]),
+        RouteId::CatchUp => catch_up::draw_catch_up(f, app, chunks[1]),
+        RouteId::Backlog => backlog::draw_backlog(f, app, chunks[1]),
+        _ => {}
+    }
+
+    if current_route.active_block == ActiveBlock::BatchEdit {
+        batch::draw_batch_popup(f, app, chunks[1]);
+    }
 
     // match current_route.id {
     //     RouteId::Search => {
@@ -231,6 +365,7 @@ where
     //         draw_dashboard(f, app, chunks[1]);
     //     }
     // };
+    None
 }
 
 pub fn draw_anime_routes<B>(f: &mut Frame<B>, app: &App, layout_chunk: Rect)
@@ -310,6 +445,80 @@ where
         Some(app.library.selected_index),
     );
 }
+pub fn draw_saved_searches<B>(f: &mut Frame<B>, app: &App, layout_chunk: Rect)
+where
+    B: Backend,
+{
+    let current_route = app.get_current_route();
+    let highlight_state = (
+        current_route.active_block == ActiveBlock::SavedSearches,
+        current_route.hovered_block == ActiveBlock::SavedSearches,
+    );
+
+    let theme = app.app_config.theme;
+    let items: Vec<ListItem> = app
+        .searches
+        .saved
+        .iter()
+        .map(|saved| {
+            let mut spans = vec![Span::styled(
+                saved.name.as_str(),
+                Style::default().fg(theme.text),
+            )];
+            if saved.scope != SearchScope::All {
+                spans.push(Span::styled(
+                    format!(" ({})", saved.scope.name()),
+                    Style::default().fg(theme.hint),
+                ));
+            }
+            ListItem::new(Spans::from(spans))
+        })
+        .collect();
+
+    draw_selectable_list(
+        f,
+        app,
+        layout_chunk,
+        "Saved",
+        items,
+        highlight_state,
+        Some(app.saved_searches_index),
+    );
+}
+
+pub fn draw_profile_switcher<B>(f: &mut Frame<B>, app: &App, layout_chunk: Rect)
+where
+    B: Backend,
+{
+    let items: Vec<ListItem> = app
+        .profiles
+        .names
+        .iter()
+        .map(|name| {
+            let (label, color) = if *name == app.profiles.active {
+                let label = match &app.profiles.user {
+                    Some(user) => format!("{} ({}, active)", name, user.name),
+                    None => format!("{} (active)", name),
+                };
+                (label, app.app_config.theme.selected)
+            } else {
+                (name.clone(), app.app_config.theme.text)
+            };
+            ListItem::new(Span::from(label)).style(Style::default().fg(color))
+        })
+        .collect();
+
+    draw_selectable_list(
+        f,
+        app,
+        layout_chunk,
+        "Accounts",
+        items,
+        (true, true),
+        Some(app.profiles.selected_index),
+    );
+}
+
 pub fn draw_user_block<B>(f: &mut Frame<B>, app: &App, layout_chunk: Rect)
 where
     B: Backend,
@@ -318,9 +527,10 @@ where
         .direction(Direction::Vertical)
         .constraints(
             [
+                Constraint::Percentage(25),
+                Constraint::Percentage(20),
+                Constraint::Percentage(25),
                 Constraint::Percentage(30),
-                Constraint::Percentage(30),
-                Constraint::Percentage(40),
             ]
             .as_ref(),
         )
@@ -329,6 +539,7 @@ where
     draw_anime_routes(f, app, chunks[0]);
     draw_manga_routes(f, app, chunks[1]);
     draw_user_routes(f, app, chunks[2]);
+    draw_saved_searches(f, app, chunks[3]);
 }
 
 pub fn draw_selectable_list<B>(
@@ -346,7 +557,15 @@ pub fn draw_selectable_list<B>(
     state.select(selected_index);
 
     let items = List::new(items)
-        .block(Block::default().borders(Borders::ALL).title(title))
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(Span::styled(
+                    title,
+                    get_color(highlight_state, app.app_config.theme),
+                ))
+                .border_style(get_color(highlight_state, app.app_config.theme)),
+        )
         .highlight_style(Style::default().add_modifier(Modifier::BOLD))
         .highlight_symbol("> ");
 
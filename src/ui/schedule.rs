@@ -0,0 +1,85 @@
+use super::util::get_color;
+use crate::app::{ActiveBlock, App};
+use crate::schedule::{countdown, Airing, WEEKDAYS};
+use chrono::{DateTime, Datelike, Utc};
+use tui::{
+    backend::Backend,
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Modifier, Style},
+    text::{Span, Spans},
+    widgets::{Block, Borders, List, ListItem, ListState},
+    Frame,
+};
+
+/// A column for each day of the week, with the shows airing on it and when
+pub fn draw_schedule<B>(f: &mut Frame<B>, app: &App, layout_chunk: Rect)
+where
+    B: Backend,
+{
+    let now = Utc::now();
+    let week = app.schedule_week(now);
+    let selected_day = app.schedule_day(now);
+    let today = app
+        .schedule_zone()
+        .localize(now)
+        .weekday()
+        .num_days_from_monday() as usize;
+    let active = app.get_current_route().active_block == ActiveBlock::Schedule;
+
+    let chunks = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Ratio(1, 7); 7].as_ref())
+        .split(layout_chunk);
+
+    for (day, shows) in week.iter().enumerate() {
+        let selected = day == selected_day;
+        let mut title = format!("{:?}", WEEKDAYS[day]);
+        if day == today {
+            title.push_str(" (today)");
+        }
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .title(Span::styled(
+                title,
+                get_color((active && selected, day == today), app.app_config.theme),
+            ))
+            .border_style(get_color((active && selected, false), app.app_config.theme));
+
+        let items: Vec<ListItem> = shows
+            .iter()
+            .map(|airing| show_item(app, airing, now))
+            .collect();
+        let mut state = ListState::default();
+        if selected && !items.is_empty() {
+            state.select(Some(app.schedule.index.min(items.len() - 1)));
+        }
+        let list = List::new(items)
+            .block(block)
+            .style(Style::default().fg(app.app_config.theme.text))
+            .highlight_style(
+                Style::default()
+                    .fg(app.app_config.theme.selected)
+                    .add_modifier(Modifier::BOLD),
+            );
+        f.render_stateful_widget(list, chunks[day], &mut state);
+    }
+}
+
+/// The time and title, then the next episode and how long until it airs
+fn show_item<'a>(app: &App, airing: &Airing<'a>, now: DateTime<Utc>) -> ListItem<'a> {
+    let title = app.app_config.title_language.title(
+        &airing.anime.title,
+        airing.anime.alternative_titles.as_ref(),
+    );
+    let episode = match airing.next.number {
+        Some(number) => format!("ep {}", number),
+        None => "next ep".to_string(),
+    };
+    ListItem::new(vec![
+        Spans::from(format!("{} {}", airing.local.format("%H:%M"), title)),
+        Spans::from(Span::styled(
+            format!("{} in {}", episode, countdown(airing.next.airs_at - now)),
+            Style::default().fg(app.app_config.theme.hint),
+        )),
+    ])
+}
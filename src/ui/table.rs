@@ -0,0 +1,415 @@
+use crate::config::app_config::TableConfig;
+use serde::Deserialize;
+use std::collections::HashSet;
+use tui::{
+    buffer::Buffer,
+    layout::Rect,
+    style::{Modifier, Style},
+    text::{Span, Spans},
+    widgets::{Block, Widget},
+};
+
+/// Space between columns
+const COLUMN_SPACING: u16 = 1;
+const HIGHLIGHT_SYMBOL: &str = "> ";
+/// Shown after the highlight symbol on marked rows
+const MARK_SYMBOL: &str = "+";
+
+/// The tables whose columns can be configured, each shared by the views showing the
+/// same kind of rows
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TableId {
+    /// Anime search results and rankings
+    Anime,
+    /// Manga search results and rankings
+    Manga,
+    User,
+    AnimeList,
+    MangaList,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ColumnId {
+    Title,
+    Type,
+    Status,
+    Year,
+    Episodes,
+    Volumes,
+    Chapters,
+    Score,
+    Rank,
+    Popularity,
+    Members,
+    MyStatus,
+    MyScore,
+    /// Episodes watched or chapters read
+    Progress,
+}
+
+impl ColumnId {
+    pub fn name(self) -> &'static str {
+        match self {
+            ColumnId::Title => "Title",
+            ColumnId::Type => "Type",
+            ColumnId::Status => "Status",
+            ColumnId::Year => "Year",
+            ColumnId::Episodes => "Eps",
+            ColumnId::Volumes => "Vols",
+            ColumnId::Chapters => "Chs",
+            ColumnId::Score => "Score",
+            ColumnId::Rank => "Rank",
+            ColumnId::Popularity => "Popularity",
+            ColumnId::Members => "Members",
+            ColumnId::MyStatus => "My Status",
+            ColumnId::MyScore => "My Score",
+            ColumnId::Progress => "Progress",
+        }
+    }
+
+    /// Width used when the config doesn't set one, 0 for the column taking the space left
+    pub fn default_width(self) -> u16 {
+        match self {
+            ColumnId::Title => 0,
+            ColumnId::Type => 8,
+            ColumnId::Status => 18,
+            ColumnId::Year | ColumnId::Episodes | ColumnId::Volumes | ColumnId::Chapters => 5,
+            ColumnId::Score | ColumnId::Rank => 6,
+            ColumnId::Popularity | ColumnId::Members => 10,
+            ColumnId::MyStatus => 14,
+            ColumnId::MyScore => 8,
+            ColumnId::Progress => 9,
+        }
+    }
+
+    /// Whether the biggest values are the interesting ones, so sorting starts with them
+    pub fn sorts_descending(self) -> bool {
+        matches!(
+            self,
+            ColumnId::Year | ColumnId::Score | ColumnId::Members | ColumnId::MyScore
+        )
+    }
+}
+
+/// Column a table is sorted by
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Sort {
+    pub column: ColumnId,
+    pub descending: bool,
+}
+
+pub struct TableHeader<'a> {
+    id: TableId,
+    items: Vec<TableHeaderItem<'a>>,
+}
+
+impl TableHeader<'_> {
+    /// The configured columns of table `id`
+    pub fn new(id: TableId, config: &TableConfig) -> Self {
+        let items = config
+            .columns
+            .iter()
+            .map(|&column| TableHeaderItem {
+                id: column,
+                text: column.name(),
+                width: config
+                    .widths
+                    .get(&column)
+                    .copied()
+                    .unwrap_or_else(|| column.default_width()),
+            })
+            .collect();
+        Self { id, items }
+    }
+
+    pub fn id(&self) -> TableId {
+        self.id
+    }
+
+    pub fn columns(&self) -> impl Iterator<Item = ColumnId> + '_ {
+        self.items.iter().map(|item| item.id)
+    }
+
+    pub fn get_index(&self, id: ColumnId) -> Option<usize> {
+        self.items.iter().position(|item| item.id == id)
+    }
+
+    /// Widths of the columns in `width`, the ones without a width share what the
+    /// others leave. Columns past the end are cut short, or to 0.
+    fn widths(&self, width: u16) -> Vec<u16> {
+        let spacing = COLUMN_SPACING * self.items.len().saturating_sub(1) as u16;
+        let fixed: u16 = self.items.iter().map(|item| item.width).sum();
+        let flexible = self.items.iter().filter(|item| item.width == 0).count() as u16;
+        let left = width.saturating_sub(fixed + spacing);
+        let mut remaining = width;
+        self.items
+            .iter()
+            .map(|item| {
+                let wanted = match item.width {
+                    0 => left / flexible.max(1),
+                    width => width,
+                };
+                let width = wanted.min(remaining);
+                remaining = remaining.saturating_sub(width + COLUMN_SPACING);
+                width
+            })
+            .collect()
+    }
+}
+
+pub struct TableHeaderItem<'a> {
+    id: ColumnId,
+    text: &'a str,
+    /// 0 to take the space the other columns leave
+    width: u16,
+}
+
+/// A row, with a cell for every column of the header
+pub struct TableItem<'a> {
+    pub id: u64,
+    pub format: Vec<Spans<'a>>,
+}
+
+/// Rows under a header that stays put while they scroll. The rows can be styled, to
+/// highlight what a filter matched.
+pub struct DataTable<'a> {
+    block: Option<Block<'a>>,
+    header: TableHeader<'a>,
+    items: Vec<TableItem<'a>>,
+    sort: Option<Sort>,
+    selected: Option<usize>,
+    marked: HashSet<u64>,
+    marked_style: Style,
+    header_style: Style,
+}
+
+impl<'a> DataTable<'a> {
+    pub fn new(header: TableHeader<'a>, items: Vec<TableItem<'a>>) -> Self {
+        Self {
+            block: None,
+            header,
+            items,
+            sort: None,
+            selected: None,
+            marked: HashSet::new(),
+            marked_style: Style::default(),
+            header_style: Style::default().add_modifier(Modifier::BOLD),
+        }
+    }
+
+    pub fn block(mut self, block: Block<'a>) -> Self {
+        self.block = Some(block);
+        self
+    }
+
+    pub fn header_style(mut self, style: Style) -> Self {
+        self.header_style = style;
+        self
+    }
+
+    /// Shows which column the rows are sorted by
+    pub fn sort(mut self, sort: Option<Sort>) -> Self {
+        self.sort = sort;
+        self
+    }
+
+    pub fn select(mut self, selected: Option<usize>) -> Self {
+        self.selected = selected;
+        self
+    }
+
+    /// Rows picked for a batch action, by id
+    pub fn marked(mut self, marked: HashSet<u64>, style: Style) -> Self {
+        self.marked = marked;
+        self.marked_style = style;
+        self
+    }
+}
+
+impl Widget for DataTable<'_> {
+    fn render(mut self, area: Rect, buf: &mut Buffer) {
+        let area = match self.block.take() {
+            Some(block) => {
+                let inner = block.inner(area);
+                block.render(area, buf);
+                inner
+            }
+            None => area,
+        };
+        if area.height == 0 {
+            return;
+        }
+
+        let symbol_width = HIGHLIGHT_SYMBOL.len() as u16;
+        let widths = self.header.widths(area.width.saturating_sub(symbol_width));
+
+        // header
+        let mut x = area.x + symbol_width;
+        for (item, &width) in self.header.items.iter().zip(&widths) {
+            if x >= area.right() {
+                break;
+            }
+            let width = width.min(area.right() - x);
+            let indicator = match self.sort {
+                Some(sort) if sort.column == item.id && sort.descending => " ▼",
+                Some(sort) if sort.column == item.id => " ▲",
+                _ => "",
+            };
+            // cut the name rather than the indicator when the column is narrow
+            let text: String = item
+                .text
+                .chars()
+                .take((width as usize).saturating_sub(indicator.chars().count()))
+                .chain(indicator.chars())
+                .collect();
+            buf.set_stringn(x, area.y, text, width as usize, self.header_style);
+            x += width + COLUMN_SPACING;
+        }
+
+        // rows, scrolled just enough to show the selected one
+        let height = (area.height - 1) as usize;
+        let offset = self
+            .selected
+            .map_or(0, |selected| (selected + 1).saturating_sub(height));
+        for (index, item) in self.items.iter().enumerate().skip(offset).take(height) {
+            let y = area.y + 1 + (index - offset) as u16;
+            let mut x = area.x + symbol_width;
+            for (cell, &width) in item.format.iter().zip(&widths) {
+                if x >= area.right() {
+                    break;
+                }
+                buf.set_spans(x, y, cell, width.min(area.right() - x));
+                x += width + COLUMN_SPACING;
+            }
+
+            if self.selected == Some(index) {
+                let width = area.width as usize;
+                buf.set_stringn(area.x, y, HIGHLIGHT_SYMBOL, width, Style::default());
+                let row = Rect::new(area.x, y, area.width, 1);
+                buf.set_style(row, Style::default().add_modifier(Modifier::BOLD));
+            }
+            if self.marked.contains(&item.id) && area.width >= symbol_width {
+                buf.set_string(area.x + 1, y, MARK_SYMBOL, self.marked_style);
+                let width = area.width.saturating_sub(symbol_width);
+                let row = Rect::new(area.x + symbol_width, y, width, 1);
+                buf.set_style(row, self.marked_style);
+            }
+        }
+    }
+}
+
+/// A cell without any styling
+pub fn plain(text: String) -> Spans<'static> {
+    Spans::from(Span::raw(text))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    fn header() -> TableHeader<'static> {
+        let mut widths = HashMap::new();
+        widths.insert(ColumnId::Score, 5);
+        TableHeader::new(
+            TableId::Anime,
+            &TableConfig {
+                columns: vec![ColumnId::Title, ColumnId::Score],
+                widths,
+            },
+        )
+    }
+
+    fn rows(count: u64) -> Vec<TableItem<'static>> {
+        (0..count)
+            .map(|id| TableItem {
+                id,
+                format: vec![plain(format!("Title {}", id)), plain("8.5".to_string())],
+            })
+            .collect()
+    }
+
+    fn lines(buf: &Buffer) -> Vec<String> {
+        (0..buf.area.height)
+            .map(|y| {
+                (0..buf.area.width)
+                    .map(|x| buf.get(x, y).symbol.as_str())
+                    .collect::<String>()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_widths() {
+        assert_eq!(header().widths(30), vec![24, 5]);
+        assert_eq!(header().widths(8), vec![2, 5]);
+        assert_eq!(header().widths(3), vec![0, 2]);
+    }
+
+    #[test]
+    fn test_render() {
+        let area = Rect::new(0, 0, 20, 3);
+        let mut buf = Buffer::empty(area);
+        let sort = Sort {
+            column: ColumnId::Score,
+            descending: true,
+        };
+        DataTable::new(header(), rows(5))
+            .sort(Some(sort))
+            .select(Some(3))
+            .render(area, &mut buf);
+
+        // the header stays while the rows scroll to the selected one
+        assert_eq!(
+            lines(&buf),
+            vec![
+                "  Title        Sco ▼",
+                "  Title 2      8.5  ",
+                "> Title 3      8.5  ",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_render_marked() {
+        let area = Rect::new(0, 0, 20, 4);
+        let mut buf = Buffer::empty(area);
+        DataTable::new(header(), rows(3))
+            .select(Some(1))
+            .marked([1, 2].iter().copied().collect(), Style::default())
+            .render(area, &mut buf);
+        assert_eq!(
+            lines(&buf)[1..],
+            [
+                "  Title 0      8.5  ",
+                ">+Title 1      8.5  ",
+                " +Title 2      8.5  ",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_render_narrow() {
+        // the table sits next to something else in the buffer, which is left alone
+        let mut buf = Buffer::empty(Rect::new(0, 0, 12, 3));
+        DataTable::new(header(), rows(2))
+            .select(Some(0))
+            .render(Rect::new(2, 0, 6, 3), &mut buf);
+        assert_eq!(
+            lines(&buf),
+            vec!["     Sco    ", "  >  8.5    ", "     8.5    "]
+        );
+    }
+
+    #[test]
+    fn test_render_marked_narrow() {
+        let mut buf = Buffer::empty(Rect::new(0, 0, 4, 3));
+        DataTable::new(header(), rows(2))
+            .select(Some(0))
+            .marked([0, 1].iter().copied().collect(), Style::default())
+            .render(Rect::new(0, 0, 1, 3), &mut buf);
+        assert_eq!(lines(&buf), vec!["    ", ">   ", "    "]);
+    }
+}